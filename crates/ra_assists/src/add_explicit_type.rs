@@ -0,0 +1,105 @@
+use hir::{source_binder, db::HirDatabase};
+use ra_syntax::{AstNode, ast};
+
+use crate::{AssistCtx, Assist};
+
+/// Writes the inferred type of a `let` binding down: `let x = 92;` becomes
+/// `let x: i32 = 92;`.
+pub(crate) fn add_explicit_type(mut ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let let_stmt = ctx.node_at_offset::<ast::LetStmt>()?;
+    if let_stmt.type_ref().is_some() {
+        return None;
+    }
+    let pat = let_stmt.pat()?;
+    // Offered on the pattern only, not on everything in the initializer.
+    if !pat.syntax().range().contains_inclusive(ctx.frange.range.start()) {
+        return None;
+    }
+    let function =
+        source_binder::function_from_child_node(ctx.db, ctx.frange.file_id, let_stmt.syntax())?;
+    let infer = function.infer(ctx.db);
+    let syntax_mapping = function.body_syntax_mapping(ctx.db);
+    let ty = match let_stmt.initializer() {
+        Some(init) => &infer[syntax_mapping.node_expr(init)?],
+        None => &infer[syntax_mapping.node_pat(pat)?],
+    };
+    let ty = ty.to_source(ctx.db, function.module(ctx.db))?;
+
+    ctx.add_action("add explicit type", |edit| {
+        edit.target(pat.syntax().range());
+        edit.insert(pat.syntax().range().end(), format!(": {}", ty));
+    });
+
+    ctx.build()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::helpers::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn test_add_explicit_type() {
+        check_assist(
+            add_explicit_type,
+            "
+fn f() {
+    let <|>x = (92, true);
+}",
+            "
+fn f() {
+    let <|>x: (i32, bool) = (92, true);
+}",
+        );
+    }
+
+    #[test]
+    fn test_add_explicit_type_shortens_paths() {
+        check_assist(
+            add_explicit_type,
+            "
+mod a {
+    pub struct S<T>(T);
+    pub mod b {
+        pub enum E { X }
+    }
+}
+use a::S;
+
+fn f() {
+    let mut <|>s = S(a::b::E::X);
+}",
+            "
+mod a {
+    pub struct S<T>(T);
+    pub mod b {
+        pub enum E { X }
+    }
+}
+use a::S;
+
+fn f() {
+    let mut <|>s: S<a::b::E> = S(a::b::E::X);
+}",
+        );
+    }
+
+    #[test]
+    fn test_add_explicit_type_not_applicable() {
+        check_assist_not_applicable(
+            add_explicit_type,
+            "
+fn f() {
+    let <|>x: u32 = 92;
+}",
+        );
+        check_assist_not_applicable(
+            add_explicit_type,
+            "
+fn f() {
+    let <|>x = unknown();
+}",
+        );
+    }
+}
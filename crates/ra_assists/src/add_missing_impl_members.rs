@@ -0,0 +1,300 @@
+use hir::{ImplItem, Trait, TraitItem, source_binder, db::HirDatabase};
+use ra_syntax::{
+    AstNode, SyntaxNode, SmolStr,
+    ast::{self, NameOwner},
+    SyntaxKind::*,
+};
+use rustc_hash::FxHashMap;
+
+use crate::{AssistCtx, Assist, utils::append_impl_items};
+
+/// Adds stubs for all the items of the implemented trait which are missing
+/// from the impl block. Methods get an `unimplemented!()` body, while the
+/// values of constants and associated types are left as `/* TODO */` holes,
+/// as there is no placeholder which would fit every type and bound.
+pub(crate) fn add_missing_impl_members(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    add_missing_members(ctx, "add missing impl members", false)
+}
+
+/// Like `add_missing_impl_members`, but skips the items which have a default
+/// in the trait.
+pub(crate) fn add_missing_required_impl_members(
+    ctx: AssistCtx<impl HirDatabase>,
+) -> Option<Assist> {
+    add_missing_members(ctx, "add missing required impl members", true)
+}
+
+fn add_missing_members(
+    mut ctx: AssistCtx<impl HirDatabase>,
+    label: &str,
+    skip_defaults: bool,
+) -> Option<Assist> {
+    let impl_node = ctx.node_at_offset::<ast::ImplBlock>()?;
+    let module =
+        source_binder::module_from_child_node(ctx.db, ctx.frange.file_id, impl_node.syntax())?;
+    let impl_block = source_binder::impl_block_from_module(ctx.db, module, impl_node)?;
+    let trait_ = impl_block.target_trait(ctx.db)?;
+
+    let present = impl_block
+        .items()
+        .iter()
+        .filter_map(|item| match item {
+            ImplItem::Method(it) => item_name(&*it.source(ctx.db).1),
+            ImplItem::Const(it) => item_name(&*it.source(ctx.db).1),
+            ImplItem::Type(it) => item_name(&*it.source(ctx.db).1),
+        })
+        .collect::<Vec<_>>();
+    let substs = impl_substs(ctx.db, trait_, impl_node);
+
+    let mut stubs = Vec::new();
+    for item in trait_.items(ctx.db) {
+        let (name, has_default, stub) = match item {
+            TraitItem::Function(it) => {
+                let fn_def = it.source(ctx.db).1;
+                let signature = render_signature(fn_def.syntax(), &substs);
+                let stub = format!("{} {{\n    unimplemented!()\n}}", signature);
+                (item_name(&*fn_def), fn_def.body().is_some(), stub)
+            }
+            TraitItem::Const(it) => {
+                let const_def = it.source(ctx.db).1;
+                let signature = render_signature(const_def.syntax(), &substs);
+                let has_default = const_def.syntax().children().any(|it| it.kind() == EQ);
+                (item_name(&*const_def), has_default, format!("{} = /* TODO */;", signature))
+            }
+            TraitItem::Type(it) => {
+                let type_def = it.source(ctx.db).1;
+                let name = item_name(&*type_def);
+                let stub = format!("type {} = /* TODO */;", name.clone().unwrap_or_default());
+                (name, type_def.type_ref().is_some(), stub)
+            }
+        };
+        match name {
+            Some(name) if !(present.contains(&name) || skip_defaults && has_default) => {
+                stubs.push(stub)
+            }
+            _ => (),
+        }
+    }
+    if stubs.is_empty() {
+        return None;
+    }
+
+    let (range, text, cursor) = append_impl_items(impl_node, &stubs)?;
+
+    ctx.add_action(label, |edit| {
+        edit.target(impl_node.syntax().range());
+        edit.replace(range, text);
+        edit.set_cursor(cursor);
+    });
+
+    ctx.build()
+}
+
+fn item_name(item: &impl NameOwner) -> Option<SmolStr> {
+    item.name().map(|it| it.text().clone())
+}
+
+/// Maps `Self` and the type parameters of the implemented trait to the types
+/// written in `impl Trait<Args> for Type`.
+fn impl_substs(
+    db: &impl HirDatabase,
+    trait_: Trait,
+    impl_block: &ast::ImplBlock,
+) -> FxHashMap<SmolStr, String> {
+    let mut substs = FxHashMap::default();
+    if let Some(self_ty) = impl_block.target_type() {
+        substs.insert(SmolStr::new("Self"), self_ty.syntax().text().to_string());
+    }
+    let trait_path = match impl_block.target_trait().map(|it| it.kind()) {
+        Some(ast::TypeRefKind::PathType(it)) => it.path(),
+        _ => None,
+    };
+    let type_args = trait_path
+        .and_then(|it| it.segment())
+        .and_then(|it| it.type_arg_list())
+        .into_iter()
+        .flat_map(|it| it.type_args())
+        .filter_map(|it| it.type_ref());
+    for (param, arg) in trait_.generic_params(db).params().iter().zip(type_args) {
+        substs.insert(SmolStr::new(param.name().to_string()), arg.syntax().text().to_string());
+    }
+    substs
+}
+
+/// Renders the declaration of a trait item up to its body or default value,
+/// replacing the names from `substs`.
+fn render_signature(node: &SyntaxNode, substs: &FxHashMap<SmolStr, String>) -> String {
+    let mut buf = String::new();
+    let children = node
+        .children()
+        .filter(|child| child.kind() != COMMENT && child.kind() != ATTR)
+        .take_while(|child| child.kind() != BLOCK && child.kind() != EQ && child.kind() != SEMI);
+    for child in children {
+        for leaf in child.descendants().filter(|it| it.first_child().is_none()) {
+            let text = match leaf.leaf_text() {
+                Some(it) => it,
+                None => continue,
+            };
+            match substs.get(text) {
+                Some(subst) if is_unqualified_path(leaf) => buf.push_str(subst),
+                _ => buf.push_str(text),
+            }
+        }
+    }
+    buf.trim().to_string()
+}
+
+/// Whether the identifier is a whole path like `T`, as opposed to a part of
+/// `T::Item` or `foo::T`.
+fn is_unqualified_path(ident: &SyntaxNode) -> bool {
+    let path = ident
+        .parent()
+        .filter(|it| it.kind() == NAME_REF)
+        .and_then(|it| it.parent())
+        .and_then(ast::PathSegment::cast)
+        .map(|it| it.parent_path());
+    match path {
+        Some(path) => {
+            path.qualifier().is_none()
+                && path.syntax().parent().map(|it| it.kind() != PATH).unwrap_or(true)
+        }
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::helpers::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn test_add_missing_impl_members() {
+        check_assist(
+            add_missing_impl_members,
+            "
+trait Foo {
+    type Output;
+    const N: usize;
+    fn foo(&self) -> Self::Output;
+    fn bar(&self) {}
+}
+
+struct S;
+
+impl Foo for S {<|>}",
+            "
+trait Foo {
+    type Output;
+    const N: usize;
+    fn foo(&self) -> Self::Output;
+    fn bar(&self) {}
+}
+
+struct S;
+
+impl Foo for S {
+    <|>type Output = /* TODO */;
+
+    const N: usize = /* TODO */;
+
+    fn foo(&self) -> Self::Output {
+        unimplemented!()
+    }
+
+    fn bar(&self) {
+        unimplemented!()
+    }
+}",
+        );
+    }
+
+    #[test]
+    fn test_add_missing_impl_members_substitutes_generics() {
+        check_assist(
+            add_missing_impl_members,
+            "
+trait Convert<T> {
+    fn convert(&self, other: Self) -> T;
+    fn check(&self) -> bool;
+}
+
+struct S;
+
+impl Convert<u32> for S {
+    fn check(&self) -> bool { true }<|>
+}",
+            "
+trait Convert<T> {
+    fn convert(&self, other: Self) -> T;
+    fn check(&self) -> bool;
+}
+
+struct S;
+
+impl Convert<u32> for S {
+    fn check(&self) -> bool { true }
+
+    <|>fn convert(&self, other: S) -> u32 {
+        unimplemented!()
+    }
+}",
+        );
+    }
+
+    #[test]
+    fn test_add_missing_required_impl_members() {
+        check_assist(
+            add_missing_required_impl_members,
+            "
+trait Foo {
+    fn foo(&self);
+    fn bar(&self) {}
+}
+
+struct S;
+
+impl Foo for S {
+    <|>
+}",
+            "
+trait Foo {
+    fn foo(&self);
+    fn bar(&self) {}
+}
+
+struct S;
+
+impl Foo for S {
+    <|>fn foo(&self) {
+        unimplemented!()
+    }
+}",
+        );
+    }
+
+    #[test]
+    fn test_add_missing_impl_members_not_applicable() {
+        check_assist_not_applicable(
+            add_missing_required_impl_members,
+            "
+trait Foo {
+    fn foo(&self);
+    fn bar(&self) {}
+}
+
+struct S;
+
+impl Foo for S {
+    fn foo(&self) {}<|>
+}",
+        );
+        check_assist_not_applicable(
+            add_missing_impl_members,
+            "
+struct S;
+
+impl S {<|>}",
+        );
+    }
+}
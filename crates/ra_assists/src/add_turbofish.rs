@@ -0,0 +1,111 @@
+use hir::{Ty, source_binder, db::HirDatabase};
+use ra_syntax::{AstNode, TextUnit, ast};
+
+use crate::{AssistCtx, Assist};
+
+/// Adds `::<>` to a call of a generic function whose type parameters can't
+/// be inferred, filled with the ones which can and `_` for the others.
+pub(crate) fn add_turbofish(mut ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let call = ctx.node_at_offset::<ast::CallExpr>()?;
+    let callee = call.expr()?;
+    let segment = match callee.kind() {
+        ast::ExprKind::PathExpr(it) => it.path()?.segment()?,
+        _ => return None,
+    };
+    if segment.type_arg_list().is_some() {
+        return None;
+    }
+    let name_ref = segment.name_ref()?;
+    let function =
+        source_binder::function_from_child_node(ctx.db, ctx.frange.file_id, call.syntax())?;
+    let infer = function.infer(ctx.db);
+    let syntax_mapping = function.body_syntax_mapping(ctx.db);
+    let substs = match &infer[syntax_mapping.node_expr(callee)?] {
+        Ty::FnDef { substs, .. } => substs.clone(),
+        _ => return None,
+    };
+    if !substs.iter().any(|it| *it == Ty::Unknown) {
+        return None;
+    }
+    let module = function.module(ctx.db);
+    let args = substs
+        .iter()
+        .map(|it| it.to_source(ctx.db, module).unwrap_or_else(|| "_".to_string()))
+        .collect::<Vec<_>>();
+
+    ctx.add_action("add turbofish", |edit| {
+        let offset = name_ref.syntax().range().end();
+        edit.target(callee.syntax().range());
+        edit.insert(offset, format!("::<{}>", args.join(", ")));
+        edit.set_cursor(offset + TextUnit::of_str("::<"));
+    });
+
+    ctx.build()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::helpers::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn test_add_turbofish() {
+        check_assist(
+            add_turbofish,
+            "
+fn make<T>() -> T {}
+
+fn f() {
+    let x = make<|>();
+}",
+            "
+fn make<T>() -> T {}
+
+fn f() {
+    let x = make::<<|>_>();
+}",
+        );
+    }
+
+    #[test]
+    fn test_add_turbofish_keeps_inferred_params() {
+        check_assist(
+            add_turbofish,
+            "
+fn convert<T, U>(t: T) -> U {}
+
+fn f() {
+    convert<|>(true);
+}",
+            "
+fn convert<T, U>(t: T) -> U {}
+
+fn f() {
+    convert::<<|>bool, _>(true);
+}",
+        );
+    }
+
+    #[test]
+    fn test_add_turbofish_not_applicable() {
+        check_assist_not_applicable(
+            add_turbofish,
+            "
+fn make<T>() -> T {}
+
+fn f() {
+    let x: u32 = make<|>();
+}",
+        );
+        check_assist_not_applicable(
+            add_turbofish,
+            "
+fn make<T>() -> T {}
+
+fn f() {
+    make::<u32><|>();
+}",
+        );
+    }
+}
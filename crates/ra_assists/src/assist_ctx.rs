@@ -1,13 +1,14 @@
 use hir::db::HirDatabase;
 use ra_text_edit::TextEditBuilder;
-use ra_db::FileRange;
+use ra_db::{FileId, FileRange, SourceRootId};
+use relative_path::RelativePathBuf;
 use ra_syntax::{
     SourceFile, TextRange, AstNode, TextUnit, SyntaxNode,
     algo::{find_leaf_at_offset, find_node_at_offset, find_covering_node, LeafAtOffset},
 };
 use ra_fmt::{leading_indent, reindent};
 
-use crate::{AssistLabel, AssistAction};
+use crate::{AssistLabel, AssistAction, NewFile};
 
 #[derive(Clone, Debug)]
 pub(crate) enum Assist {
@@ -118,6 +119,8 @@ impl<'a, DB: HirDatabase> AssistCtx<'a, DB> {
 #[derive(Default)]
 pub(crate) struct AssistBuilder {
     edit: TextEditBuilder,
+    other_file_edits: Vec<(FileId, TextEditBuilder)>,
+    new_files: Vec<NewFile>,
     cursor_position: Option<TextUnit>,
     target: Option<TextRange>,
 }
@@ -156,9 +159,40 @@ impl AssistBuilder {
         self.target = Some(target)
     }
 
+    pub(crate) fn text_edit_builder(&mut self) -> &mut TextEditBuilder {
+        &mut self.edit
+    }
+
+    /// Returns the builder for the edits to a file other than the current one.
+    pub(crate) fn other_file_edit_builder(&mut self, file_id: FileId) -> &mut TextEditBuilder {
+        let idx = match self.other_file_edits.iter().position(|(it, _)| *it == file_id) {
+            Some(idx) => idx,
+            None => {
+                self.other_file_edits.push((file_id, TextEditBuilder::default()));
+                self.other_file_edits.len() - 1
+            }
+        };
+        &mut self.other_file_edits[idx].1
+    }
+
+    pub(crate) fn create_file(
+        &mut self,
+        source_root: SourceRootId,
+        path: RelativePathBuf,
+        text: impl Into<String>,
+    ) {
+        self.new_files.push(NewFile { source_root, path, text: text.into() })
+    }
+
     fn build(self) -> AssistAction {
         AssistAction {
             edit: self.edit.finish(),
+            other_file_edits: self
+                .other_file_edits
+                .into_iter()
+                .map(|(file_id, edit)| (file_id, edit.finish()))
+                .collect(),
+            new_files: self.new_files,
             cursor_position: self.cursor_position,
             target: self.target,
         }
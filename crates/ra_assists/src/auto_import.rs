@@ -1,9 +1,11 @@
+use itertools::Itertools;
 use hir::db::HirDatabase;
+use ra_text_edit::TextEditBuilder;
 use ra_syntax::{
-    ast, AstNode, SyntaxNode, Direction, TextRange,
+    ast, AstNode, SyntaxNode, Direction, TextRange, SmolStr, SourceFile,
     SyntaxKind::{ PATH, PATH_SEGMENT, COLONCOLON, COMMA }
 };
-use crate::assist_ctx::{AssistCtx, Assist, AssistBuilder};
+use crate::assist_ctx::{AssistCtx, Assist};
 
 fn collect_path_segments(path: &ast::Path) -> Option<Vec<&ast::PathSegment>> {
     let mut v = Vec::new();
@@ -329,7 +331,7 @@ fn walk_use_tree_for_best_action<'a>(
 
 fn best_action_for_target<'b, 'a: 'b>(
     container: &'a SyntaxNode,
-    path: &'a ast::Path,
+    anchor: &'a SyntaxNode,
     target: &'b [&'a ast::PathSegment],
 ) -> ImportAction<'a> {
     let mut storage = Vec::with_capacity(16); // this should be the only allocation
@@ -347,19 +349,19 @@ fn best_action_for_target<'b, 'a: 'b>(
         None => {
             // We have no action we no use item was found in container so we find
             // another item and we use it as anchor.
-            // If there are not items, we choose the target path itself as anchor.
+            // If there are not items, we choose the given anchor.
             let anchor = container
                 .children()
                 .find_map(ast::ModuleItem::cast)
                 .map(AstNode::syntax)
-                .or(Some(path.syntax()));
+                .or(Some(anchor));
 
             return ImportAction::add_new_use(anchor, false);
         }
     }
 }
 
-fn make_assist(action: &ImportAction, target: &[&ast::PathSegment], edit: &mut AssistBuilder) {
+fn make_assist(action: &ImportAction, target: &[&ast::PathSegment], edit: &mut TextEditBuilder) {
     match action {
         ImportAction::AddNewUse { anchor, add_after_anchor } => {
             make_assist_add_new_use(anchor, *add_after_anchor, target, edit)
@@ -393,7 +395,7 @@ fn make_assist_add_new_use(
     anchor: &Option<&SyntaxNode>,
     after: bool,
     target: &[&ast::PathSegment],
-    edit: &mut AssistBuilder,
+    edit: &mut TextEditBuilder,
 ) {
     if let Some(anchor) = anchor {
         let indent = ra_fmt::leading_indent(anchor);
@@ -422,7 +424,7 @@ fn make_assist_add_in_tree_list(
     tree_list: &ast::UseTreeList,
     target: &[&ast::PathSegment],
     add_self: bool,
-    edit: &mut AssistBuilder,
+    edit: &mut TextEditBuilder,
 ) {
     let last = tree_list.use_trees().last();
     if let Some(last) = last {
@@ -451,7 +453,7 @@ fn make_assist_add_nested_import(
     first_segment_to_split: &Option<&ast::PathSegment>,
     target: &[&ast::PathSegment],
     add_self: bool,
-    edit: &mut AssistBuilder,
+    edit: &mut TextEditBuilder,
 ) {
     let use_tree = path.syntax().ancestors().find_map(ast::UseTree::cast);
     if let Some(use_tree) = use_tree {
@@ -476,7 +478,7 @@ fn make_assist_add_nested_import(
             buf.push_str(", ");
         }
         edit.insert(start, buf);
-        edit.insert(end, "}");
+        edit.insert(end, "}".to_string());
     }
 }
 
@@ -496,8 +498,8 @@ pub(crate) fn auto_import(mut ctx: AssistCtx<impl HirDatabase>) -> Option<Assist
     }
 
     ctx.add_action(format!("import {} in the current file", fmt_segments(&segments)), |edit| {
-        let action = best_action_for_target(current_file.syntax(), path, &segments);
-        make_assist(&action, segments.as_slice(), edit);
+        let action = best_action_for_target(current_file.syntax(), path.syntax(), &segments);
+        make_assist(&action, segments.as_slice(), edit.text_edit_builder());
         if let Some(last_segment) = path.segment() {
             // Here we are assuming the assist will provide a  correct use statement
             // so we can delete the path qualifier
@@ -511,6 +513,34 @@ pub(crate) fn auto_import(mut ctx: AssistCtx<impl HirDatabase>) -> Option<Assist
     ctx.build()
 }
 
+/// Adds a `use` of `target` to the module containing `position`, merging it
+/// into an existing `use` item when possible.
+pub fn auto_import_text_edit(
+    position: &SyntaxNode,
+    target: &[SmolStr],
+    edit: &mut TextEditBuilder,
+) {
+    let use_item = SourceFile::parse(&format!("use {};", target.iter().join("::")));
+    let segments = match use_item.syntax().descendants().find_map(ast::Path::cast) {
+        Some(path) => collect_path_segments(path),
+        None => None,
+    };
+    let segments = match segments {
+        Some(it) => it,
+        None => return,
+    };
+    let container = position.ancestors().find_map(|node| {
+        if let Some(module) = ast::Module::cast(node) {
+            return module.item_list().map(AstNode::syntax);
+        }
+        ast::SourceFile::cast(node).map(AstNode::syntax)
+    });
+    if let Some(container) = container {
+        let action = best_action_for_target(container, position, &segments);
+        make_assist(&action, segments.as_slice(), edit);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -728,4 +758,18 @@ impl foo<|> for Foo {
 ",
         );
     }
+
+    #[test]
+    fn test_auto_import_text_edit() {
+        let file = SourceFile::parse("use std::fmt::Debug;\n\nfn main() { HashMap }\n");
+        let position = file.syntax().descendants().filter_map(ast::NameRef::cast).last().unwrap();
+        let target: &[SmolStr] = &["std".into(), "collections".into(), "HashMap".into()];
+        let mut edit = TextEditBuilder::default();
+        auto_import_text_edit(position.syntax(), target, &mut edit);
+        let actual = edit.finish().apply(&file.syntax().text().to_string());
+        assert_eq!(
+            actual,
+            "use std::{ collections::HashMap, fmt::Debug};\n\nfn main() { HashMap }\n"
+        );
+    }
 }
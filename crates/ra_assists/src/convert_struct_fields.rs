@@ -0,0 +1,605 @@
+use hir::{
+    AdtDef, ModuleDef, Path, Resolution, StructField, Ty, VariantDef, source_binder,
+    db::HirDatabase,
+};
+use ra_db::FileId;
+use ra_syntax::{
+    AstNode, SyntaxKind, SyntaxNode, TextRange, WalkEvent,
+    ast::{self, ArgListOwner, NameOwner, TypeParamsOwner},
+    SyntaxKind::*,
+};
+
+use crate::{
+    AssistCtx, Assist,
+    utils::{is_pure, render_with_edits, workspace_files},
+};
+
+/// Converts `struct P(i32, i32);` to `struct P { field0: i32, field1: i32 }`,
+/// updating the constructors, patterns and field accesses in the crate and in
+/// the crates depending on it. Constructors used as functions, like in
+/// `.map(P)`, become closures.
+pub(crate) fn convert_to_named_fields(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    convert_fields(ctx, true)
+}
+
+/// The reverse of `convert_to_named_fields`.
+pub(crate) fn convert_to_tuple_fields(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    convert_fields(ctx, false)
+}
+
+fn convert_fields(mut ctx: AssistCtx<impl HirDatabase>, to_named: bool) -> Option<Assist> {
+    let (def, variant, is_struct) = match ctx.node_at_offset::<ast::EnumVariant>() {
+        Some(enum_variant) => {
+            let module = source_binder::module_from_child_node(
+                ctx.db,
+                ctx.frange.file_id,
+                enum_variant.syntax(),
+            )?;
+            let enum_ = source_binder::enum_from_module(ctx.db, module, enum_variant.parent_enum());
+            let name = enum_variant.name()?.text().to_string();
+            let variant = enum_
+                .variants(ctx.db)
+                .into_iter()
+                .find(|it| it.name(ctx.db).map(|it| it.to_string()) == Some(name.clone()))?;
+            (enum_variant.syntax(), VariantDef::from(variant), false)
+        }
+        None => {
+            let strukt = ctx.node_at_offset::<ast::StructDef>()?;
+            // The where clause goes to different places in tuple and named
+            // structs, moving it is not supported.
+            if strukt.where_clause().is_some() {
+                return None;
+            }
+            let module =
+                source_binder::module_from_child_node(ctx.db, ctx.frange.file_id, strukt.syntax())?;
+            let def = source_binder::struct_from_module(ctx.db, module, strukt);
+            (strukt.syntax(), VariantDef::from(def), true)
+        }
+    };
+    let def_edits = if to_named {
+        let field_list = def.children().find_map(ast::PosFieldDefList::cast)?;
+        tuple_to_named_def(def, field_list, is_struct)?
+    } else {
+        let field_list = def.children().find_map(ast::NamedFieldDefList::cast)?;
+        named_to_tuple_def(field_list, is_struct)?
+    };
+
+    let fields = variant.fields(ctx.db);
+    let old_names = fields.iter().map(|it| it.name(ctx.db).to_string()).collect::<Vec<_>>();
+    let new_names = (0..fields.len())
+        .map(|idx| if to_named { format!("field{}", idx) } else { idx.to_string() })
+        .collect();
+    let conversion = Conversion { db: ctx.db, variant, fields, old_names, new_names, to_named };
+
+    let label = if to_named { "convert to named fields" } else { "convert to tuple fields" };
+    let current_file = ctx.frange.file_id;
+    ctx.add_action(label, |edit| {
+        edit.target(def.range());
+        for file_id in workspace_files(conversion.db, current_file) {
+            let file = conversion.db.parse(file_id);
+            let mut edits = conversion.file_edits(file_id, &file);
+            if file_id == current_file {
+                edits.extend(def_edits.iter().cloned());
+            }
+            for (range, replace_with) in edits {
+                if file_id == current_file {
+                    edit.replace(range, replace_with);
+                } else {
+                    edit.other_file_edit_builder(file_id).replace(range, replace_with);
+                }
+            }
+        }
+    });
+
+    ctx.build()
+}
+
+/// `(i32, pub i32);` becomes `{ field0: i32, pub field1: i32 }`, the layout
+/// of the list is kept.
+fn tuple_to_named_def(
+    def: &SyntaxNode,
+    field_list: &ast::PosFieldDefList,
+    is_struct: bool,
+) -> Option<Vec<(TextRange, String)>> {
+    let mut edits = Vec::new();
+    let (l_paren, r_paren) = delimiters(field_list.syntax(), L_PAREN, R_PAREN)?;
+    let l_curly = if is_followed_by_space(l_paren) { "{" } else { "{ " };
+    let r_curly = if is_preceded_by_space(r_paren) { "}" } else { " }" };
+    let needs_space_before =
+        field_list.syntax().prev_sibling().map(|it| it.kind()) != Some(WHITESPACE);
+    edits.push((
+        l_paren.range(),
+        format!("{}{}", if needs_space_before { " " } else { "" }, l_curly),
+    ));
+    let mut fields = 0;
+    for (idx, field) in field_list.fields().enumerate() {
+        let type_ref = field.type_ref()?;
+        edits.push((
+            TextRange::offset_len(type_ref.syntax().range().start(), 0.into()),
+            format!("field{}: ", idx),
+        ));
+        fields += 1;
+    }
+    if fields == 0 {
+        return None;
+    }
+    edits.push((r_paren.range(), r_curly.to_string()));
+    if is_struct {
+        let semi = def.children().find(|it| it.kind() == SEMI)?;
+        edits.push((semi.range(), String::new()));
+    }
+    Some(edits)
+}
+
+/// `{ a: i32, pub b: i32 }` becomes `(i32, pub i32);`, the layout of the list
+/// is kept.
+fn named_to_tuple_def(
+    field_list: &ast::NamedFieldDefList,
+    is_struct: bool,
+) -> Option<Vec<(TextRange, String)>> {
+    let mut edits = Vec::new();
+    let (l_curly, r_curly) = delimiters(field_list.syntax(), L_CURLY, R_CURLY)?;
+    if let Some(ws) = field_list.syntax().prev_sibling().filter(|it| it.kind() == WHITESPACE) {
+        edits.push((ws.range(), String::new()));
+    }
+    edits.push((l_curly.range(), "(".to_string()));
+    if is_followed_by_space(l_curly) {
+        let ws = l_curly.next_sibling()?;
+        if !ws.leaf_text()?.contains('\n') {
+            edits.push((ws.range(), String::new()));
+        }
+    }
+    let mut fields = 0;
+    for field in field_list.fields() {
+        let name = field.name()?;
+        let type_ref = field.type_ref()?;
+        edits.push((
+            TextRange::from_to(name.syntax().range().start(), type_ref.syntax().range().start()),
+            String::new(),
+        ));
+        fields += 1;
+    }
+    if fields == 0 {
+        return None;
+    }
+    if is_preceded_by_space(r_curly) {
+        let ws = r_curly.prev_sibling()?;
+        if !ws.leaf_text()?.contains('\n') {
+            edits.push((ws.range(), String::new()));
+        }
+    }
+    edits.push((r_curly.range(), if is_struct { ");" } else { ")" }.to_string()));
+    Some(edits)
+}
+
+fn delimiters(
+    list: &SyntaxNode,
+    open: SyntaxKind,
+    close: SyntaxKind,
+) -> Option<(&SyntaxNode, &SyntaxNode)> {
+    let first = list.first_child().filter(|it| it.kind() == open)?;
+    let last = list.last_child().filter(|it| it.kind() == close)?;
+    Some((first, last))
+}
+
+fn is_followed_by_space(node: &SyntaxNode) -> bool {
+    node.next_sibling().map(|it| it.kind()) == Some(WHITESPACE)
+}
+
+fn is_preceded_by_space(node: &SyntaxNode) -> bool {
+    node.prev_sibling().map(|it| it.kind()) == Some(WHITESPACE)
+}
+
+struct Conversion<'a, DB> {
+    db: &'a DB,
+    variant: VariantDef,
+    fields: Vec<StructField>,
+    old_names: Vec<String>,
+    new_names: Vec<String>,
+    to_named: bool,
+}
+
+impl<'a, DB: HirDatabase> Conversion<'a, DB> {
+    /// Rewrites the uses of the struct in the file. Nodes are visited bottom
+    /// up, so that the rewrite of a node can include the rewritten children,
+    /// whose edits it then replaces.
+    fn file_edits(&self, file_id: FileId, file: &ast::SourceFile) -> Vec<(TextRange, String)> {
+        let mut edits: Vec<(TextRange, String)> = Vec::new();
+        for event in file.syntax().preorder() {
+            let node = match event {
+                WalkEvent::Leave(it) => it,
+                WalkEvent::Enter(_) => continue,
+            };
+            if let Some((range, replace_with)) = self.rewrite(file_id, node, &edits) {
+                edits.retain(|(it, _)| !it.is_subrange(&range));
+                edits.push((range, replace_with));
+            }
+        }
+        edits
+    }
+
+    fn rewrite(
+        &self,
+        file_id: FileId,
+        node: &SyntaxNode,
+        edits: &[(TextRange, String)],
+    ) -> Option<(TextRange, String)> {
+        if let Some(field_expr) = ast::FieldExpr::cast(node) {
+            return self.rewrite_field_expr(file_id, field_expr);
+        }
+        let text_of = |node: &SyntaxNode| render_with_edits(node, edits);
+        let replace_with = if self.to_named {
+            if let Some(call) = ast::CallExpr::cast(node) {
+                let path = match call.expr()?.kind() {
+                    ast::ExprKind::PathExpr(it) => it.path()?,
+                    _ => return None,
+                };
+                if !self.resolves_to_variant(file_id, path) {
+                    return None;
+                }
+                let args = call.arg_list()?.args().map(|it| text_of(it.syntax()));
+                self.named_list(&text_of(path.syntax()), args.map(Some).collect(), false)
+            } else if let Some(pat) = ast::TupleStructPat::cast(node) {
+                let path = pat.path()?;
+                if !self.resolves_to_variant(file_id, path) {
+                    return None;
+                }
+                let (args, has_rest) = tuple_pat_args(pat, self.fields.len(), &text_of);
+                self.named_list(&text_of(path.syntax()), args, has_rest)
+            } else if let Some(path_expr) = ast::PathExpr::cast(node) {
+                // The constructor used as a function value, like in `.map(P)`,
+                // becomes a closure. Calls are rewritten as a whole instead.
+                let is_callee = node
+                    .parent()
+                    .and_then(ast::CallExpr::cast)
+                    .and_then(|call| call.expr())
+                    .map_or(false, |callee| callee.syntax() == node);
+                let path = path_expr.path()?;
+                if is_callee || !self.resolves_to_variant(file_id, path) {
+                    return None;
+                }
+                let params = self.new_names.join(", ");
+                format!("|{}| {} {{ {} }}", params, text_of(path.syntax()), params)
+            } else {
+                return None;
+            }
+        } else if let Some(lit) = ast::StructLit::cast(node) {
+            let path = lit.path()?;
+            if !self.resolves_to_variant(file_id, path) {
+                return None;
+            }
+            // The fields by index, and in the order of the literal.
+            let mut args = vec![None; self.fields.len()];
+            let mut ordered_args = Vec::new();
+            let mut is_in_order = true;
+            let mut all_pure = true;
+            for field in lit.named_field_list()?.fields() {
+                let name_ref = field.name_ref()?;
+                let idx = self.field_idx(name_ref.text())?;
+                let arg = match field.expr() {
+                    Some(expr) => {
+                        all_pure &= is_pure(expr);
+                        text_of(expr.syntax())
+                    }
+                    None => name_ref.text().to_string(),
+                };
+                is_in_order &= ordered_args.last().map_or(true, |&(last, _)| last < idx);
+                ordered_args.push((idx, arg.clone()));
+                args[idx] = Some(arg);
+            }
+            let path = text_of(path.syntax());
+            match lit.spread() {
+                // Reordering the fields would reorder their side effects.
+                None if args.iter().all(Option::is_some) && (is_in_order || all_pure) => {
+                    let args = args.into_iter().map(Option::unwrap_or_default).collect::<Vec<_>>();
+                    format!("{}({})", path, args.join(", "))
+                }
+                // `P { 0: x, ..p }` works for tuple structs too.
+                spread => {
+                    let mut fields = ordered_args
+                        .into_iter()
+                        .map(|(idx, arg)| format!("{}: {}", idx, arg))
+                        .collect::<Vec<_>>();
+                    if let Some(spread) = spread {
+                        fields.push(format!("..{}", text_of(spread.syntax())));
+                    }
+                    format!("{} {{ {} }}", path, fields.join(", "))
+                }
+            }
+        } else if let Some(pat) = ast::StructPat::cast(node) {
+            let path = pat.path()?;
+            if !self.resolves_to_variant(file_id, path) {
+                return None;
+            }
+            let field_pats = pat.field_pat_list()?;
+            let mut args = vec![None; self.fields.len()];
+            for field in field_pats.field_pats() {
+                let idx = self.field_idx(field.name()?.text())?;
+                args[idx] = Some(text_of(field.pat()?.syntax()));
+            }
+            for bind_pat in field_pats.bind_pats() {
+                let idx = self.field_idx(bind_pat.name()?.text())?;
+                args[idx] = Some(text_of(bind_pat.syntax()));
+            }
+            let has_rest = field_pats.syntax().children().any(|it| it.kind() == DOTDOT);
+            let last_present = args.iter().rposition(Option::is_some).map_or(0, |it| it + 1);
+            let mut pats = args[..last_present]
+                .iter()
+                .map(|it| it.clone().unwrap_or_else(|| "_".to_string()))
+                .collect::<Vec<_>>();
+            if has_rest && last_present < args.len() {
+                pats.push("..".to_string());
+            }
+            format!("{}({})", text_of(path.syntax()), pats.join(", "))
+        } else {
+            return None;
+        };
+        Some((node.range(), replace_with))
+    }
+
+    /// `x.0` becomes `x.field0`, and the other way around.
+    fn rewrite_field_expr(
+        &self,
+        file_id: FileId,
+        field_expr: &ast::FieldExpr,
+    ) -> Option<(TextRange, String)> {
+        let field_name = field_expr.syntax().last_child()?;
+        let idx = self.field_idx(&field_name.text().to_string())?;
+        let function =
+            source_binder::function_from_child_node(self.db, file_id, field_expr.syntax())?;
+        let expr = ast::Expr::cast(field_expr.syntax())?;
+        let expr_id = function.body_syntax_mapping(self.db).node_expr(expr)?;
+        if function.infer(self.db).field_resolution(expr_id)? != self.fields[idx] {
+            return None;
+        }
+        Some((field_name.range(), self.new_names[idx].clone()))
+    }
+
+    /// Renders `P { field0: a, field1: b, .. }` with the fields which are
+    /// present.
+    fn named_list(&self, path: &str, args: Vec<Option<String>>, has_rest: bool) -> String {
+        let mut fields = args
+            .into_iter()
+            .zip(self.new_names.iter())
+            .filter_map(|(arg, name)| Some(format!("{}: {}", name, arg?)))
+            .collect::<Vec<_>>();
+        if has_rest {
+            fields.push("..".to_string());
+        }
+        if fields.is_empty() {
+            format!("{} {{}}", path)
+        } else {
+            format!("{} {{ {} }}", path, fields.join(", "))
+        }
+    }
+
+    fn field_idx(&self, name: &str) -> Option<usize> {
+        self.old_names.iter().position(|it| it == name)
+    }
+
+    /// Whether `path` names the struct or the variant. The path is resolved
+    /// rather than compared by name, so that `Self` and renamed imports are
+    /// found too.
+    fn resolves_to_variant(&self, file_id: FileId, path: &ast::Path) -> bool {
+        let hir_path = match Path::from_ast(path) {
+            Some(it) => it,
+            None => return false,
+        };
+        let resolver = source_binder::resolver_for_node(self.db, file_id, path.syntax());
+        let resolution = resolver.resolve_path(self.db, &hir_path);
+        // Structs and enum variants are in the type namespace, and the tuple
+        // and unit ones in the value namespace too.
+        let variant = match resolution.take_types() {
+            Some(Resolution::Def(ModuleDef::Struct(it))) => VariantDef::from(it),
+            Some(Resolution::Def(ModuleDef::EnumVariant(it))) => VariantDef::from(it),
+            Some(Resolution::SelfType(impl_block)) => match impl_block.target_ty(self.db) {
+                Ty::Adt { def_id: AdtDef::Struct(it), .. } => VariantDef::from(it),
+                _ => return false,
+            },
+            _ => return false,
+        };
+        variant == self.variant
+    }
+}
+
+/// The patterns of `P(a, .., b)` by field index, and whether there is a `..`.
+fn tuple_pat_args(
+    pat: &ast::TupleStructPat,
+    n_fields: usize,
+    text_of: &impl Fn(&SyntaxNode) -> String,
+) -> (Vec<Option<String>>, bool) {
+    let mut args = vec![None; n_fields];
+    let mut before_rest = Vec::new();
+    let mut after_rest = Vec::new();
+    let mut has_rest = false;
+    for child in pat.syntax().children() {
+        if child.kind() == DOTDOT {
+            has_rest = true;
+        } else if ast::Pat::cast(child).is_some() {
+            let pats = if has_rest { &mut after_rest } else { &mut before_rest };
+            pats.push(text_of(child));
+        }
+    }
+    let after_start = n_fields.saturating_sub(after_rest.len());
+    let indices = (0..before_rest.len()).chain(after_start..n_fields);
+    for (idx, arg) in indices.zip(before_rest.into_iter().chain(after_rest)) {
+        if idx < n_fields {
+            args[idx] = Some(arg);
+        }
+    }
+    (args, has_rest)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::helpers::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn test_convert_to_named_fields() {
+        check_assist(
+            convert_to_named_fields,
+            "
+struct <|>P(i32, i32);
+
+fn f(p: P) -> i32 {
+    let P(a, ..) = P(1, 2);
+    p.0 + a
+}",
+            "
+struct <|>P { field0: i32, field1: i32 }
+
+fn f(p: P) -> i32 {
+    let P { field0: a, .. } = P { field0: 1, field1: 2 };
+    p.field0 + a
+}",
+        );
+    }
+
+    #[test]
+    fn test_convert_to_tuple_fields() {
+        check_assist(
+            convert_to_tuple_fields,
+            "
+struct <|>P { x: i32, y: i32 }
+
+fn f(p: P) -> i32 {
+    let P { y, .. } = P { y: 2, x: 1 };
+    p.x + y
+}",
+            "
+struct <|>P(i32, i32);
+
+fn f(p: P) -> i32 {
+    let P(_, y) = P(1, 2);
+    p.0 + y
+}",
+        );
+    }
+
+    #[test]
+    fn test_convert_to_tuple_fields_keeps_the_order_of_side_effects() {
+        check_assist(
+            convert_to_tuple_fields,
+            "
+struct <|>P { x: i32, y: i32 }
+
+fn f(a: &P) -> P {
+    P { y: a.x, x: (a.y) };
+    P { y: g(), x: h() }
+}",
+            "
+struct <|>P(i32, i32);
+
+fn f(a: &P) -> P {
+    P((a.1), a.0);
+    P { 1: g(), 0: h() }
+}",
+        );
+    }
+
+    #[test]
+    fn test_convert_to_named_fields_through_self_and_renames() {
+        check_assist(
+            convert_to_named_fields,
+            "
+mod a {
+    pub struct <|>P(pub i32);
+
+    impl P {
+        fn new() -> P {
+            Self(0)
+        }
+    }
+}
+use self::a::P as Q;
+
+fn f(Q(x): Q) -> Q {
+    Q(x)
+}",
+            "
+mod a {
+    pub struct <|>P { pub field0: i32 }
+
+    impl P {
+        fn new() -> P {
+            Self { field0: 0 }
+        }
+    }
+}
+use self::a::P as Q;
+
+fn f(Q { field0: x }: Q) -> Q {
+    Q { field0: x }
+}",
+        );
+    }
+
+    #[test]
+    fn test_convert_enum_variant_to_named_fields() {
+        check_assist(
+            convert_to_named_fields,
+            "
+enum E {
+    V<|>(u32),
+}
+
+fn f(e: E) {
+    match e {
+        E::V(x) => (),
+    }
+    E::V(1);
+}",
+            "
+enum E {
+    V<|> { field0: u32 },
+}
+
+fn f(e: E) {
+    match e {
+        E::V { field0: x } => (),
+    }
+    E::V { field0: 1 };
+}",
+        );
+    }
+
+    #[test]
+    fn test_convert_to_named_fields_constructor_as_function() {
+        check_assist(
+            convert_to_named_fields,
+            "
+struct <|>P(i32, i32);
+fn f(xs: Vec<(i32, i32)>) -> Vec<P> {
+    let make: fn(i32, i32) -> P = P;
+    xs.into_iter().map(|(a, b)| make(a, b)).chain(Some(1).map(|it| P(it, 0))).collect()
+}",
+            "
+struct <|>P { field0: i32, field1: i32 }
+fn f(xs: Vec<(i32, i32)>) -> Vec<P> {
+    let make: fn(i32, i32) -> P = |field0, field1| P { field0, field1 };
+    xs.into_iter().map(|(a, b)| make(a, b)).chain(Some(1).map(|it| P { field0: it, field1: 0 })).collect()
+}",
+        );
+        check_assist(
+            convert_to_named_fields,
+            "
+struct <|>P(i32);
+fn f(xs: Vec<i32>) -> Vec<P> {
+    xs.into_iter().map(P).collect()
+}",
+            "
+struct <|>P { field0: i32 }
+fn f(xs: Vec<i32>) -> Vec<P> {
+    xs.into_iter().map(|field0| P { field0 }).collect()
+}",
+        );
+    }
+
+    #[test]
+    fn test_convert_to_tuple_fields_not_applicable() {
+        check_assist_not_applicable(convert_to_tuple_fields, "struct <|>P(i32);");
+        check_assist_not_applicable(convert_to_named_fields, "struct <|>P;");
+    }
+}
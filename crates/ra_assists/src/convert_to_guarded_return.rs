@@ -0,0 +1,148 @@
+use hir::db::HirDatabase;
+use ra_syntax::{AstNode, Direction, ast, SyntaxKind::*};
+use ra_fmt::{leading_indent, reindent};
+
+use crate::{
+    AssistCtx, Assist, non_trivia_sibling,
+    utils::{dedented_body, negate},
+};
+
+/// Turns an `if` wrapping the rest of a function or of a loop body into an
+/// early `return` or `continue`, which saves a level of nesting.
+pub(crate) fn convert_to_guarded_return(mut ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let if_expr = ctx.node_at_offset::<ast::IfExpr>()?;
+    let then_block = if_expr.then_branch()?;
+    if ctx.frange.range.start() > then_block.syntax().range().start() {
+        return None;
+    }
+    if if_expr.else_branch().is_some() {
+        return None;
+    }
+    let cond = if_expr.condition()?;
+    if cond.pat().is_some() {
+        return None;
+    }
+    let expr = cond.expr()?;
+
+    // The `if` has to be the last thing in its block...
+    let parent = if_expr.syntax().parent()?;
+    let (stmt, block) = match parent.kind() {
+        EXPR_STMT => (parent, parent.parent()?),
+        BLOCK => (if_expr.syntax(), parent),
+        _ => return None,
+    };
+    if non_trivia_sibling(stmt, Direction::Next).map(|it| it.kind()) != Some(R_CURLY) {
+        return None;
+    }
+    // ...which is the body of a function returning nothing or of a loop.
+    let block_owner = block.parent()?;
+    let exit = match block_owner.kind() {
+        FN_DEF if ast::FnDef::cast(block_owner)?.ret_type().is_none() => "return",
+        LOOP_EXPR | WHILE_EXPR | FOR_EXPR => "continue",
+        _ => return None,
+    };
+
+    ctx.add_action("convert to guarded return", |edit| {
+        let indent = leading_indent(stmt).unwrap_or("");
+        let mut buf = format!("if {} {{\n{}    {};\n{}}}", negate(expr), indent, exit, indent);
+        let body = dedented_body(then_block.syntax());
+        if !body.is_empty() {
+            buf.push('\n');
+            buf.push_str(indent);
+            buf.push_str(&reindent(body.trim_end(), indent));
+        }
+        edit.target(if_expr.syntax().range());
+        edit.replace(stmt.range(), buf);
+        edit.set_cursor(stmt.range().start());
+    });
+
+    ctx.build()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::helpers::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn test_convert_to_guarded_return() {
+        check_assist(
+            convert_to_guarded_return,
+            "
+fn f() {
+    bar();
+    if<|> x.is_valid() {
+        foo();
+        baz();
+    }
+}",
+            "
+fn f() {
+    bar();
+    <|>if !x.is_valid() {
+        return;
+    }
+    foo();
+    baz();
+}",
+        );
+    }
+
+    #[test]
+    fn test_convert_to_guarded_return_in_loop() {
+        check_assist(
+            convert_to_guarded_return,
+            "
+fn f() {
+    for x in xs {
+        <|>if x == 0 {
+            foo(x);
+        }
+    }
+}",
+            "
+fn f() {
+    for x in xs {
+        <|>if x != 0 {
+            continue;
+        }
+        foo(x);
+    }
+}",
+        );
+    }
+
+    #[test]
+    fn test_convert_to_guarded_return_not_applicable() {
+        check_assist_not_applicable(
+            convert_to_guarded_return,
+            "
+fn f() {
+    if<|> x { foo(); }
+    bar();
+}",
+        );
+        check_assist_not_applicable(
+            convert_to_guarded_return,
+            "
+fn f() -> i32 {
+    if<|> x { foo(); }
+}",
+        );
+        check_assist_not_applicable(
+            convert_to_guarded_return,
+            "
+fn f() {
+    if<|> x { foo(); } else { bar(); }
+}",
+        );
+        check_assist_not_applicable(
+            convert_to_guarded_return,
+            "
+fn f() {
+    if let<|> Some(x) = y { foo(); }
+}",
+        );
+    }
+}
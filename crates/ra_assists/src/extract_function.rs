@@ -0,0 +1,1061 @@
+use std::iter;
+
+use hir::{
+    Ty, TypeRef, AdtDef, Module, Mutability, InferenceResult, BodySyntaxMapping,
+    ScopesWithSyntaxMapping, source_binder, db::HirDatabase,
+};
+use ra_syntax::{
+    AstNode, SyntaxNode, SyntaxNodePtr, TextRange, TextUnit,
+    ast::{self, AttrsOwner, NameOwner, SelfParamFlavor},
+    algo::find_covering_node,
+    SyntaxKind::{self, *},
+};
+use ra_fmt::leading_indent;
+
+use crate::{AssistCtx, Assist};
+
+pub(crate) fn extract_function(mut ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let selection = Selection::new(ctx.covering_node(), ctx.frange.range)?;
+    let first = selection.nodes[0];
+    // Methods would need a `self` parameter, which we don't generate yet.
+    if selection.nodes.iter().flat_map(|it| it.descendants()).any(|it| it.kind() == SELF_KW) {
+        return None;
+    }
+    let fn_def = first.ancestors().find_map(ast::FnDef::cast)?;
+    let body = fn_def.body()?;
+    let file = fn_def.syntax().ancestors().find_map(ast::SourceFile::cast)?;
+    let function = source_binder::function_from_child_node(ctx.db, ctx.frange.file_id, first)?;
+
+    let mut flow = ControlFlow::default();
+    for node in selection.nodes.iter() {
+        collect_control_flow(node, false, &mut flow)?;
+    }
+    let exit = if !flow.returns.is_empty() || !flow.loop_exits.is_empty() {
+        // The extracted code leaves the function or the loop around it, so
+        // the new function reports back whether the caller should do the same.
+        if !selection.is_statements || selection.tail.is_some() || flow.has_try {
+            return None;
+        }
+        let exit = flow.exit()?;
+        let scopes = function.scopes(ctx.db);
+        let used_after = locals_used_after(body.syntax(), selection.range, &scopes);
+        if !outputs(&selection, &used_after).is_empty() {
+            return None;
+        }
+        Some(exit)
+    } else {
+        None
+    };
+    let try_ret_ty = if flow.has_try {
+        let fn_ret_ty = match function.ty(ctx.db) {
+            Ty::FnDef { sig, .. } => sig.ret().clone(),
+            _ => return None,
+        };
+        try_wrapper(&fn_ret_ty)?;
+        Some(fn_ret_ty)
+    } else {
+        None
+    };
+
+    let db = ctx.db;
+    ctx.add_action("extract into function", move |edit| {
+        let infer = function.infer(db);
+        let syntax_mapping = function.body_syntax_mapping(db);
+        let scopes = function.scopes(db);
+        let module = function.module(db);
+        let render_ty = |ty: &Ty| render_ty(db, module, ty);
+
+        let used_after = locals_used_after(body.syntax(), selection.range, &scopes);
+        let local_ty = |ptr: SyntaxNodePtr| {
+            ast::Pat::cast(ptr.to_node(file))
+                .and_then(|pat| syntax_mapping.node_pat(pat))
+                .map(|pat| infer[pat].clone())
+                .unwrap_or(Ty::Unknown)
+        };
+
+        let mut params: Vec<Param> = Vec::new();
+        for name_ref in
+            selection.nodes.iter().flat_map(|it| it.descendants()).filter_map(ast::NameRef::cast)
+        {
+            let usage = match local_usage(name_ref) {
+                Some(it) => it,
+                None => continue,
+            };
+            let entry = match scopes.resolve_local_name(name_ref) {
+                Some(it) => it,
+                None => continue,
+            };
+            if entry.ptr().range().is_subrange(&selection.range) {
+                continue;
+            }
+            match params.iter_mut().find(|it| it.ptr == entry.ptr()) {
+                Some(param) => param.usages.push(usage),
+                None => params.push(Param {
+                    name: entry.name().to_string(),
+                    ptr: entry.ptr(),
+                    ty: local_ty(entry.ptr()),
+                    kind: ParamKind::Value,
+                    usages: vec![usage],
+                }),
+            }
+        }
+        for param in params.iter_mut() {
+            let mutated = param.usages.iter().any(|it| is_mutated(db, it, &infer, &syntax_mapping));
+            let read_after = used_after.contains(&param.ptr);
+            param.kind = match (mutated, read_after) {
+                (true, true) => ParamKind::MutRef,
+                (true, false) => ParamKind::MutValue,
+                (false, true) if !is_copy(db, &param.ty) => ParamKind::SharedRef,
+                _ => ParamKind::Value,
+            };
+        }
+
+        let outputs: Vec<(&ast::BindPat, Ty)> = outputs(&selection, &used_after)
+            .into_iter()
+            .map(|pat| (pat, local_ty(SyntaxNodePtr::new(pat.syntax()))))
+            .collect();
+        let outputs_expr = match outputs.len() {
+            0 => None,
+            1 => Some(pat_name(outputs[0].0)),
+            _ => Some(format!("({})", join(outputs.iter().map(|(pat, _)| pat_name(pat))))),
+        };
+        let value_ty = match selection.tail {
+            Some(tail) => syntax_mapping
+                .node_expr(tail)
+                .map(|it| infer[it].clone())
+                .filter(|ty| *ty != Ty::unit()),
+            None => match outputs.len() {
+                0 => None,
+                1 => Some(outputs[0].1.clone()),
+                _ => Some(Ty::Tuple(outputs.iter().map(|(_, ty)| ty.clone()).collect())),
+            },
+        };
+        let outer_ret_ty =
+            fn_def.ret_type().and_then(|it| it.type_ref()).map(|it| it.syntax().text().to_string());
+
+        let mut edits: Vec<(TextRange, String)> = Vec::new();
+        for param in params.iter() {
+            for usage in param.usages.iter() {
+                edits.extend(param.usage_edit(usage));
+            }
+        }
+
+        let fn_name = match impl_anchor(fn_def) {
+            Some(_) => "fun_name",
+            None if is_in_inherent_impl(fn_def) => "Self::fun_name",
+            None => "fun_name",
+        };
+        let args = join(params.iter().map(Param::to_arg));
+        let mut call = format!("{}({})", fn_name, args);
+        let old_indent = leading_indent(first).unwrap_or("");
+
+        let (ret_ty, body_suffix, replacement) = match exit {
+            None => {
+                let mut ret_ty = value_ty.as_ref().map(render_ty);
+                let mut body_suffix = outputs_expr.clone();
+                if let Some(try_ret_ty) = &try_ret_ty {
+                    let wrapper = try_wrapper(try_ret_ty).unwrap();
+                    let ty = wrap_try_ty(try_ret_ty, value_ty.clone().unwrap_or_else(Ty::unit));
+                    match selection.tail {
+                        Some(tail) => {
+                            edits.push((
+                                TextRange::offset_len(tail.syntax().range().start(), 0.into()),
+                                format!("{}(", wrapper),
+                            ));
+                            edits.push((
+                                TextRange::offset_len(tail.syntax().range().end(), 0.into()),
+                                ")".to_string(),
+                            ));
+                        }
+                        None => {
+                            let value = outputs_expr.as_ref().map_or("()", String::as_str);
+                            body_suffix = Some(format!("{}({})", wrapper, value));
+                        }
+                    }
+                    ret_ty = Some(render_ty(&ty));
+                    call.push('?');
+                }
+                let replacement = if !selection.is_statements {
+                    call
+                } else if !outputs.is_empty() {
+                    let pats = outputs.iter().map(|(pat, _)| pat.syntax().text().to_string());
+                    match outputs.len() {
+                        1 => format!("let {} = {};", outputs[0].0.syntax().text(), call),
+                        _ => format!("let ({}) = {};", join(pats), call),
+                    }
+                } else if selection.tail.is_some() {
+                    call
+                } else {
+                    format!("{};", call)
+                };
+                (ret_ty, body_suffix, replacement)
+            }
+            Some(Exit::ReturnValue) => {
+                for expr in flow.returns.iter().filter_map(|it| it.expr()) {
+                    let range = expr.syntax().range();
+                    edits.push((TextRange::offset_len(range.start(), 0.into()), "Some(".into()));
+                    edits.push((TextRange::offset_len(range.end(), 0.into()), ")".into()));
+                }
+                let ret_ty = format!("Option<{}>", outer_ret_ty.unwrap_or_else(|| "()".into()));
+                let replacement = format!(
+                    "if let Some(value) = {} {{\n{indent}    return value;\n{indent}}}",
+                    call,
+                    indent = old_indent
+                );
+                (Some(ret_ty), Some("None".to_string()), replacement)
+            }
+            Some(Exit::Jump(kind)) => {
+                for exit in flow.exits() {
+                    edits.push((exit.range(), "return true".to_string()));
+                }
+                let jump = match kind {
+                    RETURN_EXPR => "return",
+                    BREAK_EXPR => "break",
+                    _ => "continue",
+                };
+                let replacement = format!(
+                    "if {} {{\n{indent}    {};\n{indent}}}",
+                    call,
+                    jump,
+                    indent = old_indent
+                );
+                (Some("bool".to_string()), Some("false".to_string()), replacement)
+            }
+        };
+
+        let selected_text = file.syntax().text().slice(selection.range).to_string();
+        let body_text = apply_edits(&selected_text, selection.range.start(), edits);
+
+        // The type parameters used by the signature have to be declared again,
+        // unless the new function goes into the same inherent impl.
+        let mut used_params = Vec::new();
+        let tys = params
+            .iter()
+            .map(|it| &it.ty)
+            .chain(outputs.iter().map(|(_, ty)| ty))
+            .chain(value_ty.iter())
+            .chain(try_ret_ty.iter());
+        for ty in tys {
+            ty.walk(&mut |ty| {
+                if let Ty::Param { name, .. } = ty {
+                    used_params.push(name.to_string());
+                }
+            });
+        }
+        if let (Some(Exit::ReturnValue), Some(ret_type)) = (&exit, fn_def.ret_type()) {
+            let names = ret_type.syntax().descendants().filter_map(ast::NameRef::cast);
+            used_params.extend(names.map(|it| it.text().to_string()));
+        }
+        let anchor = impl_anchor(fn_def).unwrap_or(fn_def.syntax());
+        let mut generics_owners = vec![fn_def.syntax()];
+        generics_owners.extend(impl_anchor(fn_def));
+        let (generic_params, where_preds) = generics(&generics_owners, used_params);
+
+        let fn_indent = leading_indent(anchor).unwrap_or("");
+        let mut fn_text = format!("\n\n{}fn fun_name", fn_indent);
+        if !generic_params.is_empty() {
+            fn_text.push_str(&format!("<{}>", join(generic_params.into_iter())));
+        }
+        fn_text.push_str(&format!("({})", join(params.iter().map(|it| it.to_param(render_ty)))));
+        if let Some(ret_ty) = ret_ty {
+            fn_text.push_str(&format!(" -> {}", ret_ty));
+        }
+        if where_preds.is_empty() {
+            fn_text.push_str(" {\n");
+        } else {
+            fn_text.push_str(&format!("\n{}where\n", fn_indent));
+            for pred in where_preds {
+                fn_text.push_str(&format!("{}    {},\n", fn_indent, pred));
+            }
+            fn_text.push_str(&format!("{}{{\n", fn_indent));
+        }
+        // The first line starts right at the selection, the others are
+        // indented relative to the code around it.
+        let lines = body_text
+            .lines()
+            .enumerate()
+            .map(|(idx, line)| if idx == 0 { line } else { strip_indent(line, old_indent) })
+            .chain(body_suffix.as_ref().map(String::as_str));
+        for line in lines {
+            if !line.is_empty() {
+                fn_text.push_str(fn_indent);
+                fn_text.push_str("    ");
+                fn_text.push_str(line);
+            }
+            fn_text.push('\n');
+        }
+        fn_text.push_str(fn_indent);
+        fn_text.push('}');
+
+        let cursor = TextUnit::of_str(&replacement[..replacement.find(fn_name).unwrap()]);
+        edit.target(selection.range);
+        edit.replace(selection.range, replacement);
+        edit.insert(anchor.range().end(), fn_text);
+        edit.set_cursor(selection.range.start() + cursor);
+    });
+
+    ctx.build()
+}
+
+/// The code to extract: either whole statements of a block, possibly ending
+/// with its tail expression, or a single expression.
+struct Selection<'a> {
+    range: TextRange,
+    nodes: Vec<&'a SyntaxNode>,
+    is_statements: bool,
+    /// The expression which produces the value of the selected code.
+    tail: Option<&'a ast::Expr>,
+}
+
+impl<'a> Selection<'a> {
+    fn new(covering: &'a SyntaxNode, range: TextRange) -> Option<Selection<'a>> {
+        let range = trim_whitespace(covering, range)?;
+        let node = find_covering_node(covering, range);
+        let block = node.ancestors().find_map(ast::Block::cast)?;
+        let children =
+            block.statements().map(|it| it.syntax()).chain(block.expr().map(|it| it.syntax()));
+        let mut nodes = Vec::new();
+        for child in children {
+            let child_range = child.range();
+            if child_range.is_subrange(&range) {
+                nodes.push(child);
+            } else if !range.is_subrange(&child_range)
+                && child_range.start() < range.end()
+                && range.start() < child_range.end()
+            {
+                // Only a part of the statement is selected.
+                return None;
+            }
+        }
+        if let (Some(first), Some(last)) = (nodes.first(), nodes.last()) {
+            if first.range().start() != range.start() || last.range().end() != range.end() {
+                return None;
+            }
+            let tail = block.expr().filter(|it| it.syntax().range() == last.range());
+            return Some(Selection { range, nodes, is_statements: true, tail });
+        }
+        let expr = node
+            .ancestors()
+            .take_while(|it| it.range() == range)
+            .filter_map(ast::Expr::cast)
+            .last()?;
+        Some(Selection {
+            range,
+            nodes: vec![expr.syntax()],
+            is_statements: false,
+            tail: Some(expr),
+        })
+    }
+}
+
+fn trim_whitespace(node: &SyntaxNode, range: TextRange) -> Option<TextRange> {
+    let text = node.text().slice(range).to_string();
+    let start = range.start() + TextUnit::of_str(&text[..text.len() - text.trim_start().len()]);
+    let end = range.end() - TextUnit::of_str(&text[text.trim_end().len()..]);
+    if start >= end {
+        return None;
+    }
+    Some(TextRange::from_to(start, end))
+}
+
+/// A local defined outside of the selection and used inside of it.
+struct Param<'a> {
+    name: String,
+    ptr: SyntaxNodePtr,
+    ty: Ty,
+    kind: ParamKind,
+    usages: Vec<&'a ast::PathExpr>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ParamKind {
+    Value,
+    MutValue,
+    SharedRef,
+    MutRef,
+}
+
+impl<'a> Param<'a> {
+    fn to_param(&self, render_ty: impl Fn(&Ty) -> String) -> String {
+        let ty = render_ty(&self.ty);
+        match self.kind {
+            ParamKind::Value => format!("{}: {}", self.name, ty),
+            ParamKind::MutValue => format!("mut {}: {}", self.name, ty),
+            ParamKind::SharedRef => format!("{}: &{}", self.name, ty),
+            ParamKind::MutRef => format!("{}: &mut {}", self.name, ty),
+        }
+    }
+
+    fn to_arg(&self) -> String {
+        match self.kind {
+            ParamKind::Value | ParamKind::MutValue => self.name.clone(),
+            ParamKind::SharedRef => format!("&{}", self.name),
+            ParamKind::MutRef => format!("&mut {}", self.name),
+        }
+    }
+
+    /// Once the local is passed by reference, its usages have to go through
+    /// the reference, unless auto-deref does that for us.
+    fn usage_edit(&self, usage: &ast::PathExpr) -> Option<(TextRange, String)> {
+        if self.kind == ParamKind::Value || self.kind == ParamKind::MutValue {
+            return None;
+        }
+        let parent = usage.syntax().parent()?;
+        if let Some(ref_expr) = ast::RefExpr::cast(parent) {
+            if ref_expr.is_mut() || self.kind == ParamKind::SharedRef {
+                return Some((ref_expr.syntax().range(), self.name.clone()));
+            }
+            return None;
+        }
+        let is_receiver = match parent.kind() {
+            METHOD_CALL_EXPR | FIELD_EXPR | INDEX_EXPR => {
+                parent.first_child().map(|it| it.range()) == Some(usage.syntax().range())
+            }
+            _ => false,
+        };
+        if is_receiver {
+            return None;
+        }
+        Some((usage.syntax().range(), format!("*{}", self.name)))
+    }
+}
+
+/// Returns the path expression if the name ref is a plain reference to a
+/// local, like `x` in `x + 1`.
+fn local_usage(name_ref: &ast::NameRef) -> Option<&ast::PathExpr> {
+    let segment = name_ref.syntax().parent().and_then(ast::PathSegment::cast)?;
+    let path = segment.parent_path();
+    if path.qualifier().is_some() {
+        return None;
+    }
+    path.syntax().parent().and_then(ast::PathExpr::cast)
+}
+
+/// The locals which are read once the selected code has run: either after it,
+/// or anywhere in a loop around it.
+fn locals_used_after(
+    body: &SyntaxNode,
+    range: TextRange,
+    scopes: &ScopesWithSyntaxMapping,
+) -> Vec<SyntaxNodePtr> {
+    let enclosing_loop = find_covering_node(body, range)
+        .ancestors()
+        .take_while(|it| it.range() != body.range())
+        .filter(|it| match it.kind() {
+            LOOP_EXPR | WHILE_EXPR | FOR_EXPR => true,
+            _ => false,
+        })
+        .last()
+        .map(|it| it.range());
+    body.descendants()
+        .filter_map(ast::NameRef::cast)
+        .filter(|name_ref| {
+            let name_range = name_ref.syntax().range();
+            if name_range.start() >= range.end() {
+                return true;
+            }
+            match enclosing_loop {
+                Some(loop_range) => {
+                    name_range.is_subrange(&loop_range) && !name_range.is_subrange(&range)
+                }
+                None => false,
+            }
+        })
+        .filter_map(|name_ref| scopes.resolve_local_name(name_ref))
+        .map(|entry| entry.ptr())
+        .collect()
+}
+
+/// The bindings of the selected code which are read once it has run, and so
+/// have to be returned by the new function.
+fn outputs<'a>(selection: &Selection<'a>, used_after: &[SyntaxNodePtr]) -> Vec<&'a ast::BindPat> {
+    selection
+        .nodes
+        .iter()
+        .flat_map(|it| it.descendants())
+        .filter_map(ast::BindPat::cast)
+        .filter(|pat| used_after.contains(&SyntaxNodePtr::new(pat.syntax())))
+        .collect()
+}
+
+/// Checks whether the code assigns to the local, or borrows it mutably.
+fn is_mutated(
+    db: &impl HirDatabase,
+    usage: &ast::PathExpr,
+    infer: &InferenceResult,
+    syntax_mapping: &BodySyntaxMapping,
+) -> bool {
+    // Assigning to `x.field` or to `x[0]` mutates `x` as well.
+    let mut place = usage.syntax();
+    while let Some(parent) = place.parent() {
+        match parent.kind() {
+            FIELD_EXPR | INDEX_EXPR
+                if parent.first_child().map(|it| it.range()) == Some(place.range()) =>
+            {
+                place = parent
+            }
+            _ => break,
+        }
+    }
+    let parent = match place.parent() {
+        Some(it) => it,
+        None => return false,
+    };
+    if let Some(bin_expr) = ast::BinExpr::cast(parent) {
+        return bin_expr.op().map_or(false, |op| op.is_assignment())
+            && bin_expr.lhs().map(|it| it.syntax().range()) == Some(place.range());
+    }
+    if let Some(ref_expr) = ast::RefExpr::cast(parent) {
+        return ref_expr.is_mut();
+    }
+    if let Some(call) = ast::MethodCallExpr::cast(parent) {
+        if call.expr().map(|it| it.syntax().range()) != Some(place.range()) {
+            return false;
+        }
+        let method = ast::Expr::cast(call.syntax())
+            .and_then(|it| syntax_mapping.node_expr(it))
+            .and_then(|it| infer.method_resolution(it));
+        return match method {
+            Some(method) => {
+                let (_, fn_def) = method.source(db);
+                let flavor =
+                    fn_def.param_list().and_then(|it| it.self_param()).map(|it| it.flavor());
+                flavor == Some(SelfParamFlavor::MutRef)
+            }
+            None => false,
+        };
+    }
+    false
+}
+
+/// Whether values of `ty` can still be used after being passed by value: the
+/// primitives, and the structs and enums which derive or implement `Copy`.
+fn is_copy(db: &impl HirDatabase, ty: &Ty) -> bool {
+    match ty {
+        Ty::Bool
+        | Ty::Char
+        | Ty::Int(_)
+        | Ty::Float(_)
+        | Ty::Never
+        | Ty::RawPtr(..)
+        | Ty::FnDef { .. }
+        | Ty::FnPtr(_)
+        | Ty::Ref(_, Mutability::Shared) => true,
+        Ty::Tuple(tys) => tys.iter().all(|it| is_copy(db, it)),
+        Ty::Array(ty) => is_copy(db, ty),
+        // Like a derived impl, we require the type arguments to be `Copy`.
+        Ty::Adt { def_id, substs, .. } => {
+            substs.iter().all(|it| is_copy(db, it)) && implements_copy(db, *def_id, ty)
+        }
+        _ => false,
+    }
+}
+
+fn implements_copy(db: &impl HirDatabase, adt: AdtDef, ty: &Ty) -> bool {
+    let (module, derives_copy) = match adt {
+        AdtDef::Struct(it) => (it.module(db), derives(&*it.source(db).1, "Copy")),
+        AdtDef::Enum(it) => (it.module(db), derives(&*it.source(db).1, "Copy")),
+    };
+    if derives_copy {
+        return true;
+    }
+    // `Copy` can only be implemented in the crate of the type.
+    let krate = match module.krate(db) {
+        Some(it) => it,
+        None => return false,
+    };
+    // The trait is matched by name, as it may not resolve without `core`.
+    db.impls_in_crate(krate).lookup_impl_blocks(db, ty).any(|(_, impl_block)| {
+        match impl_block.target_trait_ref() {
+            Some(TypeRef::Path(path)) => {
+                path.segments.last().map_or(false, |it| it.name.to_string() == "Copy")
+            }
+            _ => false,
+        }
+    })
+}
+
+fn derives(node: &impl AttrsOwner, trait_name: &str) -> bool {
+    node.attrs().filter_map(|it| it.as_call()).filter(|(name, _)| name == "derive").any(
+        |(_, tt)| tt.syntax().children().any(|it| it.kind() == IDENT && it.text() == trait_name),
+    )
+}
+
+#[derive(Default)]
+struct ControlFlow<'a> {
+    /// `return`s leaving the function the code is extracted from.
+    returns: Vec<&'a ast::ReturnExpr>,
+    /// `break`s and `continue`s leaving a loop around the selection.
+    loop_exits: Vec<&'a SyntaxNode>,
+    has_try: bool,
+}
+
+/// How the extracted code leaves the function or the loop around it.
+#[derive(Clone, Copy)]
+enum Exit {
+    /// Every exit is a `return` with a value.
+    ReturnValue,
+    /// Every exit is a `return`, a `break` or a `continue` without a value.
+    Jump(SyntaxKind),
+}
+
+impl<'a> ControlFlow<'a> {
+    fn exits(&self) -> impl Iterator<Item = &'a SyntaxNode> + '_ {
+        self.returns.iter().map(|it| it.syntax()).chain(self.loop_exits.iter().cloned())
+    }
+
+    /// Returns `None` if the caller can't tell the exits apart.
+    fn exit(&self) -> Option<Exit> {
+        if !self.returns.is_empty() && !self.loop_exits.is_empty() {
+            return None;
+        }
+        let returned_values = self.returns.iter().filter(|it| it.expr().is_some()).count();
+        if returned_values > 0 {
+            if returned_values != self.returns.len() {
+                return None;
+            }
+            return Some(Exit::ReturnValue);
+        }
+        let kind = self.exits().next()?.kind();
+        if self.exits().any(|it| it.kind() != kind) {
+            return None;
+        }
+        Some(Exit::Jump(kind))
+    }
+}
+
+fn collect_control_flow<'a>(
+    node: &'a SyntaxNode,
+    in_loop: bool,
+    flow: &mut ControlFlow<'a>,
+) -> Option<()> {
+    match node.kind() {
+        // Control flow inside closures and nested items doesn't escape them.
+        LAMBDA_EXPR | FN_DEF => return Some(()),
+        RETURN_EXPR => flow.returns.push(ast::ReturnExpr::cast(node)?),
+        TRY_EXPR => flow.has_try = true,
+        BREAK_EXPR | CONTINUE_EXPR => {
+            // Neither labels nor `break` values can be forwarded to the caller.
+            if node.children().any(|it| it.kind() == LIFETIME) {
+                return None;
+            }
+            if !in_loop {
+                if ast::BreakExpr::cast(node).and_then(|it| it.expr()).is_some() {
+                    return None;
+                }
+                flow.loop_exits.push(node);
+            }
+        }
+        _ => (),
+    }
+    let in_loop = in_loop
+        || match node.kind() {
+            LOOP_EXPR | WHILE_EXPR | FOR_EXPR => true,
+            _ => false,
+        };
+    for child in node.children() {
+        collect_control_flow(child, in_loop, flow)?;
+    }
+    Some(())
+}
+
+/// The constructor for the success value of the `Result<T, E>` or `Option<T>`
+/// returned by the outer function, which `?` can forward.
+fn try_wrapper(outer_ret_ty: &Ty) -> Option<&'static str> {
+    let (name, substs) = match outer_ret_ty {
+        Ty::Adt { name, substs, .. } => (name, substs),
+        _ => return None,
+    };
+    substs.iter().next()?;
+    match name.to_string().as_str() {
+        "Option" => Some("Some"),
+        "Result" => Some("Ok"),
+        _ => None,
+    }
+}
+
+/// Turns the `Result<T, E>` or `Option<T>` returned by the outer function into
+/// the one returned by the extracted function.
+fn wrap_try_ty(outer_ret_ty: &Ty, value_ty: Ty) -> Ty {
+    let substs = match outer_ret_ty {
+        Ty::Adt { substs, .. } => substs,
+        _ => return outer_ret_ty.clone(),
+    };
+    let substs = iter::once(value_ty).chain(substs.iter().skip(1).cloned()).collect::<Vec<_>>();
+    outer_ret_ty.clone().apply_substs(substs.into())
+}
+
+/// If the function is an item of a trait or a trait impl, the extracted
+/// function goes after the whole trait or impl.
+fn impl_anchor(fn_def: &ast::FnDef) -> Option<&SyntaxNode> {
+    let container = fn_def.syntax().parent().and_then(ast::ItemList::cast)?.syntax().parent()?;
+    if let Some(impl_block) = ast::ImplBlock::cast(container) {
+        impl_block.target_trait()?;
+        return Some(container);
+    }
+    ast::TraitDef::cast(container).map(|it| it.syntax())
+}
+
+fn is_in_inherent_impl(fn_def: &ast::FnDef) -> bool {
+    fn_def
+        .syntax()
+        .parent()
+        .and_then(|it| it.parent())
+        .and_then(ast::ImplBlock::cast)
+        .map_or(false, |it| it.target_trait().is_none())
+}
+
+/// The generic parameters and the where predicates of the extracted function:
+/// the type parameters of `owners` which are `used`, and those which their
+/// bounds refer to, with the lifetimes the bounds need.
+fn generics(owners: &[&SyntaxNode], mut used: Vec<String>) -> (Vec<String>, Vec<String>) {
+    let param_lists = owners
+        .iter()
+        .flat_map(|it| it.children())
+        .filter_map(ast::TypeParamList::cast)
+        .collect::<Vec<_>>();
+    let type_params = param_lists.iter().flat_map(|it| it.type_params()).collect::<Vec<_>>();
+    let where_preds = owners
+        .iter()
+        .flat_map(|it| it.children())
+        .filter(|it| it.kind() == WHERE_CLAUSE)
+        .flat_map(|it| it.children())
+        .filter(|it| it.kind() == WHERE_PRED)
+        .collect::<Vec<_>>();
+    let names = type_params
+        .iter()
+        .filter_map(|it| it.name())
+        .map(|it| it.text().to_string())
+        .collect::<Vec<_>>();
+    let mentioned = |node: &SyntaxNode| {
+        node.descendants()
+            .filter_map(ast::NameRef::cast)
+            .map(|it| it.text().to_string())
+            .filter(|it| names.contains(it))
+            .collect::<Vec<_>>()
+    };
+    used.retain(|it| names.contains(it));
+
+    let mut copied: Vec<&SyntaxNode> = Vec::new();
+    loop {
+        let new_nodes = type_params
+            .iter()
+            .filter(|it| it.name().map_or(false, |name| used.contains(&name.text().to_string())))
+            .map(|it| it.syntax())
+            .chain(
+                where_preds
+                    .iter()
+                    .cloned()
+                    .filter(|it| mentioned(it).iter().any(|name| used.contains(name))),
+            )
+            .filter(|it| !copied.contains(it))
+            .collect::<Vec<_>>();
+        if new_nodes.is_empty() {
+            break;
+        }
+        for node in new_nodes {
+            used.extend(mentioned(node));
+            copied.push(node);
+        }
+    }
+
+    let lifetimes = copied
+        .iter()
+        .flat_map(|it| it.descendants())
+        .filter(|it| it.kind() == LIFETIME)
+        .map(|it| it.text().to_string())
+        .collect::<Vec<_>>();
+    let lifetime_params = param_lists
+        .iter()
+        .flat_map(|it| it.lifetime_params())
+        .filter(|it| {
+            it.syntax()
+                .children()
+                .any(|it| it.kind() == LIFETIME && lifetimes.contains(&it.text().to_string()))
+        })
+        .map(|it| it.syntax().text().to_string());
+    let type_params = type_params
+        .iter()
+        .filter(|it| copied.contains(&it.syntax()))
+        .map(|it| it.syntax().text().to_string());
+    let where_preds = where_preds
+        .iter()
+        .filter(|it| copied.contains(it))
+        .map(|it| it.text().to_string())
+        .collect();
+    (lifetime_params.chain(type_params).collect(), where_preds)
+}
+
+fn pat_name(pat: &ast::BindPat) -> String {
+    pat.name().map(|it| it.text().to_string()).unwrap_or_default()
+}
+
+fn strip_indent<'a>(line: &'a str, indent: &str) -> &'a str {
+    if line.starts_with(indent) {
+        &line[indent.len()..]
+    } else {
+        line
+    }
+}
+
+/// The types which can't be written down are left for the user to fill in.
+fn render_ty(db: &impl HirDatabase, module: Module, ty: &Ty) -> String {
+    ty.to_source(db, module).unwrap_or_else(|| "_".to_string())
+}
+
+fn join(items: impl Iterator<Item = String>) -> String {
+    items.collect::<Vec<_>>().join(", ")
+}
+
+/// Applies the edits to the text of the selection, which starts at `offset`.
+fn apply_edits(text: &str, offset: TextUnit, mut edits: Vec<(TextRange, String)>) -> String {
+    edits.sort_by_key(|(range, _)| (range.start(), range.end()));
+    let mut res = String::new();
+    let mut pos = 0;
+    for (range, replace_with) in edits {
+        let start = (range.start() - offset).to_usize();
+        assert!(start >= pos, "overlapping edits");
+        res.push_str(&text[pos..start]);
+        res.push_str(&replace_with);
+        pos = (range.end() - offset).to_usize();
+    }
+    res.push_str(&text[pos..]);
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::helpers::{check_assist_range, check_assist_range_not_applicable};
+
+    use super::extract_function;
+
+    #[test]
+    fn extract_function_with_param_and_output() {
+        check_assist_range(
+            extract_function,
+            "
+fn foo() {
+    let n: u32 = 1;
+    <|>let m = n + 1;
+    let k = m * 2;<|>
+    let r = k + n;
+}",
+            "
+fn foo() {
+    let n: u32 = 1;
+    let k = <|>fun_name(n);
+    let r = k + n;
+}
+
+fn fun_name(n: u32) -> u32 {
+    let m = n + 1;
+    let k = m * 2;
+    k
+}",
+        );
+    }
+
+    #[test]
+    fn extract_function_expr() {
+        check_assist_range(
+            extract_function,
+            "
+fn foo() {
+    let n = 1;
+    let m = <|>n * 2<|> + 1;
+}",
+            "
+fn foo() {
+    let n = 1;
+    let m = <|>fun_name(n) + 1;
+}
+
+fn fun_name(n: i32) -> i32 {
+    n * 2
+}",
+        );
+    }
+
+    #[test]
+    fn extract_function_passes_mutated_locals_by_mut_ref() {
+        check_assist_range(
+            extract_function,
+            "
+struct Counter { count: u32 }
+
+impl Counter {
+    fn bump(&mut self) { self.count += 1; }
+}
+
+fn foo() {
+    let mut c = Counter { count: 0 };
+    let mut n: u32 = 0;
+    <|>n += 1;
+    c.bump();<|>
+    let r = c.count + n;
+}",
+            "
+struct Counter { count: u32 }
+
+impl Counter {
+    fn bump(&mut self) { self.count += 1; }
+}
+
+fn foo() {
+    let mut c = Counter { count: 0 };
+    let mut n: u32 = 0;
+    <|>fun_name(&mut n, &mut c);
+    let r = c.count + n;
+}
+
+fn fun_name(n: &mut u32, c: &mut Counter) {
+    *n += 1;
+    c.bump();
+}",
+        );
+    }
+
+    #[test]
+    fn extract_function_passes_copy_types_by_value() {
+        check_assist_range(
+            extract_function,
+            "
+#[derive(Clone, Copy)]
+struct Point { x: u32 }
+struct Meters(u32);
+impl Clone for Meters { fn clone(&self) -> Meters { Meters(self.0) } }
+impl Copy for Meters {}
+struct Name { len: u32 }
+
+fn foo() {
+    let p = Point { x: 0 };
+    let m = Meters(1);
+    let n = Name { len: 2 };
+    <|>let s = p.x + m.0 + n.len;<|>
+    let t = (p, m, n);
+}",
+            "
+#[derive(Clone, Copy)]
+struct Point { x: u32 }
+struct Meters(u32);
+impl Clone for Meters { fn clone(&self) -> Meters { Meters(self.0) } }
+impl Copy for Meters {}
+struct Name { len: u32 }
+
+fn foo() {
+    let p = Point { x: 0 };
+    let m = Meters(1);
+    let n = Name { len: 2 };
+    <|>fun_name(p, m, &n);
+    let t = (p, m, n);
+}
+
+fn fun_name(p: Point, m: Meters, n: &Name) {
+    let s = p.x + m.0 + n.len;
+}",
+        );
+    }
+
+    #[test]
+    fn extract_function_copies_used_generic_params() {
+        check_assist_range(
+            extract_function,
+            "
+fn foo<'a, T: Into<U>, U, V: Clone>(t: T, v: V) -> U
+where
+    U: From<&'a str>,
+    V: Default,
+{
+    <|>let u = t.into();<|>
+    u
+}",
+            "
+fn foo<'a, T: Into<U>, U, V: Clone>(t: T, v: V) -> U
+where
+    U: From<&'a str>,
+    V: Default,
+{
+    let u = <|>fun_name(t);
+    u
+}
+
+fn fun_name<'a, T: Into<U>, U>(t: T) -> U
+where
+    U: From<&'a str>,
+{
+    let u = t.into();
+    u
+}",
+        );
+    }
+
+    #[test]
+    fn extract_function_with_return() {
+        check_assist_range(
+            extract_function,
+            "
+fn foo(n: u32) -> u32 {
+    <|>if n > 10 {
+        return 10;
+    }<|>
+    n + 1
+}",
+            "
+fn foo(n: u32) -> u32 {
+    if let Some(value) = <|>fun_name(n) {
+        return value;
+    }
+    n + 1
+}
+
+fn fun_name(n: u32) -> Option<u32> {
+    if n > 10 {
+        return Some(10);
+    }
+    None
+}",
+        );
+    }
+
+    #[test]
+    fn extract_function_with_try() {
+        check_assist_range(
+            extract_function,
+            "
+enum Result<T, E> { Ok(T), Err(E) }
+use Result::*;
+
+fn foo(n: u32) -> Result<u32, ()> {
+    <|>check(n)?;
+    let m = n + 1;<|>
+    Ok(m)
+}",
+            "
+enum Result<T, E> { Ok(T), Err(E) }
+use Result::*;
+
+fn foo(n: u32) -> Result<u32, ()> {
+    let m = <|>fun_name(n)?;
+    Ok(m)
+}
+
+fn fun_name(n: u32) -> Result<u32, ()> {
+    check(n)?;
+    let m = n + 1;
+    Ok(m)
+}",
+        );
+    }
+
+    #[test]
+    fn extract_function_not_applicable_to_partial_statements() {
+        check_assist_range_not_applicable(
+            extract_function,
+            "
+fn foo() {
+    <|>let m = 1;
+    let k<|> = m * 2;
+}",
+        );
+    }
+}
@@ -1,73 +1,57 @@
-use std::fmt::Write;
+use hir::{source_binder, db::HirDatabase};
+use ra_syntax::{AstNode, Direction, TextUnit, ast, SyntaxKind::*};
+use ra_fmt::leading_indent;
 
-use hir::{
-    AdtDef, Ty, FieldSource, source_binder,
-    db::HirDatabase,
-};
-use ra_syntax::ast::{self, AstNode};
-
-use crate::{AssistCtx, Assist};
+use crate::{AssistCtx, Assist, non_trivia_sibling};
 
 pub(crate) fn fill_match_arms(mut ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
     let match_expr = ctx.node_at_offset::<ast::MatchExpr>()?;
-
-    // We already have some match arms, so we don't provide any assists.
-    match match_expr.match_arm_list() {
-        Some(arm_list) if arm_list.arms().count() > 0 => {
-            return None;
-        }
-        _ => {}
-    }
-
     let expr = match_expr.expr()?;
     let function =
         source_binder::function_from_child_node(ctx.db, ctx.frange.file_id, expr.syntax())?;
-    let infer_result = function.infer(ctx.db);
     let syntax_mapping = function.body_syntax_mapping(ctx.db);
-    let node_expr = syntax_mapping.node_expr(expr)?;
-    let match_expr_ty = infer_result[node_expr].clone();
-    let enum_def = match match_expr_ty {
-        Ty::Adt { def_id: AdtDef::Enum(e), .. } => e,
-        _ => return None,
-    };
-    let enum_name = enum_def.name(ctx.db)?;
-    let db = ctx.db;
+    let match_expr_id = syntax_mapping.node_expr(ast::Expr::cast(match_expr.syntax())?)?;
+    let missing = function.missing_match_arms(ctx.db, match_expr_id);
+    if missing.is_empty() {
+        return None;
+    }
+    let resolver = source_binder::resolver_for_node(ctx.db, ctx.frange.file_id, expr.syntax());
+    let pats = missing.iter().map(|it| it.to_source(ctx.db, &resolver)).collect::<Vec<_>>();
+    let last_arm = match_expr.match_arm_list().and_then(|it| it.arms().last());
 
     ctx.add_action("fill match arms", |edit| {
-        let mut buf = format!("match {} {{\n", expr.syntax().text().to_string());
-        let variants = enum_def.variants(db);
-        for variant in variants {
-            let name = match variant.name(db) {
-                Some(it) => it,
-                None => continue,
-            };
-            write!(&mut buf, "    {}::{}", enum_name, name.to_string()).unwrap();
-
-            let pat = variant
-                .fields(db)
-                .into_iter()
-                .map(|field| {
-                    let name = field.name(db).to_string();
-                    let (_, source) = field.source(db);
-                    match source {
-                        FieldSource::Named(_) => name,
-                        FieldSource::Pos(_) => "_".to_string(),
-                    }
-                })
-                .collect::<Vec<_>>();
-
-            match pat.first().map(|s| s.as_str()) {
-                Some("_") => write!(&mut buf, "({})", pat.join(", ")).unwrap(),
-                Some(_) => write!(&mut buf, "{{{}}}", pat.join(", ")).unwrap(),
-                None => (),
-            };
-
-            buf.push_str(" => (),\n");
-        }
-        buf.push_str("}");
         edit.target(match_expr.syntax().range());
-        edit.set_cursor(expr.syntax().range().start());
-        edit.replace_node_and_indent(match_expr.syntax(), buf);
+        let last_arm = match last_arm {
+            Some(it) => it,
+            None => {
+                let mut buf = format!("match {} {{\n", expr.syntax().text().to_string());
+                for pat in pats {
+                    buf.push_str(&format!("    {} => (),\n", pat));
+                }
+                buf.push_str("}");
+                edit.set_cursor(expr.syntax().range().start());
+                edit.replace_node_and_indent(match_expr.syntax(), buf);
+                return;
+            }
+        };
+        // The new arms go after the last one, which might need a comma.
+        let comma =
+            non_trivia_sibling(last_arm.syntax(), Direction::Next).filter(|it| it.kind() == COMMA);
+        let needs_comma =
+            comma.is_none() && last_arm.expr().map(|it| it.syntax().kind()) != Some(BLOCK_EXPR);
+        let offset = comma.unwrap_or(last_arm.syntax()).range().end();
+        let indent = leading_indent(last_arm.syntax()).unwrap_or("");
+        let mut buf = String::new();
+        if needs_comma {
+            buf.push(',');
+        }
+        buf.push('\n');
+        buf.push_str(indent);
+        let cursor = offset + TextUnit::of_str(&buf);
+        let arms = pats.iter().map(|pat| format!("{} => (),", pat)).collect::<Vec<_>>();
+        buf.push_str(&arms.join(&format!("\n{}", indent)));
+        edit.insert(offset, buf);
+        edit.set_cursor(cursor);
     });
 
     ctx.build()
@@ -75,7 +59,7 @@ pub(crate) fn fill_match_arms(mut ctx: AssistCtx<impl HirDatabase>) -> Option<As
 
 #[cfg(test)]
 mod tests {
-    use crate::helpers::{check_assist, check_assist_target};
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
 
     use super::fill_match_arms;
 
@@ -113,7 +97,7 @@ mod tests {
                     A::Bs => (),
                     A::Cs(_) => (),
                     A::Ds(_, _) => (),
-                    A::Es{x, y} => (),
+                    A::Es { x, y } => (),
                 }
             }
             "#,
@@ -157,4 +141,79 @@ mod tests {
             "match E::X {}",
         );
     }
+
+    #[test]
+    fn fill_match_arms_partial() {
+        check_assist(
+            fill_match_arms,
+            r#"
+            enum Option<T> { Some(T), None }
+            use Option::*;
+            enum E { X, Y }
+
+            fn main(e: Option<E>) {
+                match e<|> {
+                    Some(E::X) => {}
+                }
+            }
+            "#,
+            r#"
+            enum Option<T> { Some(T), None }
+            use Option::*;
+            enum E { X, Y }
+
+            fn main(e: Option<E>) {
+                match e {
+                    Some(E::X) => {}
+                    <|>Some(E::Y) => (),
+                    None => (),
+                }
+            }
+            "#,
+        );
+    }
+
+    #[test]
+    fn fill_match_arms_tuple_of_references() {
+        check_assist(
+            fill_match_arms,
+            r#"
+            enum E { X, Y }
+
+            fn main(a: &E, b: &E) {
+                match (a, b)<|> {
+                    (E::X, _) => (),
+                    (_, E::X) => ()
+                }
+            }
+            "#,
+            r#"
+            enum E { X, Y }
+
+            fn main(a: &E, b: &E) {
+                match (a, b) {
+                    (E::X, _) => (),
+                    (_, E::X) => (),
+                    <|>(E::Y, E::Y) => (),
+                }
+            }
+            "#,
+        );
+    }
+
+    #[test]
+    fn fill_match_arms_not_applicable_when_exhaustive() {
+        check_assist_not_applicable(
+            fill_match_arms,
+            r#"
+            enum E { X, Y }
+
+            fn main(e: E) {
+                match e<|> {
+                    E::X | E::Y => (),
+                }
+            }
+            "#,
+        );
+    }
 }
@@ -0,0 +1,171 @@
+use hir::db::HirDatabase;
+use ra_syntax::{
+    AstNode,
+    ast::{self, NameOwner},
+};
+
+use crate::{
+    AssistCtx, Assist,
+    utils::{StructImpls, field_ty, method_insertion, visibility},
+};
+
+pub(crate) fn generate_getter(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    generate_accessor(
+        ctx,
+        "generate getter",
+        |name| name.to_string(),
+        |vis, name, ty| format!("{}fn {}(&self) -> &{} {{\n    &self.{}\n}}", vis, name, ty, name),
+    )
+}
+
+pub(crate) fn generate_getter_mut(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    generate_accessor(
+        ctx,
+        "generate mutable getter",
+        |name| format!("{}_mut", name),
+        |vis, name, ty| {
+            format!(
+                "{}fn {}_mut(&mut self) -> &mut {} {{\n    &mut self.{}\n}}",
+                vis, name, ty, name
+            )
+        },
+    )
+}
+
+pub(crate) fn generate_setter(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    generate_accessor(
+        ctx,
+        "generate setter",
+        |name| format!("set_{}", name),
+        |vis, name, ty| {
+            format!(
+                "{}fn set_{}(&mut self, {}: {}) {{\n    self.{} = {};\n}}",
+                vis, name, name, ty, name, name
+            )
+        },
+    )
+}
+
+/// Adds the method rendered by `render` from the visibility, the name and the
+/// type of the field under the cursor, unless one called `method_name` exists.
+fn generate_accessor(
+    mut ctx: AssistCtx<impl HirDatabase>,
+    label: &str,
+    method_name: impl FnOnce(&str) -> String,
+    render: impl FnOnce(&str, &str, &str) -> String,
+) -> Option<Assist> {
+    let field_def = ctx.node_at_offset::<ast::NamedFieldDef>()?;
+    let strukt = field_def.syntax().ancestors().find_map(ast::StructDef::cast)?;
+    let name = field_def.name()?.text().to_string();
+    let impls = StructImpls::new(ctx.db, ctx.frange.file_id, strukt)?;
+    if impls.has_method(&method_name(&name)) {
+        return None;
+    }
+    let field =
+        impls.def.fields(ctx.db).into_iter().find(|it| it.name(ctx.db).to_string() == name)?;
+    let method =
+        render(&visibility(strukt), &name, &field_ty(ctx.db, impls.def.module(ctx.db), field)?);
+    let impl_block = impls.impl_block.as_ref().map(|it| &**it);
+    let (range, text, cursor) = method_insertion(strukt, impl_block, &method)?;
+
+    ctx.add_action(label, |edit| {
+        edit.target(strukt.syntax().range());
+        edit.replace(range, text);
+        edit.set_cursor(cursor);
+    });
+
+    ctx.build()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::helpers::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn test_generate_getter() {
+        check_assist(
+            generate_getter,
+            "
+struct Person {
+    name<|>: String,
+}",
+            "
+struct Person {
+    name: String,
+}
+
+impl Person {
+    <|>fn name(&self) -> &String {
+        &self.name
+    }
+}",
+        );
+    }
+
+    #[test]
+    fn test_generate_getter_mut() {
+        check_assist(
+            generate_getter_mut,
+            "
+pub struct Person {
+    age<|>: u32,
+}
+
+impl Person {}",
+            "
+pub struct Person {
+    age: u32,
+}
+
+impl Person {
+    <|>pub fn age_mut(&mut self) -> &mut u32 {
+        &mut self.age
+    }
+}",
+        );
+    }
+
+    #[test]
+    fn test_generate_setter() {
+        check_assist(
+            generate_setter,
+            "
+struct Person {
+    age<|>: u32,
+}
+
+impl Person {
+    fn age(&self) -> u32 { self.age }
+}",
+            "
+struct Person {
+    age: u32,
+}
+
+impl Person {
+    fn age(&self) -> u32 { self.age }
+
+    <|>fn set_age(&mut self, age: u32) {
+        self.age = age;
+    }
+}",
+        );
+    }
+
+    #[test]
+    fn test_generate_getter_not_applicable_if_exists() {
+        check_assist_not_applicable(
+            generate_getter,
+            "
+struct Person {
+    age<|>: u32,
+}
+
+impl Person {
+    fn age(&self) -> u32 { self.age }
+}",
+        );
+    }
+}
@@ -0,0 +1,169 @@
+use join_to_string::join;
+use hir::db::HirDatabase;
+use ra_syntax::{AstNode, ast};
+
+use crate::{
+    AssistCtx, Assist,
+    utils::{StructImpls, field_ty, method_insertion, visibility},
+};
+
+pub(crate) fn generate_new(mut ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let strukt = ctx.node_at_offset::<ast::StructDef>()?;
+    let impls = StructImpls::new(ctx.db, ctx.frange.file_id, strukt)?;
+    if impls.has_method("new") {
+        return None;
+    }
+    let module = impls.def.module(ctx.db);
+    let fields = impls.def.fields(ctx.db);
+    let (names, body) = match strukt.flavor() {
+        ast::StructFlavor::Named(_) => {
+            let names = fields.iter().map(|it| it.name(ctx.db).to_string()).collect::<Vec<_>>();
+            let body = join(names.iter()).separator(", ").surround_with("Self { ", " }");
+            (names.clone(), body.to_string())
+        }
+        ast::StructFlavor::Tuple(_) => {
+            let names = (0..fields.len()).map(positional_name).collect::<Vec<_>>();
+            let body = join(names.iter()).separator(", ").surround_with("Self(", ")");
+            (names.clone(), body.to_string())
+        }
+        ast::StructFlavor::Unit => (Vec::new(), "Self".to_string()),
+    };
+    let params = fields
+        .iter()
+        .zip(names.iter())
+        .map(|(field, name)| Some(format!("{}: {}", name, field_ty(ctx.db, module, *field)?)))
+        .collect::<Option<Vec<_>>>()?;
+    let method =
+        format!("{}fn new({}) -> Self {{\n    {}\n}}", visibility(strukt), params.join(", "), body);
+    let impl_block = impls.impl_block.as_ref().map(|it| &**it);
+    let (range, text, cursor) = method_insertion(strukt, impl_block, &method)?;
+
+    ctx.add_action("generate new", |edit| {
+        edit.target(strukt.syntax().range());
+        edit.replace(range, text);
+        edit.set_cursor(cursor);
+    });
+
+    ctx.build()
+}
+
+/// The parameter names for the fields of tuple structs: `a`, `b` and so on.
+fn positional_name(idx: usize) -> String {
+    if idx < 26 {
+        ((b'a' + idx as u8) as char).to_string()
+    } else {
+        format!("field{}", idx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::helpers::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn test_generate_new() {
+        check_assist(
+            generate_new,
+            "
+pub struct Foo<T> {<|>
+    a: u32,
+    b: Vec<T>,
+    c: [u8; 4],
+}",
+            "
+pub struct Foo<T> {
+    a: u32,
+    b: Vec<T>,
+    c: [u8; 4],
+}
+
+impl<T> Foo<T> {
+    <|>pub fn new(a: u32, b: Vec<T>, c: [u8; 4]) -> Self {
+        Self { a, b, c }
+    }
+}",
+        );
+    }
+
+    #[test]
+    fn test_generate_new_into_existing_impl() {
+        check_assist(
+            generate_new,
+            "
+struct Foo<|>;
+
+impl Foo {
+    fn foo(&self) {}
+}",
+            "
+struct Foo;
+
+impl Foo {
+    fn foo(&self) {}
+
+    <|>fn new() -> Self {
+        Self
+    }
+}",
+        );
+    }
+
+    #[test]
+    fn test_generate_new_not_applicable() {
+        check_assist_not_applicable(
+            generate_new,
+            "
+struct Foo<|> { a: u32 }
+
+impl Foo {
+    fn new(a: u32) -> Self { Foo { a } }
+}",
+        );
+    }
+
+    #[test]
+    fn test_generate_new_for_tuple_struct() {
+        check_assist(
+            generate_new,
+            "
+struct Foo<'a><|>(u32, &'a str);",
+            "
+struct Foo<'a>(u32, &'a str);
+
+impl<'a> Foo<'a> {
+    <|>fn new(a: u32, b: &'a str) -> Self {
+        Self(a, b)
+    }
+}",
+        );
+    }
+
+    #[test]
+    fn test_generate_new_shortens_paths() {
+        check_assist(
+            generate_new,
+            "
+mod foo {
+    pub struct Bar;
+}
+use foo::Bar;
+
+struct Foo<|> { a: crate::foo::Bar, b: ::std::vec::Vec<u8> }",
+            "
+mod foo {
+    pub struct Bar;
+}
+use foo::Bar;
+
+struct Foo { a: crate::foo::Bar, b: ::std::vec::Vec<u8> }
+
+impl Foo {
+    <|>fn new(a: Bar, b: ::std::vec::Vec<u8>) -> Self {
+        Self { a, b }
+    }
+}",
+        );
+    }
+}
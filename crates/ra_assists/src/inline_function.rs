@@ -0,0 +1,304 @@
+use hir::{ModuleDef, Resolution, source_binder, db::HirDatabase};
+use ra_syntax::{
+    AstNode, SyntaxNode,
+    ast::{self, ArgListOwner, NameOwner},
+    algo::find_covering_node,
+    SyntaxKind::*,
+};
+use ra_fmt::leading_indent;
+
+use crate::{AssistCtx, Assist, inline_local_variable::needs_parens, utils::render_with_edits};
+
+pub(crate) fn inline_function(mut ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let call = ctx.node_at_offset::<ast::CallExpr>()?;
+    let path = match call.expr()?.kind() {
+        ast::ExprKind::PathExpr(it) => it.path()?,
+        _ => return None,
+    };
+    let resolver = source_binder::resolver_for_node(ctx.db, ctx.frange.file_id, call.syntax());
+    let function = match resolver.resolve_path(ctx.db, &hir::Path::from_ast(path)?).take_values() {
+        Some(Resolution::Def(ModuleDef::Function(it))) => it,
+        _ => return None,
+    };
+    let (callee_file, fn_def) = function.source(ctx.db);
+    let callee_file = callee_file.original_file(ctx.db);
+    let body = fn_def.body()?;
+    let param_list = fn_def.param_list()?;
+    if param_list.self_param().is_some() {
+        return None;
+    }
+    // Recursive functions can't be inlined, and `return` or `?` would leave
+    // the caller instead of the inlined body.
+    let fn_name = fn_def.name()?.text().clone();
+    let is_simple = body.syntax().descendants().all(|it| match it.kind() {
+        RETURN_EXPR | TRY_EXPR => false,
+        _ => ast::NameRef::cast(it).map_or(true, |it| *it.text() != fn_name),
+    });
+    if !is_simple {
+        return None;
+    }
+
+    let params = param_list
+        .params()
+        .map(|param| match param.pat()?.kind() {
+            ast::PatKind::BindPat(it) => Some(it),
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>()?;
+    let args = call.arg_list()?.args().collect::<Vec<_>>();
+    if params.len() != args.len() {
+        return None;
+    }
+
+    // The paths in the body have to mean the same at the call site, which
+    // rules out most of the callees from other modules.
+    for path in body.syntax().descendants().filter_map(ast::Path::cast) {
+        if path.syntax().parent().map(|it| it.kind()) == Some(PATH) {
+            continue;
+        }
+        let hir_path = match hir::Path::from_ast(path) {
+            Some(it) => it,
+            None => continue,
+        };
+        let callee_resolution =
+            source_binder::resolver_for_node(ctx.db, callee_file, path.syntax())
+                .resolve_path(ctx.db, &hir_path);
+        if let Some(Resolution::LocalBinding(_)) = callee_resolution.values {
+            continue;
+        }
+        if resolver.resolve_path(ctx.db, &hir_path) != callee_resolution {
+            return None;
+        }
+    }
+
+    // Arguments are substituted for the parameters if they are trivial, and
+    // bound with `let` otherwise, or if a local of the callee would capture
+    // them.
+    let body_names = body
+        .syntax()
+        .descendants()
+        .filter_map(ast::BindPat::cast)
+        .filter_map(|it| Some(it.name()?.text().to_string()))
+        .collect::<Vec<_>>();
+    let binds_with_let = params
+        .iter()
+        .zip(args.iter())
+        .map(|(param, arg)| {
+            let is_trivial = match arg.syntax().kind() {
+                PATH_EXPR | LITERAL => true,
+                _ => false,
+            };
+            !is_trivial
+                || param.is_mutable()
+                || free_names(arg.syntax()).iter().any(|it| body_names.contains(it))
+        })
+        .collect::<Vec<_>>();
+    let let_names = params
+        .iter()
+        .zip(binds_with_let.iter())
+        .filter(|(_, &binds_with_let)| binds_with_let)
+        .filter_map(|(param, _)| Some(param.name()?.text().to_string()))
+        .collect::<Vec<_>>();
+
+    let scopes = function.scopes(ctx.db);
+    let mut edits = Vec::new();
+    let mut lets = Vec::new();
+    for ((param, arg), binds_with_let) in params.iter().zip(args.iter()).zip(binds_with_let) {
+        let arg_names = free_names(arg.syntax());
+        if binds_with_let {
+            // The parameters bound before this one would capture the argument.
+            if arg_names.iter().any(|it| let_names[..lets.len()].contains(it)) {
+                return None;
+            }
+            lets.push(format!("let {} = {};", param.syntax().text(), arg.syntax().text()));
+            continue;
+        }
+        if arg_names.iter().any(|it| let_names.contains(it)) {
+            return None;
+        }
+        for reference in scopes.find_all_refs(param) {
+            let path_expr = find_covering_node(fn_def.syntax(), reference.range)
+                .ancestors()
+                .find_map(ast::PathExpr::cast)?;
+            edits.push((path_expr.syntax().range(), arg.syntax().text().to_string()));
+        }
+    }
+
+    let replacement = match body.expr() {
+        Some(tail) if lets.is_empty() && body.statements().next().is_none() => {
+            let text = render_with_edits(tail.syntax(), &edits);
+            match call.syntax().parent() {
+                Some(parent) if needs_parens(tail, parent) => format!("({})", text),
+                _ => text,
+            }
+        }
+        _ => {
+            let indent = leading_indent(call.syntax()).unwrap_or("");
+            let body_text = render_with_edits(body.syntax(), &edits);
+            // Strip the braces, the statements get a new block.
+            let body_text = body_text[1..body_text.len() - 1].trim();
+            let body_indent = body
+                .statements()
+                .next()
+                .map(|it| it.syntax())
+                .or(body.expr().map(|it| it.syntax()))
+                .and_then(leading_indent)
+                .unwrap_or("");
+            let mut buf = String::from("{\n");
+            let lines = lets.iter().map(String::as_str).chain(body_text.lines().map(|line| {
+                if line.starts_with(body_indent) {
+                    &line[body_indent.len()..]
+                } else {
+                    line
+                }
+            }));
+            for line in lines {
+                if !line.is_empty() {
+                    buf.push_str(indent);
+                    buf.push_str("    ");
+                    buf.push_str(line);
+                }
+                buf.push('\n');
+            }
+            buf.push_str(indent);
+            buf.push('}');
+            buf
+        }
+    };
+
+    ctx.add_action("inline function", move |edit| {
+        edit.target(call.syntax().range());
+        edit.replace(call.syntax().range(), replacement);
+        edit.set_cursor(call.syntax().range().start());
+    });
+
+    ctx.build()
+}
+
+/// The names the paths in `node` start with.
+fn free_names(node: &SyntaxNode) -> Vec<String> {
+    node.descendants()
+        .filter_map(ast::Path::cast)
+        .filter(|it| it.qualifier().is_none())
+        .filter_map(|it| Some(it.segment()?.name_ref()?.text().to_string()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::helpers::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn test_inline_function_into_expr() {
+        check_assist(
+            inline_function,
+            "
+fn add(a: u32, b: u32) -> u32 {
+    a + b
+}
+
+fn foo() {
+    let x = <|>add(1, 2) * 3;
+}",
+            "
+fn add(a: u32, b: u32) -> u32 {
+    a + b
+}
+
+fn foo() {
+    let x = <|>(1 + 2) * 3;
+}",
+        );
+    }
+
+    #[test]
+    fn test_inline_function_binds_complex_args() {
+        check_assist(
+            inline_function,
+            "
+fn square(x: u32) -> u32 {
+    x * x
+}
+
+fn foo(n: u32) {
+    let y = <|>square(n + 1);
+}",
+            "
+fn square(x: u32) -> u32 {
+    x * x
+}
+
+fn foo(n: u32) {
+    let y = <|>{
+        let x = n + 1;
+        x * x
+    };
+}",
+        );
+    }
+
+    #[test]
+    fn test_inline_function_binds_args_captured_by_locals() {
+        check_assist(
+            inline_function,
+            "
+fn double(a: u32) -> u32 {
+    let x = a * 2;
+    x
+}
+
+fn foo(x: u32) {
+    let y = <|>double(x);
+}",
+            "
+fn double(a: u32) -> u32 {
+    let x = a * 2;
+    x
+}
+
+fn foo(x: u32) {
+    let y = <|>{
+        let a = x;
+        let x = a * 2;
+        x
+    };
+}",
+        );
+    }
+
+    #[test]
+    fn test_inline_function_not_applicable_if_paths_resolve_differently() {
+        check_assist_not_applicable(
+            inline_function,
+            "
+mod m {
+    const K: u32 = 2;
+
+    pub fn scale(a: u32) -> u32 {
+        a * K
+    }
+}
+
+fn foo() {
+    <|>m::scale(1);
+}",
+        );
+    }
+
+    #[test]
+    fn test_inline_function_not_applicable_to_recursive_fns() {
+        check_assist_not_applicable(
+            inline_function,
+            "
+fn fact(n: u32) -> u32 {
+    if n == 0 { 1 } else { n * fact(n - 1) }
+}
+
+fn foo() {
+    <|>fact(3);
+}",
+        );
+    }
+}
@@ -0,0 +1,437 @@
+use hir::{source_binder, db::HirDatabase};
+use ra_syntax::{
+    AstNode, Direction, SmolStr, SourceFile, SyntaxNode, TextRange,
+    ast::{self, NameOwner},
+    algo::find_covering_node,
+    SyntaxKind::*,
+};
+
+use crate::{AssistCtx, Assist, utils::is_pure};
+
+pub(crate) fn inline_local_variable(mut ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let let_stmt = ctx.node_at_offset::<ast::LetStmt>()?;
+    let bind_pat = match let_stmt.pat()?.kind() {
+        ast::PatKind::BindPat(it) => it,
+        _ => return None,
+    };
+    // Inlining a mutable binding would lose the writes to it.
+    if bind_pat.is_mutable() {
+        return None;
+    }
+    let name = bind_pat.name()?.text().clone();
+    let initializer = let_stmt.initializer()?;
+    let function =
+        source_binder::function_from_child_node(ctx.db, ctx.frange.file_id, let_stmt.syntax())?;
+    let scopes = function.scopes(ctx.db);
+    let refs = scopes.find_all_refs(bind_pat);
+    // Without uses, inlining would silently drop the initializer.
+    if refs.is_empty() {
+        return None;
+    }
+    let root = let_stmt.syntax().ancestors().last()?;
+    let name_refs = refs
+        .iter()
+        .map(|it| find_covering_node(root, it.range).ancestors().find_map(ast::NameRef::cast))
+        .collect::<Option<Vec<_>>>()?;
+
+    // The initializer can be moved to a use evaluated right after the `let`.
+    // Otherwise, it would be evaluated several times, later or conditionally,
+    // which is only fine when it has no side effects and reads nothing that
+    // is written to before the uses.
+    let evaluated_next = match name_refs.as_slice() {
+        [name_ref] => is_evaluated_next(let_stmt, name_ref.syntax()),
+        _ => false,
+    };
+    if !evaluated_next {
+        if !is_pure(initializer) {
+            return None;
+        }
+        let source_file = SourceFile::cast(root)?;
+        let mutable_locals = initializer
+            .syntax()
+            .descendants()
+            .filter_map(ast::NameRef::cast)
+            .filter(|name_ref| {
+                scopes
+                    .resolve_local_name(name_ref)
+                    .and_then(|entry| ast::BindPat::cast(entry.ptr().to_node(source_file)))
+                    .map_or(false, |it| it.is_mutable())
+            })
+            .map(|it| it.text().clone())
+            .collect::<Vec<_>>();
+        let last_use = name_refs.iter().map(|it| it.syntax().range().end()).max()?;
+        let range = TextRange::from_to(let_stmt.syntax().range().end(), last_use);
+        if is_written_in(root, range, &mutable_locals) {
+            return None;
+        }
+    }
+
+    let mut usages = Vec::new();
+    for name_ref in name_refs {
+        let usage = match name_ref.syntax().ancestors().nth(3).and_then(ast::PathExpr::cast) {
+            Some(path_expr) => {
+                let parent = path_expr.syntax().parent();
+                let replacement = if parent.map_or(false, |it| needs_parens(initializer, it)) {
+                    format!("({})", initializer.syntax().text())
+                } else {
+                    initializer.syntax().text().to_string()
+                };
+                (path_expr.syntax().range(), replacement)
+            }
+            // A field shorthand, as in `S { x }`.
+            None if name_ref.syntax().parent().map(|it| it.kind()) == Some(NAMED_FIELD) => {
+                (name_ref.syntax().range(), format!("{}: {}", name, initializer.syntax().text()))
+            }
+            None => return None,
+        };
+        usages.push(usage);
+    }
+
+    ctx.add_action("inline variable", move |edit| {
+        let delete_range = match let_stmt.syntax().next_sibling() {
+            Some(ws) if ws.kind() == WHITESPACE => {
+                TextRange::from_to(let_stmt.syntax().range().start(), ws.range().end())
+            }
+            _ => let_stmt.syntax().range(),
+        };
+        edit.target(let_stmt.syntax().range());
+        edit.delete(delete_range);
+        for (range, replacement) in usages {
+            edit.replace(range, replacement);
+        }
+        edit.set_cursor(delete_range.start());
+    });
+
+    ctx.build()
+}
+
+/// Checks whether `node` is the first expression evaluated by the statement
+/// following `let_stmt`, and is evaluated exactly once.
+fn is_evaluated_next(let_stmt: &ast::LetStmt, node: &SyntaxNode) -> bool {
+    let next = match let_stmt
+        .syntax()
+        .siblings(Direction::Next)
+        .skip(1)
+        .find(|it| it.kind() != WHITESPACE && it.kind() != COMMENT)
+    {
+        Some(it) => it,
+        None => return false,
+    };
+    let mut child = node;
+    for parent in node.ancestors().skip(1) {
+        let evaluated_once = match parent.kind() {
+            LOOP_EXPR | WHILE_EXPR | FOR_EXPR | LAMBDA_EXPR => false,
+            IF_EXPR => child.kind() == CONDITION,
+            MATCH_EXPR => child.kind() != MATCH_ARM_LIST,
+            BIN_EXPR => match ast::BinExpr::cast(parent).and_then(|it| it.op()) {
+                Some(ast::BinOp::BooleanAnd) | Some(ast::BinOp::BooleanOr) => {
+                    child.prev_sibling().is_none()
+                }
+                _ => true,
+            },
+            _ => true,
+        };
+        if !evaluated_once {
+            return false;
+        }
+        if !parent.children().take_while(|it| *it != child).all(is_pure_node) {
+            return false;
+        }
+        if parent == next {
+            return true;
+        }
+        child = parent;
+    }
+    false
+}
+
+fn is_pure_node(node: &SyntaxNode) -> bool {
+    match ast::Expr::cast(node) {
+        Some(expr) => is_pure(expr),
+        None => node.children().all(is_pure_node),
+    }
+}
+
+/// Checks whether one of the `locals` may be written to in `range`, by an
+/// assignment, a mutable borrow or a method call.
+fn is_written_in(root: &SyntaxNode, range: TextRange, locals: &[SmolStr]) -> bool {
+    if locals.is_empty() {
+        return false;
+    }
+    find_covering_node(root, range)
+        .descendants()
+        .filter(|it| it.range().is_subrange(&range))
+        .filter_map(ast::NameRef::cast)
+        .filter(|name_ref| locals.contains(name_ref.text()))
+        .any(|name_ref| {
+            // The outermost place expression, like `x.field[0]` for `x`.
+            let place = name_ref
+                .syntax()
+                .ancestors()
+                .take_while(|it| match it.kind() {
+                    NAME_REF | PATH_SEGMENT | PATH | PATH_EXPR | FIELD_EXPR | INDEX_EXPR => true,
+                    _ => false,
+                })
+                .last();
+            match place.and_then(|it| Some((it, it.parent()?))) {
+                Some((place, parent)) => is_written(place, parent),
+                None => false,
+            }
+        })
+}
+
+fn is_written(place: &SyntaxNode, parent: &SyntaxNode) -> bool {
+    if let Some(bin_expr) = ast::BinExpr::cast(parent) {
+        bin_expr.op().map_or(false, ast::BinOp::is_assignment)
+            && bin_expr.lhs().map(AstNode::syntax) == Some(place)
+    } else if let Some(ref_expr) = ast::RefExpr::cast(parent) {
+        ref_expr.is_mut()
+    } else if let Some(method_call) = ast::MethodCallExpr::cast(parent) {
+        method_call.expr().map(AstNode::syntax) == Some(place)
+    } else {
+        false
+    }
+}
+
+/// Checks whether `expr` has to be wrapped in parentheses to take the place
+/// of a child of `parent`, because otherwise it would bind differently.
+pub(crate) fn needs_parens(expr: &ast::Expr, parent: &SyntaxNode) -> bool {
+    let binds_loosely = match expr.syntax().kind() {
+        BIN_EXPR | RANGE_EXPR | CAST_EXPR | LAMBDA_EXPR | RETURN_EXPR | BREAK_EXPR => true,
+        PREFIX_EXPR | REF_EXPR => match parent.kind() {
+            BIN_EXPR | PREFIX_EXPR | REF_EXPR | CAST_EXPR | RANGE_EXPR => false,
+            _ => true,
+        },
+        _ => false,
+    };
+    if !binds_loosely {
+        return false;
+    }
+    match parent.kind() {
+        BIN_EXPR | PREFIX_EXPR | REF_EXPR | CAST_EXPR | RANGE_EXPR | TRY_EXPR | FIELD_EXPR
+        | METHOD_CALL_EXPR | INDEX_EXPR | CALL_EXPR => true,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::helpers::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn test_inline_local_variable() {
+        check_assist(
+            inline_local_variable,
+            "
+fn foo() {
+    let a<|> = 1 + 1;
+    let b = a * 10;
+    let c = a;
+    bar(a);
+}",
+            "
+fn foo() {
+    <|>let b = (1 + 1) * 10;
+    let c = 1 + 1;
+    bar(1 + 1);
+}",
+        );
+    }
+
+    #[test]
+    fn test_inline_local_variable_not_applicable_to_impure_initializers() {
+        check_assist_not_applicable(
+            inline_local_variable,
+            "
+fn foo() {
+    let a<|> = bar();
+    baz(a);
+    baz(a);
+}",
+        );
+    }
+
+    #[test]
+    fn test_inline_local_variable_not_applicable_without_uses() {
+        check_assist_not_applicable(
+            inline_local_variable,
+            "
+fn foo() {
+    let a<|> = bar();
+}",
+        );
+    }
+
+    #[test]
+    fn test_inline_local_variable_impure_initializer_into_next_statement() {
+        check_assist(
+            inline_local_variable,
+            "
+fn foo() {
+    let a<|> = bar();
+    if a.is_ok() { baz(1) }
+}",
+            "
+fn foo() {
+    <|>if bar().is_ok() { baz(1) }
+}",
+        );
+    }
+
+    #[test]
+    fn test_inline_local_variable_not_applicable_to_reordered_impure_initializers() {
+        // Moved past another statement.
+        check_assist_not_applicable(
+            inline_local_variable,
+            "
+fn foo() {
+    let <|>a = bar();
+    let b = -x;
+    a.baz();
+}",
+        );
+        // Moved past another call.
+        check_assist_not_applicable(
+            inline_local_variable,
+            "
+fn foo() {
+    let <|>a = bar();
+    baz(qux(), a);
+}",
+        );
+        // Evaluated conditionally.
+        check_assist_not_applicable(
+            inline_local_variable,
+            "
+fn foo() {
+    let <|>a = bar();
+    if x { baz(a) }
+}",
+        );
+        // Evaluated repeatedly.
+        check_assist_not_applicable(
+            inline_local_variable,
+            "
+fn foo() {
+    let <|>a = bar();
+    loop { baz(a) }
+}",
+        );
+        check_assist_not_applicable(
+            inline_local_variable,
+            "
+fn foo() {
+    let <|>a = bar();
+    let f = || baz(a);
+}",
+        );
+    }
+
+    #[test]
+    fn test_inline_local_variable_not_applicable_past_writes() {
+        check_assist_not_applicable(
+            inline_local_variable,
+            "
+fn foo() {
+    let mut x = 1;
+    let <|>a = x;
+    x = 2;
+    baz(a);
+}",
+        );
+        check_assist_not_applicable(
+            inline_local_variable,
+            "
+fn foo() {
+    let mut v = S { x: 1 };
+    let <|>a = v.x;
+    v.set(2);
+    baz(a);
+}",
+        );
+        check_assist(
+            inline_local_variable,
+            "
+fn foo() {
+    let mut x = 1;
+    let <|>a = x;
+    baz(x);
+    baz(a);
+    x = 2;
+}",
+            "
+fn foo() {
+    let mut x = 1;
+    <|>baz(x);
+    baz(x);
+    x = 2;
+}",
+        );
+    }
+
+    #[test]
+    fn test_inline_local_variable_receivers() {
+        check_assist(
+            inline_local_variable,
+            "
+fn foo() {
+    let <|>a = bar();
+    a.baz();
+    let b = -x;
+}",
+            "
+fn foo() {
+    <|>bar().baz();
+    let b = -x;
+}",
+        );
+        check_assist(
+            inline_local_variable,
+            "
+fn foo() {
+    let <|>b = -x;
+    b.baz();
+    let c = b + 1;
+}",
+            "
+fn foo() {
+    <|>(-x).baz();
+    let c = -x + 1;
+}",
+        );
+    }
+
+    #[test]
+    fn test_inline_local_variable_field_shorthand() {
+        check_assist(
+            inline_local_variable,
+            "
+struct S { x: u32 }
+
+fn foo() {
+    let <|>x = 92;
+    S { x };
+}",
+            "
+struct S { x: u32 }
+
+fn foo() {
+    <|>S { x: 92 };
+}",
+        );
+    }
+
+    #[test]
+    fn test_inline_local_variable_not_applicable_to_mut() {
+        check_assist_not_applicable(
+            inline_local_variable,
+            "
+fn foo() {
+    let mut a<|> = 1;
+    a += 1;
+}",
+        );
+    }
+}
@@ -0,0 +1,107 @@
+use hir::db::HirDatabase;
+use ra_syntax::{AstNode, ast};
+
+use crate::{AssistCtx, Assist, utils::negate};
+
+pub(crate) fn invert_if(mut ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let if_expr = ctx.node_at_offset::<ast::IfExpr>()?;
+    let then_block = if_expr.then_branch()?;
+    // Offered on the `if` and the condition, not on everything in the branches.
+    if ctx.frange.range.start() > then_block.syntax().range().start() {
+        return None;
+    }
+    let cond = if_expr.condition()?;
+    if cond.pat().is_some() {
+        return None;
+    }
+    let expr = cond.expr()?;
+    let else_block = match if_expr.else_branch()? {
+        ast::ElseBranchFlavor::Block(it) => it,
+        ast::ElseBranchFlavor::IfExpr(_) => return None,
+    };
+
+    ctx.add_action("invert if", |edit| {
+        edit.target(if_expr.syntax().range());
+        edit.replace(expr.syntax().range(), negate(expr));
+        edit.replace(then_block.syntax().range(), else_block.syntax().text().to_string());
+        edit.replace(else_block.syntax().range(), then_block.syntax().text().to_string());
+        edit.set_cursor(if_expr.syntax().range().start());
+    });
+
+    ctx.build()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::helpers::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn test_invert_if() {
+        check_assist(
+            invert_if,
+            "
+fn f() {
+    <|>if x.is_empty() {
+        foo();
+    } else {
+        bar();
+    }
+}",
+            "
+fn f() {
+    <|>if !x.is_empty() {
+        bar();
+    } else {
+        foo();
+    }
+}",
+        );
+    }
+
+    #[test]
+    fn test_invert_if_simplifies_condition() {
+        check_assist(
+            invert_if,
+            "
+fn f() {
+    if !(a && b)<|> { 1 } else { 2 }
+}",
+            "
+fn f() {
+    <|>if a && b { 2 } else { 1 }
+}",
+        );
+        check_assist(
+            invert_if,
+            "
+fn f() {
+    if a == b<|> { 1 } else { 2 }
+}",
+            "
+fn f() {
+    <|>if a != b { 2 } else { 1 }
+}",
+        );
+        check_assist(
+            invert_if,
+            "
+fn f() {
+    if a < b<|> { 1 } else { 2 }
+}",
+            "
+fn f() {
+    <|>if !(a < b) { 2 } else { 1 }
+}",
+        );
+    }
+
+    #[test]
+    fn test_invert_if_not_applicable() {
+        check_assist_not_applicable(invert_if, "fn f() { if <|>x { 1 } }");
+        check_assist_not_applicable(invert_if, "fn f() { if <|>x { 1 } else if y { 2 } }");
+        check_assist_not_applicable(invert_if, "fn f() { if let <|>Some(x) = y { 1 } else { 2 } }");
+        check_assist_not_applicable(invert_if, "fn f() { if x { <|>1 } else { 2 } }");
+    }
+}
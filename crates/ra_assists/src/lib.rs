@@ -11,11 +11,14 @@ use itertools::Itertools;
 
 use ra_text_edit::TextEdit;
 use ra_syntax::{TextRange, TextUnit, SyntaxNode, Direction};
-use ra_db::FileRange;
+use ra_db::{FileId, FileRange, SourceRootId};
+use relative_path::RelativePathBuf;
 use hir::db::HirDatabase;
 
 pub(crate) use crate::assist_ctx::{AssistCtx, Assist};
 
+pub use crate::auto_import::auto_import_text_edit;
+
 #[derive(Debug, Clone)]
 pub struct AssistLabel {
     /// Short description of the assist, as shown in the UI.
@@ -25,10 +28,21 @@ pub struct AssistLabel {
 #[derive(Debug, Clone)]
 pub struct AssistAction {
     pub edit: TextEdit,
+    /// Edits to the files other than the one the assist was invoked in.
+    pub other_file_edits: Vec<(FileId, TextEdit)>,
+    pub new_files: Vec<NewFile>,
     pub cursor_position: Option<TextUnit>,
     pub target: Option<TextRange>,
 }
 
+/// A file created by an assist.
+#[derive(Debug, Clone)]
+pub struct NewFile {
+    pub source_root: SourceRootId,
+    pub path: RelativePathBuf,
+    pub text: String,
+}
+
 /// Return all the assists applicable at the given position.
 ///
 /// Assists are returned in the "unresolved" state, that is only labels are
@@ -79,25 +93,62 @@ where
 }
 
 mod add_derive;
+mod add_explicit_type;
 mod add_impl;
+mod add_missing_impl_members;
+mod add_turbofish;
 mod flip_comma;
 mod change_visibility;
+mod convert_struct_fields;
+mod convert_to_guarded_return;
+mod extract_function;
 mod fill_match_arms;
+mod generate_accessors;
+mod generate_new;
+mod inline_function;
+mod inline_local_variable;
 mod introduce_variable;
+mod invert_if;
+mod merge_match_arms;
+mod move_item_to_module;
+mod move_module_to_file;
+mod organize_imports;
 mod replace_if_let_with_match;
+mod replace_match_with_if_let;
 mod split_import;
+mod utils;
 mod remove_dbg;
 mod auto_import;
 
 fn all_assists<DB: HirDatabase>() -> &'static [fn(AssistCtx<DB>) -> Option<Assist>] {
     &[
         add_derive::add_derive,
+        add_explicit_type::add_explicit_type,
         add_impl::add_impl,
+        add_missing_impl_members::add_missing_impl_members,
+        add_missing_impl_members::add_missing_required_impl_members,
+        add_turbofish::add_turbofish,
         change_visibility::change_visibility,
+        convert_struct_fields::convert_to_named_fields,
+        convert_struct_fields::convert_to_tuple_fields,
+        convert_to_guarded_return::convert_to_guarded_return,
+        extract_function::extract_function,
         fill_match_arms::fill_match_arms,
+        generate_accessors::generate_getter,
+        generate_accessors::generate_getter_mut,
+        generate_accessors::generate_setter,
+        generate_new::generate_new,
+        inline_function::inline_function,
+        inline_local_variable::inline_local_variable,
         flip_comma::flip_comma,
         introduce_variable::introduce_variable,
+        invert_if::invert_if,
+        merge_match_arms::merge_match_arms,
+        move_item_to_module::move_item_to_module,
+        move_module_to_file::move_module_to_file,
+        organize_imports::organize_imports,
         replace_if_let_with_match::replace_if_let_with_match,
+        replace_match_with_if_let::replace_match_with_if_let,
         split_import::split_import,
         remove_dbg::remove_dbg,
         auto_import::auto_import,
@@ -257,6 +308,17 @@ mod helpers {
         let assist = AssistCtx::with_ctx(&db, frange, true, assist);
         assert!(assist.is_none());
     }
+
+    pub(crate) fn check_assist_range_not_applicable(
+        assist: fn(AssistCtx<MockDatabase>) -> Option<Assist>,
+        before: &str,
+    ) {
+        let (range, before) = extract_range(before);
+        let (db, _source_root, file_id) = MockDatabase::with_single_file(&before);
+        let frange = FileRange { file_id, range };
+        let assist = AssistCtx::with_ctx(&db, frange, true, assist);
+        assert!(assist.is_none());
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,177 @@
+use hir::{source_binder, db::HirDatabase};
+use ra_syntax::{AstNode, Direction, TextRange, ast, SyntaxKind::*};
+
+use crate::{AssistCtx, Assist, non_trivia_sibling, utils::pat_bindings};
+
+/// Merges a match arm with the next one if they have the same body:
+/// `A => foo(), B => foo(),` becomes `A | B => foo(),`.
+pub(crate) fn merge_match_arms(mut ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let arm1 = ctx.node_at_offset::<ast::MatchArm>()?;
+    let arm2 = non_trivia_sibling(arm1.syntax(), Direction::Next)
+        .and_then(|it| match it.kind() {
+            COMMA => non_trivia_sibling(it, Direction::Next),
+            _ => Some(it),
+        })
+        .and_then(ast::MatchArm::cast)?;
+    if arm1.guard().is_some() || arm2.guard().is_some() {
+        return None;
+    }
+    let expr1 = arm1.expr()?;
+    let expr2 = arm2.expr()?;
+    if expr1.syntax().text().to_string() != expr2.syntax().text().to_string() {
+        return None;
+    }
+    let function =
+        source_binder::function_from_child_node(ctx.db, ctx.frange.file_id, arm1.syntax())?;
+    let bindings = |arm: &ast::MatchArm| {
+        let mut res =
+            arm.pats().flat_map(|pat| pat_bindings(ctx.db, function, pat)).collect::<Vec<_>>();
+        res.sort_by(|(a, _), (b, _)| a.cmp(b));
+        res
+    };
+    // The body has to mean the same in both arms, so the names it uses have
+    // to be bound to values of the same types.
+    if bindings(arm1) != bindings(arm2) {
+        return None;
+    }
+    let last_pat = arm1.pats().last()?;
+    let pats2 = arm2.pats().map(|it| it.syntax().text().to_string()).collect::<Vec<_>>();
+
+    ctx.add_action("merge match arms", |edit| {
+        edit.target(arm1.syntax().range());
+        edit.insert(last_pat.syntax().range().end(), format!(" | {}", pats2.join(" | ")));
+        edit.delete(TextRange::from_to(arm1.syntax().range().end(), arm2.syntax().range().end()));
+        edit.set_cursor(arm1.syntax().range().start());
+    });
+
+    ctx.build()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    use super::*;
+
+    #[test]
+    fn test_merge_match_arms() {
+        check_assist(
+            merge_match_arms,
+            "
+enum E { A, B, C }
+
+fn f(e: E) -> u32 {
+    match e {
+        E::A<|> => 1,
+        E::B => 1,
+        E::C => 2,
+    }
+}",
+            "
+enum E { A, B, C }
+
+fn f(e: E) -> u32 {
+    match e {
+        <|>E::A | E::B => 1,
+        E::C => 2,
+    }
+}",
+        );
+    }
+
+    #[test]
+    fn test_merge_match_arms_with_bindings() {
+        check_assist(
+            merge_match_arms,
+            "
+enum E { A(u32), B(u32), C }
+
+fn f(e: E) -> u32 {
+    match e {
+        E::A(x) => <|>x + 1,
+        E::B(x) => x + 1,
+        E::C => 0
+    }
+}",
+            "
+enum E { A(u32), B(u32), C }
+
+fn f(e: E) -> u32 {
+    match e {
+        <|>E::A(x) | E::B(x) => x + 1,
+        E::C => 0
+    }
+}",
+        );
+    }
+
+    #[test]
+    fn test_merge_match_arms_target() {
+        check_assist_target(
+            merge_match_arms,
+            "
+enum Option<T> { Some(T), None }
+use Option::*;
+
+fn f(x: Option<u32>) {
+    match x {
+        Some(_)<|> => foo(),
+        None => foo(),
+    }
+}",
+            "Some(_) => foo()",
+        );
+    }
+
+    #[test]
+    fn test_merge_match_arms_not_applicable() {
+        check_assist_not_applicable(
+            merge_match_arms,
+            "
+enum Option<T> { Some(T), None }
+use Option::*;
+
+fn f(x: Option<u32>) {
+    match x {
+        Some(_)<|> => foo(),
+        None => bar(),
+    }
+}",
+        );
+        check_assist_not_applicable(
+            merge_match_arms,
+            "
+fn f(x: Result<u32, u32>) {
+    match x {
+        Ok(x)<|> => foo(x),
+        Err(y) => foo(x),
+    }
+}",
+        );
+        check_assist_not_applicable(
+            merge_match_arms,
+            "
+enum Option<T> { Some(T), None }
+use Option::*;
+
+fn f(x: Option<u32>) {
+    match x {
+        Some(x)<|> if x > 0 => foo(),
+        _ => foo(),
+    }
+}",
+        );
+        check_assist_not_applicable(
+            merge_match_arms,
+            "
+enum E { A(u32), B(bool) }
+
+fn f(e: E) {
+    match e {
+        E::A(x)<|> => foo(x),
+        E::B(x) => foo(x),
+    }
+}",
+        );
+    }
+}
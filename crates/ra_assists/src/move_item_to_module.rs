@@ -0,0 +1,443 @@
+use hir::{Module, ModuleDef, ModuleSource, Path, PerNs, Resolution, source_binder, db::HirDatabase};
+use ra_db::FileId;
+use ra_syntax::{
+    AstNode, SyntaxNode, TextRange, TextUnit,
+    ast::{self, ModuleItemOwner, NameOwner},
+    SyntaxKind::*,
+};
+use ra_fmt::{leading_indent, reindent};
+
+use crate::{
+    AssistCtx, Assist,
+    utils::{render_with_edits, workspace_files},
+};
+
+/// Moves the item under the cursor into one of the modules declared next to
+/// it. The paths to the item get qualified with the module, and the paths
+/// from the item to its former siblings with `super`.
+pub(crate) fn move_item_to_module(mut ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let name = ctx.node_at_offset::<ast::Name>()?;
+    let item = name.syntax().parent()?;
+    match item.kind() {
+        FN_DEF | STRUCT_DEF | ENUM_DEF | TRAIT_DEF | TYPE_DEF | CONST_DEF | STATIC_DEF => (),
+        _ => return None,
+    }
+    let container = item.parent()?;
+    let is_module_level = match container.kind() {
+        SOURCE_FILE => true,
+        ITEM_LIST => container.parent().map(|it| it.kind()) == Some(MODULE),
+        _ => false,
+    };
+    if !is_module_level {
+        return None;
+    }
+    let target_names = container
+        .children()
+        .filter_map(ast::Module::cast)
+        .filter_map(|it| Some(it.name()?.text().to_string()))
+        .collect::<Vec<_>>();
+    if target_names.is_empty() {
+        return None;
+    }
+
+    let file_id = ctx.frange.file_id;
+    let module = source_binder::module_from_child_node(ctx.db, file_id, item)?;
+    let item_name = name.text().to_string();
+    let item_def = module
+        .scope(ctx.db)
+        .entries()
+        .find(|(name, res)| name.to_string() == item_name && res.import.is_none())?
+        .1
+        .def;
+    let fixer = PathFixer { db: ctx.db, file_id, module, item, item_name, item_def };
+    let delete_range = delete_range(item);
+
+    let mut targets = Vec::new();
+    for target in module.children(ctx.db) {
+        let target_name = match target.name(ctx.db) {
+            Some(it) => it.to_string(),
+            None => continue,
+        };
+        if !target_names.contains(&target_name) {
+            continue;
+        }
+        let destination = match target.definition_source(ctx.db) {
+            (target_file, ModuleSource::Module(it)) => {
+                Destination::inline_module(target_file, &it)?
+            }
+            (target_file, ModuleSource::SourceFile(it)) => {
+                Destination::end_of_file(target_file, it.syntax())
+            }
+        };
+        targets.push((target_name, destination));
+    }
+
+    // Finding the paths to qualify means looking at every file, so it's only
+    // done when an action is applied, and only once for all the targets.
+    let mut moved = None;
+    for (target_name, destination) in targets {
+        let moved = &mut moved;
+        let fixer = &fixer;
+        ctx.add_action(format!("move to module `{}`", target_name), |edit| {
+            let (qualify_at, item_text) = moved.get_or_insert_with(|| {
+                let mut qualify_at = Vec::new();
+                for path_file in workspace_files(fixer.db, file_id) {
+                    let file = fixer.db.parse(path_file);
+                    qualify_at.extend(
+                        fixer.paths_to_qualify(path_file, file.syntax()).map(|it| (path_file, it)),
+                    );
+                }
+                // The item was visible in its module, which is now the parent one.
+                let needs_visibility =
+                    !qualify_at.is_empty() && !item.children().any(|it| it.kind() == VISIBILITY);
+                (qualify_at, fixer.moved_text(needs_visibility))
+            });
+            let (target_file, offset) = (destination.file_id, destination.offset);
+            let (text, cursor) = destination.text(item_text);
+            edit.target(item.range());
+            edit.delete(delete_range);
+            if target_file == file_id {
+                // The offset of the moved item once the edits before it are applied.
+                let mut cursor = offset + cursor;
+                if delete_range.end() <= offset {
+                    cursor -= delete_range.len();
+                }
+                for &(path_file, path_offset) in qualify_at.iter() {
+                    if path_file == file_id && path_offset < offset {
+                        cursor += TextUnit::of_str(&target_name) + TextUnit::of_str("::");
+                    }
+                }
+                edit.insert(offset, text);
+                edit.set_cursor(cursor);
+            } else {
+                edit.other_file_edit_builder(target_file).insert(offset, text);
+            }
+            for &(path_file, path_offset) in qualify_at.iter() {
+                let qualifier = format!("{}::", target_name);
+                if path_file == file_id {
+                    edit.insert(path_offset, qualifier);
+                } else {
+                    edit.other_file_edit_builder(path_file).insert(path_offset, qualifier);
+                }
+            }
+        });
+    }
+
+    ctx.build()
+}
+
+struct PathFixer<'a, DB> {
+    db: &'a DB,
+    file_id: FileId,
+    /// The module the item is moved out of.
+    module: Module,
+    item: &'a SyntaxNode,
+    item_name: String,
+    item_def: PerNs<ModuleDef>,
+}
+
+impl<'a, DB: HirDatabase> PathFixer<'a, DB> {
+    /// The offsets at which the target module has to be inserted into the
+    /// paths to the item, like `foo::Item` or `Item` in its old module, and
+    /// into the imports of it.
+    fn paths_to_qualify<'b>(
+        &'b self,
+        file_id: FileId,
+        file: &'b SyntaxNode,
+    ) -> impl Iterator<Item = TextUnit> + 'b {
+        let item_range = self.item.range();
+        file.descendants().filter_map(move |node| {
+            if file_id == self.file_id && node.range().is_subrange(&item_range) {
+                return None;
+            }
+            if let Some(use_item) = ast::UseItem::cast(node) {
+                return self.import_to_qualify(file_id, use_item);
+            }
+            let path = ast::Path::cast(node)?;
+            if node.ancestors().any(|it| it.kind() == USE_ITEM) {
+                return None;
+            }
+            let segment = path.segment()?;
+            if segment.name_ref()?.text().as_str() != self.item_name {
+                return None;
+            }
+            if !self.resolves_to_item(file_id, node, &Path::from_ast(path)?) {
+                return None;
+            }
+            let is_direct = match path.qualifier() {
+                None => {
+                    source_binder::module_from_child_node(self.db, file_id, node)
+                        == Some(self.module)
+                }
+                Some(qualifier) => self.is_old_module(file_id, node, &Path::from_ast(qualifier)?),
+            };
+            if is_direct {
+                Some(segment.syntax().range().start())
+            } else {
+                None
+            }
+        })
+    }
+
+    fn import_to_qualify(&self, file_id: FileId, use_item: &ast::UseItem) -> Option<TextUnit> {
+        let mut res = None;
+        Path::expand_use_item(use_item, |path, segment, _alias| {
+            let segment = match segment {
+                Some(it) => it,
+                None => return,
+            };
+            let is_item = segment.name_ref().map(|it| it.text().as_str() == self.item_name)
+                == Some(true)
+                && path.segments.last().map(|it| it.name.to_string())
+                    == Some(self.item_name.clone());
+            if !is_item {
+                return;
+            }
+            let mut prefix = path.clone();
+            prefix.segments.pop();
+            if self.is_old_module(file_id, use_item.syntax(), &prefix) {
+                res = Some(segment.syntax().range().start());
+            }
+        });
+        res
+    }
+
+    fn resolves_to_item(&self, file_id: FileId, node: &SyntaxNode, path: &Path) -> bool {
+        let resolution =
+            source_binder::resolver_for_node(self.db, file_id, node).resolve_path(self.db, path);
+        let def = match resolution.clone().take_types().or_else(|| resolution.take_values()) {
+            Some(Resolution::Def(it)) => it,
+            _ => return false,
+        };
+        self.item_def.types == Some(def) || self.item_def.values == Some(def)
+    }
+
+    fn is_old_module(&self, file_id: FileId, node: &SyntaxNode, path: &Path) -> bool {
+        let resolution = source_binder::resolver_for_node(self.db, file_id, node)
+            .resolve_path(self.db, path)
+            .take_types();
+        resolution == Some(Resolution::Def(ModuleDef::Module(self.module)))
+    }
+
+    /// The text of the item in its new place, where the names of its former
+    /// siblings have to be prefixed with `super`.
+    fn moved_text(&self, needs_visibility: bool) -> String {
+        let scope = self.module.scope(self.db);
+        let mut edits = Vec::new();
+        for path in self.item.descendants().filter_map(ast::Path::cast) {
+            if path.qualifier().is_some()
+                || path.syntax().parent().map(|it| it.kind()) == Some(PATH)
+            {
+                continue;
+            }
+            let hir_path = match Path::from_ast(path) {
+                Some(it) => it,
+                None => continue,
+            };
+            let name = match hir_path.as_ident() {
+                Some(it) => it,
+                None => continue,
+            };
+            if name.to_string() == self.item_name {
+                continue;
+            }
+            let sibling = match scope.get(name) {
+                Some(it) => it.def,
+                None => continue,
+            };
+            let resolution = source_binder::resolver_for_node(self.db, self.file_id, path.syntax())
+                .resolve_path(self.db, &hir_path);
+            let def = resolution.clone().take_types().or_else(|| resolution.take_values());
+            let is_sibling = match def {
+                Some(Resolution::Def(def)) => {
+                    sibling.types == Some(def) || sibling.values == Some(def)
+                }
+                _ => false,
+            };
+            if is_sibling {
+                let start = path.syntax().range().start();
+                edits.push((TextRange::offset_len(start, 0.into()), "super::".to_string()));
+            }
+        }
+        if needs_visibility {
+            let keyword = self
+                .item
+                .children()
+                .find(|it| it.kind() != COMMENT && it.kind() != ATTR && it.kind() != WHITESPACE);
+            if let Some(keyword) = keyword {
+                let start = keyword.range().start();
+                edits.push((TextRange::offset_len(start, 0.into()), "pub(super) ".to_string()));
+            }
+        }
+        edits.sort_by_key(|(range, _)| range.start());
+        let text = render_with_edits(self.item, &edits);
+        // Lines after the first one are indented for the old place.
+        let indent = leading_indent(self.item).unwrap_or("");
+        let lines =
+            text.lines()
+                .map(|line| if line.starts_with(indent) { &line[indent.len()..] } else { line });
+        lines.collect::<Vec<_>>().join("\n")
+    }
+}
+
+/// The item together with the whitespace separating it from its neighbours.
+fn delete_range(item: &SyntaxNode) -> TextRange {
+    let prev_ws = item.prev_sibling().filter(|it| it.kind() == WHITESPACE);
+    let has_prev_item =
+        prev_ws.and_then(|it| it.prev_sibling()).map_or(false, |it| it.kind() != L_CURLY);
+    match (prev_ws, item.next_sibling()) {
+        (Some(ws), _) if has_prev_item => {
+            TextRange::from_to(ws.range().start(), item.range().end())
+        }
+        (_, Some(ws)) if ws.kind() == WHITESPACE => {
+            TextRange::from_to(item.range().start(), ws.range().end())
+        }
+        _ => item.range(),
+    }
+}
+
+/// Where the moved item goes: the end of an inline module or of a file.
+struct Destination {
+    file_id: FileId,
+    offset: TextUnit,
+    /// The indentation of the item in the module.
+    indent: String,
+    /// The text to put before and after the item.
+    prefix: String,
+    suffix: String,
+}
+
+impl Destination {
+    fn inline_module(file_id: FileId, module: &ast::Module) -> Option<Destination> {
+        let item_list = module.item_list()?;
+        let module_indent = leading_indent(module.syntax()).unwrap_or("");
+        let last_item = item_list.items().last().map(|it| it.syntax());
+        let anchor = match last_item {
+            Some(it) => it,
+            None => item_list.syntax().first_child().filter(|it| it.kind() == L_CURLY)?,
+        };
+        let indent = format!("{}    ", module_indent);
+        let mut prefix = String::new();
+        if last_item.is_some() {
+            prefix.push('\n');
+        }
+        prefix.push('\n');
+        prefix.push_str(&indent);
+        let has_newline_before_r_curly = item_list
+            .syntax()
+            .last_child()
+            .and_then(|it| it.prev_sibling())
+            .and_then(|it| it.leaf_text())
+            .map_or(false, |it| it.contains('\n'));
+        let suffix =
+            if has_newline_before_r_curly { String::new() } else { format!("\n{}", module_indent) };
+        Some(Destination { file_id, offset: anchor.range().end(), indent, prefix, suffix })
+    }
+
+    fn end_of_file(file_id: FileId, file: &SyntaxNode) -> Destination {
+        let file_text = file.text().to_string();
+        let separator = if file_text.trim().is_empty() {
+            ""
+        } else if file_text.ends_with('\n') {
+            "\n"
+        } else {
+            "\n\n"
+        };
+        Destination {
+            file_id,
+            offset: file.range().end(),
+            indent: String::new(),
+            prefix: separator.to_string(),
+            suffix: "\n".to_string(),
+        }
+    }
+
+    /// The text to insert, and the offset of the item in it.
+    fn text(&self, item_text: &str) -> (String, TextUnit) {
+        let text = format!("{}{}{}", self.prefix, reindent(item_text, &self.indent), self.suffix);
+        (text, TextUnit::of_str(&self.prefix))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::helpers::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn test_move_item_to_module() {
+        check_assist(
+            move_item_to_module,
+            "
+const LIMIT: u32 = 92;
+
+fn <|>check(x: u32) -> bool {
+    x < LIMIT
+}
+
+mod checks {
+    fn other() {}
+}
+
+fn main() {
+    check(1);
+}",
+            "
+const LIMIT: u32 = 92;
+
+mod checks {
+    fn other() {}
+
+    <|>pub(super) fn check(x: u32) -> bool {
+        x < super::LIMIT
+    }
+}
+
+fn main() {
+    checks::check(1);
+}",
+        );
+    }
+
+    #[test]
+    fn test_move_item_to_module_fixes_imports() {
+        check_assist(
+            move_item_to_module,
+            "
+mod a {
+    pub struct <|>S;
+
+    pub mod b {}
+}
+
+use crate::a::S;
+
+fn f(_: S, _: a::S) {}",
+            "
+mod a {
+    pub mod b {
+        <|>pub struct S;
+    }
+}
+
+use crate::a::b::S;
+
+fn f(_: S, _: a::b::S) {}",
+        );
+    }
+
+    #[test]
+    fn test_move_item_to_module_not_applicable() {
+        check_assist_not_applicable(move_item_to_module, "fn <|>foo() {}");
+        check_assist_not_applicable(
+            move_item_to_module,
+            "
+mod m {}
+
+fn main() {
+    let <|>x = 1;
+}",
+        );
+    }
+}
@@ -0,0 +1,135 @@
+use hir::{source_binder, db::HirDatabase};
+use ra_syntax::{
+    AstNode, TextRange,
+    ast::{self, NameOwner},
+    SyntaxKind::*,
+};
+
+use crate::{AssistCtx, Assist, utils::dedented_body};
+
+/// Turns `mod foo { ... }` into `mod foo;`, moving the items to a new file.
+pub(crate) fn move_module_to_file(mut ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let module = ctx.node_at_offset::<ast::Module>()?;
+    let item_list = module.item_list()?;
+    // Offered on the declaration only, not on everything inside the module.
+    if ctx.frange.range.start() > item_list.syntax().range().start() {
+        return None;
+    }
+    let name = module.name()?.text().to_string();
+    let parent = source_binder::module_from_child_node(
+        ctx.db,
+        ctx.frange.file_id,
+        module.syntax().parent()?,
+    )?;
+    let candidates = parent.child_file_candidates(ctx.db, &name);
+    let source_root = ctx.db.file_source_root(ctx.frange.file_id);
+    let files = &ctx.db.source_root(source_root).files;
+    if candidates.iter().any(|it| files.contains_key(it)) {
+        return None;
+    }
+    let path = candidates.into_iter().next()?;
+    let body_start = match item_list.syntax().prev_sibling() {
+        Some(ws) if ws.kind() == WHITESPACE => ws.range().start(),
+        _ => item_list.syntax().range().start(),
+    };
+
+    ctx.add_action("move module to file", |edit| {
+        edit.target(module.syntax().range());
+        edit.replace(TextRange::from_to(body_start, item_list.syntax().range().end()), ";");
+        edit.create_file(source_root, path, dedented_body(item_list.syntax()));
+    });
+
+    ctx.build()
+}
+
+#[cfg(test)]
+mod tests {
+    use hir::mock::MockDatabase;
+    use ra_db::FileRange;
+    use test_utils::extract_offset;
+
+    use crate::helpers::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn test_move_module_to_file() {
+        check_assist(
+            move_module_to_file,
+            "
+#[cfg(test)]
+mod <|>tests {
+    fn foo() {}
+
+    struct Bar;
+}
+
+fn main() {}",
+            "
+#[cfg(test)]
+mod <|>tests;
+
+fn main() {}",
+        );
+    }
+
+    #[test]
+    fn test_move_module_to_file_creates_file() {
+        let (offset, text) = extract_offset("mod <|>foo {\n    fn bar() {\n        92\n    }\n}");
+        let (db, _source_root, file_id) = MockDatabase::with_single_file(&text);
+        let frange = FileRange { file_id, range: TextRange::offset_len(offset, 0.into()) };
+        let (_, action) = crate::assists(&db, frange)
+            .into_iter()
+            .find(|(label, _)| label.label == "move module to file")
+            .expect("move module to file is not applicable");
+        assert_eq!(action.new_files.len(), 1);
+        assert_eq!(action.new_files[0].path.as_str(), "foo.rs");
+        assert_eq!(action.new_files[0].text, "fn bar() {\n    92\n}\n");
+    }
+
+    #[test]
+    fn test_move_nested_module_to_file() {
+        let (db, position) = MockDatabase::with_position(
+            "
+            //- /lib.rs
+            mod foo;
+            //- /foo.rs
+            mod bar {
+                mod <|>baz {}
+            }
+            ",
+        );
+        let frange = FileRange {
+            file_id: position.file_id,
+            range: TextRange::offset_len(position.offset, 0.into()),
+        };
+        let (_, action) = crate::assists(&db, frange)
+            .into_iter()
+            .find(|(label, _)| label.label == "move module to file")
+            .expect("move module to file is not applicable");
+        assert_eq!(action.new_files[0].path.as_str(), "foo/bar/baz.rs");
+    }
+
+    #[test]
+    fn test_move_module_to_file_not_applicable() {
+        check_assist_not_applicable(move_module_to_file, "mod foo;<|>");
+        check_assist_not_applicable(move_module_to_file, "mod foo { fn <|>bar() {} }");
+    }
+
+    #[test]
+    fn test_move_module_to_file_not_applicable_to_existing_file() {
+        let (db, position) = MockDatabase::with_position(
+            "
+            //- /lib.rs
+            mod <|>foo {}
+            //- /foo/mod.rs
+            ",
+        );
+        let frange = FileRange {
+            file_id: position.file_id,
+            range: TextRange::offset_len(position.offset, 0.into()),
+        };
+        let assists = crate::assists(&db, frange);
+        assert!(assists.iter().all(|(label, _)| label.label != "move module to file"));
+    }
+}
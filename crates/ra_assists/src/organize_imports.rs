@@ -0,0 +1,629 @@
+use std::collections::BTreeMap;
+
+use hir::{ModuleDef, Path, PathKind, PerNs, Resolution, Resolver, source_binder, db::HirDatabase};
+use ra_db::FileId;
+use ra_syntax::{AstNode, SyntaxNode, TextRange, ast, SyntaxKind::*};
+use ra_fmt::leading_indent;
+
+use crate::{AssistCtx, Assist};
+
+/// Rewrites all the `use` items of the module or block around the cursor:
+/// the imports are merged into one tree per crate, sorted, grouped into std,
+/// external and local ones, and the unused ones are removed. The result
+/// replaces the first block of imports, and the other blocks are removed.
+pub(crate) fn organize_imports(mut ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let use_item = ctx.node_at_offset::<ast::UseItem>()?;
+    let container = use_item.syntax().parent()?;
+    let runs = import_runs(container);
+    let ranges = runs
+        .iter()
+        .map(|run| {
+            Some(TextRange::from_to(run.first()?.range().start(), run.last()?.range().end()))
+        })
+        .collect::<Option<Vec<_>>>()?;
+    let range = *ranges.first()?;
+    let module =
+        source_binder::module_from_child_node(ctx.db, ctx.frange.file_id, use_item.syntax())?;
+    let resolver = module.resolver(ctx.db);
+    let uses = uses(ctx.db, ctx.frange.file_id, container, &ranges);
+    // The files of child modules may use the imports through `use super::*`,
+    // and aren't scanned.
+    let has_file_children =
+        container.descendants().filter_map(ast::Module::cast).any(|it| it.item_list().is_none());
+    let indent = leading_indent(runs[0][0]).unwrap_or("");
+
+    let mut groups: [ImportGroup; 3] = Default::default();
+    for import in runs.iter().flat_map(|run| attach_comments(run)) {
+        let key = import.item.use_tree()?.syntax().text().to_string();
+        let group = &mut groups[group_of(&key)];
+        let paths = if import.is_mergeable() { expand_use_item(import.item) } else { None };
+        let paths = match paths {
+            Some(it) => it,
+            None => {
+                group.verbatim.push((key, import.render(indent)));
+                continue;
+            }
+        };
+        // Commented imports are kept as is, so that the comment stays next
+        // to them, and the same paths are not imported again by the tree.
+        if import.has_comments() {
+            group.verbatim.push((key, import.render(indent)));
+            group.commented.extend(paths);
+            continue;
+        }
+        for (segments, alias, is_glob) in paths {
+            let name = alias.as_ref().or(segments.last()).map(String::as_str).unwrap_or("");
+            let is_used = has_file_children || is_glob || name == "_" || {
+                let imported = resolve_import(ctx.db, &resolver, import.item, &segments);
+                // Imports which don't resolve, like traits from crates outside
+                // of the graph, may be used without being named.
+                imported.is_none()
+                    || uses.iter().any(|(it, res)| it == name && refers_to(res, &imported))
+                    || is_trait(&imported)
+            };
+            if is_used {
+                group.tree.insert(&segments, alias, is_glob);
+            }
+        }
+    }
+    for group in groups.iter_mut() {
+        for (segments, alias, is_glob) in group.commented.iter() {
+            group.tree.remove(segments, alias, *is_glob);
+        }
+    }
+
+    let new_text = groups
+        .iter()
+        .map(|group| group.render().join(&format!("\n{}", indent)))
+        .filter(|it| !it.is_empty())
+        .collect::<Vec<_>>()
+        .join(&format!("\n\n{}", indent));
+    let old_text = container.text().slice(range).to_string();
+    if new_text == old_text && runs.len() == 1 {
+        return None;
+    }
+
+    ctx.add_action("organize imports", move |edit| {
+        edit.target(TextRange::from_to(range.start(), ranges.last().unwrap().end()));
+        if new_text.is_empty() {
+            edit.delete(removal_range(&runs[0]));
+        } else {
+            edit.replace(range, new_text);
+        }
+        for run in runs[1..].iter() {
+            edit.delete(removal_range(run));
+        }
+        edit.set_cursor(range.start());
+    });
+
+    ctx.build()
+}
+
+/// The range to delete to remove `run`, with the whitespace after it.
+fn removal_range(run: &[&SyntaxNode]) -> TextRange {
+    let (first, last) = (run[0], run[run.len() - 1]);
+    match last.next_sibling() {
+        Some(ws) if ws.kind() == WHITESPACE => {
+            TextRange::from_to(first.range().start(), ws.range().end())
+        }
+        _ => TextRange::from_to(first.range().start(), last.range().end()),
+    }
+}
+
+/// The blocks of `use` items of `container`, with the comments between them.
+fn import_runs(container: &SyntaxNode) -> Vec<Vec<&SyntaxNode>> {
+    let mut runs = vec![Vec::new()];
+    for child in container.children() {
+        let is_part_of_run = match child.kind() {
+            USE_ITEM | WHITESPACE => true,
+            COMMENT => !is_inner_doc_comment(child),
+            _ => false,
+        };
+        if is_part_of_run {
+            runs.last_mut().unwrap().push(child);
+        } else {
+            runs.push(Vec::new());
+        }
+    }
+    runs.into_iter()
+        .filter(|run| run.iter().any(|it| it.kind() == USE_ITEM))
+        .map(trim_run)
+        .collect()
+}
+
+fn trim_run(mut run: Vec<&SyntaxNode>) -> Vec<&SyntaxNode> {
+    while let Some(last) = run.last() {
+        if last.kind() == USE_ITEM || is_trailing_comment(last) {
+            break;
+        }
+        run.pop();
+    }
+    // Comments separated from the imports by a blank line, like a license
+    // header, are not attached to them.
+    while let Some(first) = run.first() {
+        let is_detached = match first.kind() {
+            WHITESPACE => true,
+            COMMENT => first
+                .next_sibling()
+                .map_or(false, |it| it.leaf_text().map_or(false, |it| it.contains("\n\n"))),
+            _ => false,
+        };
+        if !is_detached {
+            break;
+        }
+        run.remove(0);
+    }
+    run
+}
+
+fn is_inner_doc_comment(comment: &SyntaxNode) -> bool {
+    comment.leaf_text().map_or(false, |it| it.starts_with("//!") || it.starts_with("/*!"))
+}
+
+/// Whether the comment follows an import on the same line.
+fn is_trailing_comment(node: &SyntaxNode) -> bool {
+    if node.kind() != COMMENT {
+        return false;
+    }
+    let mut prev = node.prev_sibling();
+    if let Some(ws) = prev.filter(|it| it.kind() == WHITESPACE) {
+        if ws.leaf_text().map_or(true, |it| it.contains('\n')) {
+            return false;
+        }
+        prev = ws.prev_sibling();
+    }
+    prev.map(|it| it.kind()) == Some(USE_ITEM)
+}
+
+struct ImportWithComments<'a> {
+    item: &'a ast::UseItem,
+    leading_comments: Vec<&'a SyntaxNode>,
+    trailing_comment: Option<&'a SyntaxNode>,
+}
+
+impl<'a> ImportWithComments<'a> {
+    /// Imports which are neither re-exported nor have attributes can be
+    /// merged with others, unless they are commented.
+    fn is_mergeable(&self) -> bool {
+        self.item.syntax().children().all(|it| it.kind() != VISIBILITY && it.kind() != ATTR)
+    }
+
+    fn has_comments(&self) -> bool {
+        !self.leading_comments.is_empty() || self.trailing_comment.is_some()
+    }
+
+    fn render(&self, indent: &str) -> String {
+        let mut buf = String::new();
+        for comment in self.leading_comments.iter() {
+            buf.push_str(&comment.text().to_string());
+            buf.push('\n');
+            buf.push_str(indent);
+        }
+        buf.push_str(&self.item.syntax().text().to_string());
+        if let Some(comment) = self.trailing_comment {
+            buf.push(' ');
+            buf.push_str(&comment.text().to_string());
+        }
+        buf
+    }
+}
+
+fn attach_comments<'a>(run: &[&'a SyntaxNode]) -> Vec<ImportWithComments<'a>> {
+    let mut res: Vec<ImportWithComments> = Vec::new();
+    let mut comments = Vec::new();
+    for &node in run {
+        if is_trailing_comment(node) {
+            if let Some(last) = res.last_mut() {
+                last.trailing_comment = Some(node);
+            }
+        } else if node.kind() == COMMENT {
+            comments.push(node);
+        } else if let Some(item) = ast::UseItem::cast(node) {
+            let leading_comments = std::mem::replace(&mut comments, Vec::new());
+            res.push(ImportWithComments { item, leading_comments, trailing_comment: None });
+        }
+    }
+    res
+}
+
+/// The names which paths outside of the imports start with, and what they
+/// resolve to there. The identifiers in macro calls can't be resolved.
+fn uses(
+    db: &impl HirDatabase,
+    file_id: FileId,
+    container: &SyntaxNode,
+    imports: &[TextRange],
+) -> Vec<(String, PerNs<Resolution>)> {
+    container
+        .descendants()
+        .filter(|it| !imports.iter().any(|range| it.range().is_subrange(range)))
+        .filter_map(|it| match it.kind() {
+            PATH => {
+                let path = ast::Path::cast(it)?;
+                if path.qualifier().is_some() {
+                    return None;
+                }
+                let name = path.segment()?.name_ref()?.text().to_string();
+                let resolution = match Path::from_ast(path)?.as_ident() {
+                    Some(ident) => {
+                        source_binder::resolver_for_node(db, file_id, it).resolve_name(ident)
+                    }
+                    None => PerNs::none(),
+                };
+                Some((name, resolution))
+            }
+            IDENT if it.parent().map(|it| it.kind()) == Some(TOKEN_TREE) => {
+                Some((it.text().to_string(), PerNs::none()))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Whether a use of a name resolving to `resolution` refers to what an
+/// import resolves to. Uses which can't be resolved are assumed to refer to
+/// the import, as the import is likely unresolved too.
+fn refers_to(resolution: &PerNs<Resolution>, imported: &PerNs<Resolution>) -> bool {
+    if resolution.is_none() {
+        return true;
+    }
+    let types = resolution.types.is_some() && resolution.types == imported.types;
+    let values = resolution.values.is_some() && resolution.values == imported.values;
+    types || values
+}
+
+/// Traits can be used by calling their methods, without naming them.
+fn is_trait(imported: &PerNs<Resolution>) -> bool {
+    match imported.types {
+        Some(Resolution::Def(ModuleDef::Trait(_))) => true,
+        _ => false,
+    }
+}
+
+fn resolve_import(
+    db: &impl HirDatabase,
+    resolver: &Resolver,
+    item: &ast::UseItem,
+    segments: &[String],
+) -> PerNs<Resolution> {
+    let mut res = PerNs::none();
+    Path::expand_use_item(item, |path, _, _| {
+        if res.is_none() && path_segments(&path) == segments {
+            res = resolver.resolve_path(db, &path);
+        }
+    });
+    res
+}
+
+/// An imported path, with its alias and whether it is a glob.
+type ImportedPath = (Vec<String>, Option<String>, bool);
+
+/// The imported paths, or `None` if some of them can't be represented.
+fn expand_use_item(item: &ast::UseItem) -> Option<Vec<ImportedPath>> {
+    let mut paths = Vec::new();
+    Path::expand_use_item(item, |path, segment, alias| {
+        paths.push((path_segments(&path), alias.map(|it| it.to_string()), segment.is_none()))
+    });
+    let leaves = item
+        .syntax()
+        .descendants()
+        .filter_map(ast::UseTree::cast)
+        .filter(|it| it.use_tree_list().is_none())
+        .count();
+    if paths.len() != leaves || paths.iter().any(|(segments, _, _)| segments.is_empty()) {
+        return None;
+    }
+    Some(paths)
+}
+
+fn path_segments(path: &Path) -> Vec<String> {
+    let mut segments = path.segments.iter().map(|it| it.name.to_string()).collect::<Vec<_>>();
+    let prefix = match path.kind {
+        PathKind::Plain => return segments,
+        PathKind::Self_ => "self",
+        PathKind::Super => "super",
+        PathKind::Crate => "crate",
+        PathKind::Abs => {
+            if let Some(first) = segments.first_mut() {
+                first.insert_str(0, "::");
+            }
+            return segments;
+        }
+    };
+    segments.insert(0, prefix.to_string());
+    segments
+}
+
+/// Imports from std come first, then the ones from other crates, then the
+/// ones from the current crate.
+fn group_of(path: &str) -> usize {
+    let first_segment = path.trim_start_matches("::").split("::").next().unwrap_or("");
+    match first_segment.trim() {
+        "std" | "core" | "alloc" => 0,
+        "crate" | "self" | "super" => 2,
+        _ => 1,
+    }
+}
+
+#[derive(Default)]
+struct ImportGroup {
+    tree: ImportTree,
+    /// Imports which are kept as is, with their sort keys.
+    verbatim: Vec<(String, String)>,
+    /// The paths imported by the commented imports among them.
+    commented: Vec<ImportedPath>,
+}
+
+impl ImportGroup {
+    fn render(&self) -> Vec<String> {
+        let mut items = self
+            .tree
+            .children
+            .iter()
+            .map(|(name, child)| {
+                let tree = child.render(name);
+                (tree.clone(), format!("use {};", tree))
+            })
+            .chain(self.verbatim.iter().cloned())
+            .collect::<Vec<_>>();
+        items.sort_by(|(key1, _), (key2, _)| key1.cmp(key2));
+        items.into_iter().map(|(_, text)| text).collect()
+    }
+}
+
+#[derive(Default)]
+struct ImportTree {
+    /// The aliases under which the path to this node is imported, `None` for
+    /// importing it under its own name.
+    imports: Vec<Option<String>>,
+    has_glob: bool,
+    children: BTreeMap<String, ImportTree>,
+}
+
+impl ImportTree {
+    fn insert(&mut self, segments: &[String], alias: Option<String>, is_glob: bool) {
+        match segments.split_first() {
+            Some((first, rest)) => {
+                self.children.entry(first.clone()).or_default().insert(rest, alias, is_glob)
+            }
+            None if is_glob => self.has_glob = true,
+            None => {
+                if !self.imports.contains(&alias) {
+                    self.imports.push(alias);
+                }
+            }
+        }
+    }
+
+    /// Removes an import, and the nodes left without imports.
+    fn remove(&mut self, segments: &[String], alias: &Option<String>, is_glob: bool) {
+        match segments.split_first() {
+            Some((first, rest)) => {
+                let is_empty = match self.children.get_mut(first) {
+                    Some(child) => {
+                        child.remove(rest, alias, is_glob);
+                        child.is_empty()
+                    }
+                    None => false,
+                };
+                if is_empty {
+                    self.children.remove(first);
+                }
+            }
+            None if is_glob => self.has_glob = false,
+            None => self.imports.retain(|it| it != alias),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.imports.is_empty() && !self.has_glob && self.children.is_empty()
+    }
+
+    fn render(&self, name: &str) -> String {
+        let mut imports = self.imports.clone();
+        imports.sort();
+        if imports.len() == 1 && self.children.is_empty() && !self.has_glob {
+            return match &imports[0] {
+                Some(alias) => format!("{} as {}", name, alias),
+                None => name.to_string(),
+            };
+        }
+        let mut entries = imports
+            .iter()
+            .map(|alias| match alias {
+                Some(alias) => format!("self as {}", alias),
+                None => "self".to_string(),
+            })
+            .collect::<Vec<_>>();
+        entries.extend(self.children.iter().map(|(name, child)| child.render(name)));
+        if self.has_glob {
+            entries.push("*".to_string());
+        }
+        if entries.len() == 1 {
+            format!("{}::{}", name, entries[0])
+        } else {
+            format!("{}::{{{}}}", name, entries.join(", "))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::helpers::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn organize_imports_merges_sorts_and_removes_unused() {
+        check_assist(
+            organize_imports,
+            "
+mod foo {
+    pub struct Bar;
+    pub struct Baz;
+    pub struct Qux;
+}
+
+use crate::foo::Bar;
+use std::sync::Arc;
+use itertools::Itertools;<|>
+use std::fmt;
+use crate::foo::Qux;
+use std::collections::HashMap;
+use crate::foo::Baz;
+
+fn main() {
+    let x: Arc<Bar> = fmt::foo();
+    Baz::new();
+}",
+            "
+mod foo {
+    pub struct Bar;
+    pub struct Baz;
+    pub struct Qux;
+}
+
+<|>use std::{collections::HashMap, fmt, sync::Arc};
+
+use itertools::Itertools;
+
+use crate::foo::{Bar, Baz};
+
+fn main() {
+    let x: Arc<Bar> = fmt::foo();
+    Baz::new();
+}",
+        );
+    }
+
+    #[test]
+    fn organize_imports_keeps_comments_and_reexports() {
+        check_assist(
+            organize_imports,
+            "
+// The parser.
+use crate::parser::Parser;
+pub use crate::ast::Node;
+use crate::lexer::Lexer;<|>
+use crate::lexer::Lexer; // Twice.
+use crate::lexer::Token;
+
+fn f(_: Parser, _: Lexer, _: Node, _: Token) {}",
+            "
+<|>pub use crate::ast::Node;
+use crate::lexer::Lexer; // Twice.
+use crate::lexer::Token;
+// The parser.
+use crate::parser::Parser;
+
+fn f(_: Parser, _: Lexer, _: Node, _: Token) {}",
+        );
+    }
+
+    #[test]
+    fn organize_imports_removes_shadowed_imports() {
+        check_assist(
+            organize_imports,
+            "
+mod foo {
+    pub fn bar() {}
+    pub fn baz() {}
+}
+
+use crate::foo::bar;<|>
+use crate::foo::baz;
+
+fn main() {
+    let bar = 1;
+    bar;
+    baz();
+}",
+            "
+mod foo {
+    pub fn bar() {}
+    pub fn baz() {}
+}
+
+<|>use crate::foo::baz;
+
+fn main() {
+    let bar = 1;
+    bar;
+    baz();
+}",
+        );
+    }
+
+    #[test]
+    fn organize_imports_keeps_imports_of_modules_with_files() {
+        check_assist(
+            organize_imports,
+            "
+mod foo {
+    pub fn bar() {}
+    pub fn baz() {}
+}
+mod tests;
+
+use crate::foo::baz;<|>
+use crate::foo::bar;",
+            "
+mod foo {
+    pub fn bar() {}
+    pub fn baz() {}
+}
+mod tests;
+
+<|>use crate::foo::{bar, baz};",
+        );
+    }
+
+    #[test]
+    fn organize_imports_gathers_all_imports_of_the_module() {
+        check_assist(
+            organize_imports,
+            "
+use std::fmt;<|>
+
+mod foo {
+    pub struct Bar;
+    pub struct Baz;
+}
+
+use crate::foo::Baz;
+use std::sync::Arc;
+
+fn f(_: Arc<Baz>) -> fmt::Result {}
+
+use crate::foo::Bar;
+
+fn g(_: Bar) {}",
+            "
+<|>use std::{fmt, sync::Arc};
+
+use crate::foo::{Bar, Baz};
+
+mod foo {
+    pub struct Bar;
+    pub struct Baz;
+}
+
+fn f(_: Arc<Baz>) -> fmt::Result {}
+
+fn g(_: Bar) {}",
+        );
+    }
+
+    #[test]
+    fn organize_imports_not_applicable_to_organized_imports() {
+        check_assist_not_applicable(
+            organize_imports,
+            "
+use std::{fmt, sync::Arc};<|>
+
+use crate::foo::Bar;
+
+fn f(_: Arc<Bar>) -> fmt::Result {}",
+        );
+    }
+}
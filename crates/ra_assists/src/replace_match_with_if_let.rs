@@ -0,0 +1,235 @@
+use hir::{source_binder, db::HirDatabase};
+use ra_syntax::{AstNode, ast, SyntaxKind::*};
+use ra_fmt::reindent;
+
+use crate::{
+    AssistCtx, Assist,
+    utils::{dedented_body, pat_bindings},
+};
+
+/// The reverse of `replace_if_let_with_match`: turns a match with two arms,
+/// one of which binds nothing, into an `if let` on the pattern of the other.
+pub(crate) fn replace_match_with_if_let(mut ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let match_expr = ctx.node_at_offset::<ast::MatchExpr>()?;
+    let arm_list = match_expr.match_arm_list()?;
+    if ctx.frange.range.start() > arm_list.syntax().range().start() {
+        return None;
+    }
+    let expr = match_expr.expr()?;
+    let arms = arm_list.arms().collect::<Vec<_>>();
+    if arms.len() != 2 || arms.iter().any(|it| it.guard().is_some() || it.pats().count() != 1) {
+        return None;
+    }
+    let function =
+        source_binder::function_from_child_node(ctx.db, ctx.frange.file_id, match_expr.syntax())?;
+    let binds_nothing =
+        |arm: &ast::MatchArm| arm.pats().all(|it| pat_bindings(ctx.db, function, it).is_empty());
+    let (then_arm, else_arm) = if binds_nothing(arms[1]) {
+        (arms[0], arms[1])
+    } else if binds_nothing(arms[0]) {
+        // A first arm matching everything makes the second one unreachable.
+        if arms[0].pats().any(|it| it.syntax().kind() == PLACEHOLDER_PAT) {
+            return None;
+        }
+        (arms[1], arms[0])
+    } else {
+        return None;
+    };
+    let pat = then_arm.pats().next()?;
+    let then_block = format_block(then_arm.expr()?)?;
+    let else_expr = else_arm.expr()?;
+    let else_block = if is_empty(else_expr) { None } else { Some(format_block(else_expr)?) };
+
+    ctx.add_action("replace with if let", |edit| {
+        let mut buf =
+            format!("if let {} = {} {}", pat.syntax().text(), expr.syntax().text(), then_block);
+        if let Some(else_block) = else_block {
+            buf.push_str(" else ");
+            buf.push_str(&else_block);
+        }
+        edit.target(match_expr.syntax().range());
+        edit.replace_node_and_indent(match_expr.syntax(), buf);
+        edit.set_cursor(match_expr.syntax().range().start());
+    });
+
+    ctx.build()
+}
+
+fn format_block(expr: &ast::Expr) -> Option<String> {
+    let body = match expr.kind() {
+        ast::ExprKind::BlockExpr(it) => dedented_body(it.block()?.syntax()),
+        _ => expr.syntax().text().to_string(),
+    };
+    if body.trim().is_empty() {
+        return Some("{}".to_string());
+    }
+    Some(format!("{{\n    {}\n}}", reindent(body.trim_end(), "    ")))
+}
+
+/// Whether `expr` is `()` or `{}`, which don't need an `else`.
+fn is_empty(expr: &ast::Expr) -> bool {
+    match expr.kind() {
+        ast::ExprKind::TupleExpr(it) => it.exprs().next().is_none(),
+        ast::ExprKind::BlockExpr(it) => it
+            .block()
+            .map_or(false, |block| block.statements().next().is_none() && block.expr().is_none()),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    use super::*;
+
+    #[test]
+    fn test_replace_match_with_if_let() {
+        check_assist(
+            replace_match_with_if_let,
+            "
+enum Option<T> { Some(T), None }
+use Option::*;
+
+fn f(x: Option<u32>) {
+    <|>match x {
+        Some(v) => foo(v),
+        None => bar(),
+    }
+}",
+            "
+enum Option<T> { Some(T), None }
+use Option::*;
+
+fn f(x: Option<u32>) {
+    <|>if let Some(v) = x {
+        foo(v)
+    } else {
+        bar()
+    }
+}",
+        );
+    }
+
+    #[test]
+    fn test_replace_match_with_if_let_omits_empty_else() {
+        check_assist(
+            replace_match_with_if_let,
+            "
+enum Option<T> { Some(T), None }
+use Option::*;
+
+fn f(x: Option<u32>) {
+    <|>match x {
+        Some(v) => {
+            foo(v);
+            bar(v);
+        }
+        _ => {}
+    }
+}",
+            "
+enum Option<T> { Some(T), None }
+use Option::*;
+
+fn f(x: Option<u32>) {
+    <|>if let Some(v) = x {
+        foo(v);
+        bar(v);
+    }
+}",
+        );
+    }
+
+    #[test]
+    fn test_replace_match_with_if_let_swaps_arms() {
+        check_assist(
+            replace_match_with_if_let,
+            "
+enum Option<T> { Some(T), None }
+use Option::*;
+
+fn f(x: Option<u32>) {
+    <|>match x {
+        None => bar(),
+        Some(v) => foo(v),
+    }
+}",
+            "
+enum Option<T> { Some(T), None }
+use Option::*;
+
+fn f(x: Option<u32>) {
+    <|>if let Some(v) = x {
+        foo(v)
+    } else {
+        bar()
+    }
+}",
+        );
+    }
+
+    #[test]
+    fn test_replace_match_with_if_let_target() {
+        check_assist_target(
+            replace_match_with_if_let,
+            "
+enum Option<T> { Some(T), None }
+use Option::*;
+
+fn f(x: Option<u32>) {
+    match<|> x {
+        Some(v) => foo(v),
+        None => (),
+    }
+}",
+            "match x {
+        Some(v) => foo(v),
+        None => (),
+    }",
+        );
+    }
+
+    #[test]
+    fn test_replace_match_with_if_let_not_applicable() {
+        check_assist_not_applicable(
+            replace_match_with_if_let,
+            "
+enum Option<T> { Some(T), None }
+use Option::*;
+
+fn f(x: Option<u32>) {
+    <|>match x {
+        _ => bar(),
+        Some(v) => foo(v),
+    }
+}",
+        );
+        check_assist_not_applicable(
+            replace_match_with_if_let,
+            "
+enum Result<T, E> { Ok(T), Err(E) }
+use Result::*;
+
+fn f(x: Result<u32, u32>) {
+    <|>match x {
+        Ok(v) => foo(v),
+        Err(e) => bar(e),
+    }
+}",
+        );
+        check_assist_not_applicable(
+            replace_match_with_if_let,
+            "
+enum E { A, B, C }
+
+fn f(e: E) {
+    <|>match e {
+        E::A => foo(),
+        E::B => bar(),
+        E::C => baz(),
+    }
+}",
+        );
+    }
+}
@@ -0,0 +1,309 @@
+//! Helpers shared by several assists.
+
+use join_to_string::join;
+use hir::{
+    AdtDef, FieldSource, Function, ImplItem, Module, Struct, StructField, Ty, source_binder,
+    db::HirDatabase,
+};
+use ra_db::FileId;
+use ra_syntax::{
+    AstNode, SyntaxNode, TextRange, TextUnit, TreeArc,
+    ast::{self, AstToken, NameOwner, TypeParamsOwner, VisibilityOwner},
+    SyntaxKind::*,
+};
+use ra_fmt::{leading_indent, reindent};
+use ra_text_edit::TextEditBuilder;
+
+/// The text between the braces of `block`, without the common indentation
+/// of its lines.
+pub(crate) fn dedented_body(block: &SyntaxNode) -> String {
+    let text = block.text().to_string();
+    let body = &text[1..text.len() - 1];
+    let lines = body.lines().map(str::trim_end).collect::<Vec<_>>();
+    let indent = lines
+        .iter()
+        .filter(|line| !line.is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+    let first = lines.iter().position(|line| !line.is_empty()).unwrap_or(lines.len());
+    let last = lines.iter().rposition(|line| !line.is_empty()).map_or(first, |it| it + 1);
+    let mut buf = String::new();
+    for line in lines[first..last].iter() {
+        if !line.is_empty() {
+            buf.push_str(&line[indent..]);
+        }
+        buf.push('\n');
+    }
+    buf
+}
+
+/// Whether evaluating `expr` has no side effects, so that it can be evaluated
+/// several times, or before or after other expressions.
+pub(crate) fn is_pure(expr: &ast::Expr) -> bool {
+    match expr.kind() {
+        ast::ExprKind::Literal(_) | ast::ExprKind::PathExpr(_) => true,
+        ast::ExprKind::FieldExpr(it) => it.expr().map_or(false, is_pure),
+        ast::ExprKind::RefExpr(it) => it.expr().map_or(false, is_pure),
+        ast::ExprKind::ParenExpr(it) => it.expr().map_or(false, is_pure),
+        ast::ExprKind::PrefixExpr(it) => it.expr().map_or(false, is_pure),
+        ast::ExprKind::BinExpr(it) => match it.op() {
+            None => false,
+            Some(op) if op.is_assignment() => false,
+            Some(_) => it.lhs().map_or(false, is_pure) && it.rhs().map_or(false, is_pure),
+        },
+        _ => false,
+    }
+}
+
+/// The text of the negation of the boolean `expr`, which drops the `!` of a
+/// negated expression and flips `==` and `!=`.
+pub(crate) fn negate(expr: &ast::Expr) -> String {
+    match expr.kind() {
+        ast::ExprKind::PrefixExpr(it) if it.op() == Some(ast::PrefixOp::Not) => {
+            if let Some(inner) = it.expr() {
+                let inner = match inner.kind() {
+                    ast::ExprKind::ParenExpr(paren) => paren.expr().unwrap_or(inner),
+                    _ => inner,
+                };
+                return inner.syntax().text().to_string();
+            }
+        }
+        ast::ExprKind::BinExpr(it) => {
+            let op = it.syntax().children().find(|it| it.kind() == EQEQ || it.kind() == NEQ);
+            if let Some(op) = op {
+                let text = it.syntax().text().to_string();
+                let range = op.range() - it.syntax().range().start();
+                let negated_op = if op.kind() == EQEQ { "!=" } else { "==" };
+                let (start, end) = (range.start().to_usize(), range.end().to_usize());
+                return format!("{}{}{}", &text[..start], negated_op, &text[end..]);
+            }
+        }
+        _ => (),
+    }
+    let text = expr.syntax().text().to_string();
+    match expr.syntax().kind() {
+        BIN_EXPR | RANGE_EXPR | CAST_EXPR | LAMBDA_EXPR | RETURN_EXPR | BREAK_EXPR => {
+            format!("!({})", text)
+        }
+        _ => format!("!{}", text),
+    }
+}
+
+/// The names and the types of the variables bound by `pat`, sorted by name.
+/// Identifiers naming unit structs or variants, like `None`, don't bind
+/// anything.
+pub(crate) fn pat_bindings(
+    db: &impl HirDatabase,
+    function: Function,
+    pat: &ast::Pat,
+) -> Vec<(String, Ty)> {
+    let infer = function.infer(db);
+    let syntax_mapping = function.body_syntax_mapping(db);
+    let mut res = pat
+        .syntax()
+        .descendants()
+        .filter_map(ast::BindPat::cast)
+        .filter_map(|bind_pat| {
+            let name = bind_pat.name()?.text().to_string();
+            let ty = match syntax_mapping.node_pat(ast::Pat::cast(bind_pat.syntax())?) {
+                Some(pat) if infer.variant_resolution(pat).is_some() => return None,
+                Some(pat) => infer[pat].clone(),
+                None => Ty::Unknown,
+            };
+            Some((name, ty))
+        })
+        .collect::<Vec<_>>();
+    res.sort_by(|(a, _), (b, _)| a.cmp(b));
+    res
+}
+
+/// Computes the edit appending `items` to the end of the item list of
+/// `impl_block`, separated by blank lines. Returns the range to replace, the
+/// text to replace it with and the offset of the first item after the edit.
+pub(crate) fn append_impl_items(
+    impl_block: &ast::ImplBlock,
+    items: &[String],
+) -> Option<(TextRange, String, TextUnit)> {
+    let item_list = impl_block.item_list()?;
+    let indent = leading_indent(impl_block.syntax()).unwrap_or("");
+    let item_indent = format!("{}    ", indent);
+    let last_item = item_list.impl_items().last().map(|it| it.syntax());
+    let anchor = match last_item {
+        Some(it) => it,
+        None => item_list.syntax().first_child().filter(|it| it.kind() == L_CURLY)?,
+    };
+    let r_curly = item_list.syntax().last_child().filter(|it| it.kind() == R_CURLY)?;
+    let start = anchor.range().end();
+    let mut buf = String::new();
+    let mut cursor = None;
+    for item in items {
+        if last_item.is_some() || cursor.is_some() {
+            buf.push('\n');
+        }
+        buf.push('\n');
+        buf.push_str(&item_indent);
+        cursor.get_or_insert(start + TextUnit::of_str(&buf));
+        buf.push_str(&reindent(item, &item_indent));
+    }
+    let gap = TextRange::from_to(start, r_curly.range().start());
+    // Only whitespace before the closing brace is replaced, comments stay.
+    let only_whitespace = item_list
+        .syntax()
+        .children()
+        .filter(|it| it.range().is_subrange(&gap))
+        .all(|it| it.kind() == WHITESPACE);
+    if only_whitespace {
+        buf.push('\n');
+        buf.push_str(indent);
+        Some((gap, buf, cursor?))
+    } else {
+        Some((TextRange::offset_len(start, 0.into()), buf, cursor?))
+    }
+}
+
+/// Returns the text of the node with those of the `edits` which fall into it
+/// applied.
+pub(crate) fn render_with_edits(node: &SyntaxNode, edits: &[(TextRange, String)]) -> String {
+    let range = node.range();
+    let mut builder = TextEditBuilder::default();
+    for (edit_range, replace_with) in edits.iter().filter(|(it, _)| it.is_subrange(&range)) {
+        builder.replace(*edit_range - range.start(), replace_with.clone());
+    }
+    builder.finish().apply(&node.text().to_string())
+}
+
+/// The files of the crate of `file_id` and of the crates depending on it.
+pub(crate) fn workspace_files(db: &impl HirDatabase, file_id: FileId) -> Vec<FileId> {
+    let mut res = vec![file_id];
+    let krate = match source_binder::module_from_file_id(db, file_id).and_then(|it| it.krate(db)) {
+        Some(it) => it,
+        None => return res,
+    };
+    let mut modules = std::iter::once(krate)
+        .chain(krate.reverse_dependencies(db))
+        .filter_map(|it| it.root_module(db))
+        .collect::<Vec<_>>();
+    while let Some(module) = modules.pop() {
+        let (file_id, _) = module.definition_source(db);
+        if !res.contains(&file_id) {
+            res.push(file_id);
+        }
+        modules.extend(module.children(db));
+    }
+    res
+}
+
+/// The struct together with its inherent impls from the current file.
+pub(crate) struct StructImpls {
+    pub(crate) def: Struct,
+    /// The first of the impls, the new methods go there.
+    pub(crate) impl_block: Option<TreeArc<ast::ImplBlock>>,
+    method_names: Vec<String>,
+}
+
+impl StructImpls {
+    pub(crate) fn new(
+        db: &impl HirDatabase,
+        file_id: FileId,
+        strukt: &ast::StructDef,
+    ) -> Option<StructImpls> {
+        let module = source_binder::module_from_child_node(db, file_id, strukt.syntax())?;
+        let def = source_binder::struct_from_module(db, module, strukt);
+        let mut impl_block = None;
+        let mut method_names = Vec::new();
+        for block in module.impl_blocks(db) {
+            if block.target_trait_ref().is_some() {
+                continue;
+            }
+            match block.target_ty(db) {
+                Ty::Adt { def_id: AdtDef::Struct(it), .. } if it == def => (),
+                _ => continue,
+            }
+            method_names.extend(block.items().iter().filter_map(|item| match item {
+                ImplItem::Method(it) => Some(it.name(db).to_string()),
+                _ => None,
+            }));
+            if impl_block.is_none() {
+                impl_block = Some(module.impl_source(db, block.id()));
+            }
+        }
+        Some(StructImpls { def, impl_block, method_names })
+    }
+
+    pub(crate) fn has_method(&self, name: &str) -> bool {
+        self.method_names.iter().any(|it| it == name)
+    }
+}
+
+/// The type of `field` as written in `module`, the module of its struct.
+/// Falls back to the source of the type for what the rendering can't express,
+/// like lifetimes or the length of arrays.
+pub(crate) fn field_ty(
+    db: &impl HirDatabase,
+    module: Module,
+    field: StructField,
+) -> Option<String> {
+    let source = field.source(db).1;
+    let type_ref = match &source {
+        FieldSource::Named(it) => it.type_ref()?,
+        FieldSource::Pos(it) => it.type_ref()?,
+    };
+    let source_text = type_ref.syntax().text().to_string();
+    if type_ref.syntax().descendants().any(|it| it.kind() == LIFETIME) {
+        return Some(source_text);
+    }
+    Some(field.ty(db).to_source(db, module).unwrap_or(source_text))
+}
+
+/// The visibility of the struct, applied to the methods generated for it.
+pub(crate) fn visibility(strukt: &ast::StructDef) -> String {
+    match strukt.visibility() {
+        Some(it) => format!("{} ", it.syntax().text()),
+        None => String::new(),
+    }
+}
+
+/// Computes the edit adding `method` to the end of `impl_block`, or to a new
+/// impl right after the struct. Returns the range to replace, the text to
+/// replace it with and the offset of the method after the edit.
+pub(crate) fn method_insertion(
+    strukt: &ast::StructDef,
+    impl_block: Option<&ast::ImplBlock>,
+    method: &str,
+) -> Option<(TextRange, String, TextUnit)> {
+    let impl_block = match impl_block {
+        Some(it) => it,
+        None => {
+            let indent = leading_indent(strukt.syntax()).unwrap_or("");
+            let start = strukt.syntax().range().end();
+            let mut buf = format!("\n\n{}impl", indent);
+            let type_params = strukt.type_param_list();
+            if let Some(type_params) = type_params {
+                type_params.syntax().text().push_to(&mut buf);
+            }
+            buf.push(' ');
+            buf.push_str(strukt.name()?.text().as_str());
+            if let Some(type_params) = type_params {
+                let lifetime_params = type_params
+                    .lifetime_params()
+                    .filter_map(|it| it.lifetime())
+                    .map(|it| it.text());
+                let type_params =
+                    type_params.type_params().filter_map(|it| it.name()).map(|it| it.text());
+                join(lifetime_params.chain(type_params)).surround_with("<", ">").to_buf(&mut buf);
+            }
+            let item_indent = format!("{}    ", indent);
+            buf.push_str(" {\n");
+            buf.push_str(&item_indent);
+            let cursor = start + TextUnit::of_str(&buf);
+            buf.push_str(&reindent(method, &item_indent));
+            buf.push('\n');
+            buf.push_str(indent);
+            buf.push('}');
+            return Some((TextRange::offset_len(start, 0.into()), buf, cursor));
+        }
+    };
+
+    append_impl_items(impl_block, &[method.to_string()])
+}
@@ -143,6 +143,13 @@ impl VariantData {
             _ => None,
         }
     }
+
+    pub(crate) fn is_tuple(&self) -> bool {
+        match &self.0 {
+            VariantDataInner::Tuple(_) => true,
+            _ => false,
+        }
+    }
 }
 
 impl VariantData {
@@ -189,6 +196,14 @@ impl VariantDef {
             VariantDef::EnumVariant(it) => it.field(db, name),
         }
     }
+
+    pub fn fields(self, db: &impl HirDatabase) -> Vec<StructField> {
+        match self {
+            VariantDef::Struct(it) => it.fields(db),
+            VariantDef::EnumVariant(it) => it.fields(db),
+        }
+    }
+
     pub(crate) fn variant_data(self, db: &impl PersistentHirDatabase) -> Arc<VariantData> {
         match self {
             VariantDef::Struct(it) => it.variant_data(db),
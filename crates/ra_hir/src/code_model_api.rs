@@ -9,9 +9,10 @@ use crate::{
     type_ref::TypeRef,
     nameres::{ModuleScope, lower::ImportId},
     HirDatabase, PersistentHirDatabase,
-    expr::{Body, BodySyntaxMapping},
-    ty::InferenceResult,
+    expr::{Body, BodySyntaxMapping, ExprId},
+    ty::{InferenceResult, MissingPattern},
     adt::{EnumVariantId, StructFieldId, VariantDef},
+    traits::TraitItem,
     generics::GenericParams,
     docs::{Documentation, Docs, docs_from_ast},
     module_tree::ModuleId,
@@ -41,9 +42,19 @@ impl Crate {
     pub fn dependencies(&self, db: &impl PersistentHirDatabase) -> Vec<CrateDependency> {
         self.dependencies_impl(db)
     }
+    /// Returns all crates which depend on this one, directly or transitively.
+    pub fn reverse_dependencies(&self, db: &impl PersistentHirDatabase) -> Vec<Crate> {
+        self.reverse_dependencies_impl(db)
+    }
     pub fn root_module(&self, db: &impl PersistentHirDatabase) -> Option<Module> {
         self.root_module_impl(db)
     }
+    /// The shortest path, from the crate root, under which `def` can be
+    /// imported from other crates, going through public modules and `pub use`
+    /// re-exports.
+    pub fn import_path(&self, db: &impl HirDatabase, def: ModuleDef) -> Option<Vec<Name>> {
+        db.import_paths(*self).get(&def).cloned()
+    }
 
     // TODO: should this be in source_binder?
     pub fn source_root_crates(
@@ -67,7 +78,7 @@ pub struct Module {
 }
 
 /// The defs which can be visible in the module.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum ModuleDef {
     Module(Module),
     Function(Function),
@@ -169,6 +180,22 @@ impl Module {
         db.item_map(self.krate)[self.module_id].clone()
     }
 
+    /// Files which could be declared as children of this module with `mod name;`,
+    /// but which no module includes yet.
+    pub fn unclaimed_children(&self, db: &impl PersistentHirDatabase) -> Vec<(Name, FileId)> {
+        self.unclaimed_children_impl(db)
+    }
+
+    /// The paths at which the file of a child module `name` may be, following
+    /// the `mod.rs` and `foo.rs` layout rules. The preferred one comes first.
+    pub fn child_file_candidates(
+        &self,
+        db: &impl PersistentHirDatabase,
+        name: &str,
+    ) -> Vec<RelativePathBuf> {
+        self.child_file_candidates_impl(db, name)
+    }
+
     pub fn problems(&self, db: &impl HirDatabase) -> Vec<(TreeArc<SyntaxNode>, Problem)> {
         self.problems_impl(db)
     }
@@ -298,7 +325,7 @@ impl Struct {
         let r = self.module(db).resolver(db);
         // ...and add generic params, if present
         let p = self.generic_params(db);
-        let r = if !p.params.is_empty() { r.push_generic_params_scope(p) } else { r };
+        let r = if !p.is_empty() { r.push_generic_params_scope(p) } else { r };
         r
     }
 }
@@ -358,7 +385,7 @@ impl Enum {
         let r = self.module(db).resolver(db);
         // ...and add generic params, if present
         let p = self.generic_params(db);
-        let r = if !p.params.is_empty() { r.push_generic_params_scope(p) } else { r };
+        let r = if !p.is_empty() { r.push_generic_params_scope(p) } else { r };
         r
     }
 }
@@ -487,10 +514,31 @@ impl Function {
         db.fn_signature(*self)
     }
 
+    pub fn ty(&self, db: &impl HirDatabase) -> Ty {
+        db.type_for_def((*self).into())
+    }
+
     pub fn infer(&self, db: &impl HirDatabase) -> Arc<InferenceResult> {
         db.infer(*self)
     }
 
+    /// The patterns the arms of the `match` expression don't cover.
+    pub fn missing_match_arms(
+        &self,
+        db: &impl HirDatabase,
+        match_expr: ExprId,
+    ) -> Vec<MissingPattern> {
+        crate::ty::missing_match_arms(db, *self, match_expr)
+    }
+
+    /// The `match` expressions in the body which don't cover all the values.
+    pub fn non_exhaustive_matches(
+        &self,
+        db: &impl HirDatabase,
+    ) -> Vec<(ExprId, Vec<MissingPattern>)> {
+        crate::ty::non_exhaustive_matches(db, *self)
+    }
+
     pub fn generic_params(&self, db: &impl PersistentHirDatabase) -> Arc<GenericParams> {
         db.generic_params((*self).into())
     }
@@ -505,7 +553,7 @@ impl Function {
             .unwrap_or_else(|| self.module(db).resolver(db));
         // ...and add generic params, if present
         let p = self.generic_params(db);
-        let r = if !p.params.is_empty() { r.push_generic_params_scope(p) } else { r };
+        let r = if !p.is_empty() { r.push_generic_params_scope(p) } else { r };
         r
     }
 }
@@ -525,6 +573,10 @@ impl Const {
     pub fn source(&self, db: &impl PersistentHirDatabase) -> (HirFileId, TreeArc<ast::ConstDef>) {
         self.id.source(db)
     }
+
+    pub fn module(&self, db: &impl PersistentHirDatabase) -> Module {
+        self.id.module(db)
+    }
 }
 
 impl Docs for Const {
@@ -542,6 +594,10 @@ impl Static {
     pub fn source(&self, db: &impl PersistentHirDatabase) -> (HirFileId, TreeArc<ast::StaticDef>) {
         self.id.source(db)
     }
+
+    pub fn module(&self, db: &impl PersistentHirDatabase) -> Module {
+        self.id.module(db)
+    }
 }
 
 impl Docs for Static {
@@ -560,6 +616,18 @@ impl Trait {
         self.id.source(db)
     }
 
+    pub fn module(&self, db: &impl PersistentHirDatabase) -> Module {
+        self.id.module(db)
+    }
+
+    pub fn name(&self, db: &impl PersistentHirDatabase) -> Option<Name> {
+        db.trait_data(*self).name().clone()
+    }
+
+    pub fn items(&self, db: &impl PersistentHirDatabase) -> Vec<TraitItem> {
+        db.trait_data(*self).items().to_vec()
+    }
+
     pub fn generic_params(&self, db: &impl PersistentHirDatabase) -> Arc<GenericParams> {
         db.generic_params((*self).into())
     }
@@ -581,6 +649,10 @@ impl Type {
         self.id.source(db)
     }
 
+    pub fn module(&self, db: &impl PersistentHirDatabase) -> Module {
+        self.id.module(db)
+    }
+
     pub fn generic_params(&self, db: &impl PersistentHirDatabase) -> Arc<GenericParams> {
         db.generic_params((*self).into())
     }
@@ -1,3 +1,3 @@
-mod krate; // `crate` is invalid ident :(
+pub(crate) mod krate; // `crate` is invalid ident :(
 mod module;
 pub(crate) mod function;
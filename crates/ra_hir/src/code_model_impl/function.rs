@@ -10,10 +10,8 @@ use crate::{
 };
 
 impl Function {
-    // TODO impl_block should probably also be part of the code model API?
-
     /// The containing impl block, if this is a method.
-    pub(crate) fn impl_block(&self, db: &impl HirDatabase) -> Option<ImplBlock> {
+    pub fn impl_block(&self, db: &impl HirDatabase) -> Option<ImplBlock> {
         let module_impls = db.impls_in_module(self.module(db));
         ImplBlock::containing(module_impls, (*self).into())
     }
@@ -1,5 +1,13 @@
+use std::{collections::VecDeque, sync::Arc};
+
+use rustc_hash::FxHashMap;
+use ra_syntax::{
+    AstNode,
+    ast::{self, VisibilityOwner},
+};
+
 use crate::{
-    Crate, CrateDependency, AsName, Module, PersistentHirDatabase,
+    Crate, CrateDependency, AsName, Module, ModuleDef, Name, HirDatabase, PersistentHirDatabase,
 };
 
 impl Crate {
@@ -17,6 +25,27 @@ impl Crate {
             })
             .collect()
     }
+    pub(crate) fn reverse_dependencies_impl(&self, db: &impl PersistentHirDatabase) -> Vec<Crate> {
+        let crate_graph = db.crate_graph();
+        let mut res = vec![self.crate_id];
+        let mut candidates = crate_graph.iter().collect::<Vec<_>>();
+        candidates.sort();
+        loop {
+            let len = res.len();
+            for &crate_id in candidates.iter() {
+                if res.contains(&crate_id) {
+                    continue;
+                }
+                if crate_graph.dependencies(crate_id).any(|dep| res.contains(&dep.crate_id())) {
+                    res.push(crate_id);
+                }
+            }
+            if res.len() == len {
+                break;
+            }
+        }
+        res.into_iter().skip(1).map(|crate_id| Crate { crate_id }).collect()
+    }
     pub(crate) fn root_module_impl(&self, db: &impl PersistentHirDatabase) -> Option<Module> {
         let module_tree = db.module_tree(*self);
         let module_id = module_tree.modules().next()?;
@@ -25,3 +54,74 @@ impl Crate {
         Some(module)
     }
 }
+
+pub(crate) fn import_paths_query(
+    db: &impl HirDatabase,
+    krate: Crate,
+) -> Arc<FxHashMap<ModuleDef, Vec<Name>>> {
+    let mut res = FxHashMap::default();
+    let mut queue = VecDeque::new();
+    queue.extend(krate.root_module(db).map(|root| (root, Vec::new())));
+    // Breadth-first, so that the first path found for a def is the shortest.
+    while let Some((module, path)) = queue.pop_front() {
+        for (name, resolution) in module.scope(db).entries() {
+            if let Some(import) = resolution.import {
+                let segment = module.import_source(db, import);
+                let use_item = segment.syntax().ancestors().find_map(ast::UseItem::cast);
+                if !use_item.map_or(false, is_pub) {
+                    continue;
+                }
+            }
+            let defs = resolution.def.take_types().into_iter().chain(resolution.def.take_values());
+            for def in defs {
+                if resolution.import.is_none() && !is_pub_def(db, def) {
+                    continue;
+                }
+                let mut path = path.clone();
+                path.push(name.clone());
+                res.entry(def).or_insert(path);
+            }
+        }
+        for child in module.children(db) {
+            if !is_pub_def(db, child.into()) {
+                continue;
+            }
+            if let Some(name) = child.name(db) {
+                let mut path = path.clone();
+                path.push(name);
+                queue.push_back((child, path));
+            }
+        }
+    }
+    Arc::new(res)
+}
+
+fn is_pub_def(db: &impl HirDatabase, def: ModuleDef) -> bool {
+    def_visibility(db, def).map_or(false, |it| it == "pub")
+}
+
+/// The visibility of `def` as written, like `pub` or `pub(crate)`, or `None`
+/// for private items.
+pub(crate) fn def_visibility(db: &impl HirDatabase, def: ModuleDef) -> Option<String> {
+    match def {
+        ModuleDef::Module(it) => it.declaration_source(db).and_then(|(_, it)| visibility(&*it)),
+        ModuleDef::Function(it) => visibility(&*it.source(db).1),
+        ModuleDef::Struct(it) => visibility(&*it.source(db).1),
+        ModuleDef::Enum(it) => visibility(&*it.source(db).1),
+        // Variants are as visible as their enum.
+        ModuleDef::EnumVariant(it) => visibility(&*it.parent_enum(db).source(db).1),
+        ModuleDef::Const(it) => visibility(&*it.source(db).1),
+        ModuleDef::Static(it) => visibility(&*it.source(db).1),
+        ModuleDef::Trait(it) => visibility(&*it.source(db).1),
+        ModuleDef::Type(it) => visibility(&*it.source(db).1),
+    }
+}
+
+fn visibility(node: &impl VisibilityOwner) -> Option<String> {
+    node.visibility().map(|it| it.syntax().text().to_string())
+}
+
+/// Only a plain `pub` makes an item visible from other crates.
+fn is_pub(node: &impl VisibilityOwner) -> bool {
+    node.visibility().map_or(false, |it| it.syntax().text() == "pub")
+}
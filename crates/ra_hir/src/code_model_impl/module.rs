@@ -1,4 +1,5 @@
 use ra_db::FileId;
+use relative_path::RelativePathBuf;
 use ra_syntax::{ast, SyntaxNode, TreeArc};
 
 use crate::{
@@ -97,6 +98,31 @@ impl Module {
         Some(self.with_module_id(parent_id))
     }
 
+    pub(crate) fn unclaimed_children_impl(
+        &self,
+        db: &impl PersistentHirDatabase,
+    ) -> Vec<(Name, FileId)> {
+        let module_tree = db.module_tree(self.krate);
+        let file_id = self.module_id.file_id(&module_tree).original_file(db);
+        let orphans = db.orphan_files(db.file_source_root(file_id));
+        let mut res = orphans
+            .iter()
+            .filter(|(_, parent, _)| parent == self)
+            .map(|(file_id, _, name)| (name.clone(), *file_id))
+            .collect::<Vec<_>>();
+        res.sort_by_key(|(name, _)| name.to_string());
+        res
+    }
+
+    pub(crate) fn child_file_candidates_impl(
+        &self,
+        db: &impl PersistentHirDatabase,
+        name: &str,
+    ) -> Vec<RelativePathBuf> {
+        let module_tree = db.module_tree(self.krate);
+        self.module_id.child_file_candidates(&module_tree, db, name)
+    }
+
     pub(crate) fn problems_impl(
         &self,
         db: &impl HirDatabase,
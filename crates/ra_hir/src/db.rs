@@ -1,18 +1,20 @@
 use std::sync::Arc;
 
-use ra_syntax::{SyntaxNode, TreeArc, SourceFile};
-use ra_db::{SourceDatabase, salsa};
+use rustc_hash::FxHashMap;
+use ra_syntax::{SyntaxNode, SyntaxNodePtr, TreeArc, SourceFile};
+use ra_db::{FileId, SourceDatabase, SourceRootId, salsa};
 
 use crate::{
-    MacroCallId, HirFileId,
-    SourceFileItems, SourceItemId, Crate, Module, HirInterner,
+    MacroCallId, HirFileId, Name,
+    SourceFileItems, SourceItemId, Crate, Module, ModuleDef, HirInterner,
     Function, FnSignature, ExprScopes,
-    Struct, Enum, StructField,
+    Struct, Enum, StructField, Trait,
     macros::MacroExpansion,
     module_tree::ModuleTree,
     nameres::{ItemMap, lower::{LoweredModule, ImportSourceMap}},
     ty::{InferenceResult, Ty, method_resolution::CrateImplBlocks, TypableDef},
     adt::{StructData, EnumData},
+    traits::TraitData,
     impl_block::{ModuleImplBlocks, ImplSourceMap},
     generics::{GenericParams, GenericDef},
     ids::SourceFileItemId,
@@ -32,6 +34,9 @@ pub trait PersistentHirDatabase: SourceDatabase + AsRef<HirInterner> {
     #[salsa::invoke(crate::adt::EnumData::enum_data_query)]
     fn enum_data(&self, e: Enum) -> Arc<EnumData>;
 
+    #[salsa::invoke(crate::traits::TraitData::trait_data_query)]
+    fn trait_data(&self, t: Trait) -> Arc<TraitData>;
+
     #[salsa::invoke(crate::ids::SourceFileItems::file_items_query)]
     fn file_items(&self, file_id: HirFileId) -> Arc<SourceFileItems>;
 
@@ -60,6 +65,12 @@ pub trait PersistentHirDatabase: SourceDatabase + AsRef<HirInterner> {
     #[salsa::invoke(crate::module_tree::ModuleTree::module_tree_query)]
     fn module_tree(&self, krate: Crate) -> Arc<ModuleTree>;
 
+    #[salsa::invoke(crate::module_tree::orphan_files_query)]
+    fn orphan_files(&self, source_root: SourceRootId) -> Arc<Vec<(FileId, Module, Name)>>;
+
+    #[salsa::invoke(crate::macros::exported_macros_query)]
+    fn exported_macros(&self, krate: Crate) -> Arc<Vec<(FileId, SyntaxNodePtr)>>;
+
     #[salsa::invoke(crate::impl_block::impls_in_module)]
     fn impls_in_module(&self, module: Module) -> Arc<ModuleImplBlocks>;
 
@@ -101,6 +112,9 @@ pub trait HirDatabase: PersistentHirDatabase {
 
     #[salsa::invoke(crate::ty::method_resolution::CrateImplBlocks::impls_in_crate_query)]
     fn impls_in_crate(&self, krate: Crate) -> Arc<CrateImplBlocks>;
+
+    #[salsa::invoke(crate::code_model_impl::krate::import_paths_query)]
+    fn import_paths(&self, krate: Crate) -> Arc<FxHashMap<ModuleDef, Vec<Name>>>;
 }
 
 #[test]
@@ -6,7 +6,8 @@ use rustc_hash::FxHashMap;
 use ra_arena::{Arena, RawId, impl_arena_id, map::ArenaMap};
 use ra_syntax::{
     SyntaxNodePtr, AstNode,
-    ast::{self, LoopBodyOwner, ArgListOwner, NameOwner, LiteralFlavor}
+    ast::{self, LoopBodyOwner, ArgListOwner, NameOwner, LiteralFlavor},
+    SyntaxKind::{INT_NUMBER, TRUE_KW},
 };
 
 use crate::{
@@ -78,6 +79,28 @@ impl Body {
         self.pats.iter()
     }
 
+    /// Returns the places which are written to in this body: targets of
+    /// (compound) assignments and mutably borrowed expressions, together with
+    /// the receivers of field accesses among them.
+    pub fn written_places(&self) -> Vec<ExprId> {
+        let mut res = Vec::new();
+        for (_, expr) in self.exprs() {
+            let mut place = match expr {
+                Expr::BinaryOp { lhs, op: Some(op), .. } if op.is_assignment() => *lhs,
+                Expr::Ref { expr, mutability: Mutability::Mut } => *expr,
+                _ => continue,
+            };
+            loop {
+                res.push(place);
+                match &self[place] {
+                    Expr::Field { expr, .. } => place = *expr,
+                    _ => break,
+                }
+            }
+        }
+        res
+    }
+
     pub fn syntax_mapping(&self, db: &impl HirDatabase) -> Arc<BodySyntaxMapping> {
         db.body_syntax_mapping(self.owner)
     }
@@ -175,15 +198,18 @@ pub enum Expr {
     },
     Loop {
         body: ExprId,
+        label: Option<Name>,
     },
     While {
         condition: ExprId,
         body: ExprId,
+        label: Option<Name>,
     },
     For {
         iterable: ExprId,
         pat: PatId,
         body: ExprId,
+        label: Option<Name>,
     },
     Call {
         callee: ExprId,
@@ -297,8 +323,8 @@ impl Expr {
                     f(*expr);
                 }
             }
-            Expr::Loop { body } => f(*body),
-            Expr::While { condition, body } => {
+            Expr::Loop { body, .. } => f(*body),
+            Expr::While { condition, body, .. } => {
                 f(*condition);
                 f(*body);
             }
@@ -558,7 +584,8 @@ impl ExprCollector {
             ast::ExprKind::BlockExpr(e) => self.collect_block_opt(e.block()),
             ast::ExprKind::LoopExpr(e) => {
                 let body = self.collect_block_opt(e.loop_body());
-                self.alloc_expr(Expr::Loop { body }, syntax_ptr)
+                let label = label_name(e.label());
+                self.alloc_expr(Expr::Loop { body, label }, syntax_ptr)
             }
             ast::ExprKind::WhileExpr(e) => {
                 let condition = if let Some(condition) = e.condition() {
@@ -572,13 +599,15 @@ impl ExprCollector {
                     self.exprs.alloc(Expr::Missing)
                 };
                 let body = self.collect_block_opt(e.loop_body());
-                self.alloc_expr(Expr::While { condition, body }, syntax_ptr)
+                let label = label_name(e.label());
+                self.alloc_expr(Expr::While { condition, body, label }, syntax_ptr)
             }
             ast::ExprKind::ForExpr(e) => {
                 let iterable = self.collect_expr_opt(e.iterable());
                 let pat = self.collect_pat_opt(e.pat());
                 let body = self.collect_block_opt(e.loop_body());
-                self.alloc_expr(Expr::For { iterable, pat, body }, syntax_ptr)
+                let label = label_name(e.label());
+                self.alloc_expr(Expr::For { iterable, pat, body, label }, syntax_ptr)
             }
             ast::ExprKind::CallExpr(e) => {
                 let callee = self.collect_expr_opt(e.expr());
@@ -673,7 +702,17 @@ impl ExprCollector {
             }
             ast::ExprKind::FieldExpr(e) => {
                 let expr = self.collect_expr_opt(e.expr());
-                let name = e.name_ref().map(|nr| nr.as_name()).unwrap_or_else(Name::missing);
+                let name = match e.name_ref() {
+                    Some(nr) => nr.as_name(),
+                    // A tuple field, as in `x.0`.
+                    None => e
+                        .syntax()
+                        .children()
+                        .find(|it| it.kind() == INT_NUMBER)
+                        .and_then(|it| it.leaf_text())
+                        .map(|it| Name::new(it.clone()))
+                        .unwrap_or_else(Name::missing),
+                };
                 self.alloc_expr(Expr::Field { expr, name }, syntax_ptr)
             }
             ast::ExprKind::TryExpr(e) => {
@@ -726,43 +765,10 @@ impl ExprCollector {
                 let exprs = e.exprs().map(|expr| self.collect_expr(expr)).collect();
                 self.alloc_expr(Expr::Array { exprs }, syntax_ptr)
             }
-            ast::ExprKind::Literal(e) => {
-                let child = if let Some(child) = e.literal_expr() {
-                    child
-                } else {
-                    return self.alloc_expr(Expr::Missing, syntax_ptr);
-                };
-
-                let lit = match child.flavor() {
-                    LiteralFlavor::IntNumber { suffix } => {
-                        let known_name =
-                            suffix.map(Name::new).and_then(|name| UncertainIntTy::from_name(&name));
-
-                        Literal::Int(
-                            Default::default(),
-                            known_name.unwrap_or(UncertainIntTy::Unknown),
-                        )
-                    }
-                    LiteralFlavor::FloatNumber { suffix } => {
-                        let known_name = suffix
-                            .map(Name::new)
-                            .and_then(|name| UncertainFloatTy::from_name(&name));
-
-                        Literal::Float(
-                            Default::default(),
-                            known_name.unwrap_or(UncertainFloatTy::Unknown),
-                        )
-                    }
-                    LiteralFlavor::ByteString => Literal::ByteString(Default::default()),
-                    LiteralFlavor::String => Literal::String(Default::default()),
-                    LiteralFlavor::Byte => {
-                        Literal::Int(Default::default(), UncertainIntTy::Unsigned(UintTy::U8))
-                    }
-                    LiteralFlavor::Bool => Literal::Bool(Default::default()),
-                    LiteralFlavor::Char => Literal::Char(Default::default()),
-                };
-                self.alloc_expr(Expr::Literal(lit), syntax_ptr)
-            }
+            ast::ExprKind::Literal(e) => match lower_literal(e) {
+                Some(lit) => self.alloc_expr(Expr::Literal(lit), syntax_ptr),
+                None => self.alloc_expr(Expr::Missing, syntax_ptr),
+            },
 
             // TODO implement HIR for these:
             ast::ExprKind::Label(_e) => self.alloc_expr(Expr::Missing, syntax_ptr),
@@ -857,8 +863,12 @@ impl ExprCollector {
                 Pat::Struct { path, args: fields }
             }
 
+            // The literal is mapped to the syntax as a pattern only.
+            ast::PatKind::LiteralPat(p) => match p.literal().and_then(lower_literal) {
+                Some(lit) => Pat::Lit(self.exprs.alloc(Expr::Literal(lit))),
+                None => Pat::Missing,
+            },
             // TODO: implement
-            ast::PatKind::LiteralPat(_) => Pat::Missing,
             ast::PatKind::SlicePat(_) | ast::PatKind::RangePat(_) => Pat::Missing,
         };
         let syntax_ptr = SyntaxNodePtr::new(pat.syntax());
@@ -923,6 +933,36 @@ impl ExprCollector {
     }
 }
 
+fn lower_literal(e: &ast::Literal) -> Option<Literal> {
+    let child = e.literal_expr()?;
+    let lit = match child.flavor() {
+        LiteralFlavor::IntNumber { suffix } => {
+            let known_name =
+                suffix.map(Name::new).and_then(|name| UncertainIntTy::from_name(&name));
+
+            Literal::Int(Default::default(), known_name.unwrap_or(UncertainIntTy::Unknown))
+        }
+        LiteralFlavor::FloatNumber { suffix } => {
+            let known_name =
+                suffix.map(Name::new).and_then(|name| UncertainFloatTy::from_name(&name));
+
+            Literal::Float(Default::default(), known_name.unwrap_or(UncertainFloatTy::Unknown))
+        }
+        LiteralFlavor::ByteString => Literal::ByteString(Default::default()),
+        LiteralFlavor::String => Literal::String(Default::default()),
+        LiteralFlavor::Byte => {
+            Literal::Int(Default::default(), UncertainIntTy::Unsigned(UintTy::U8))
+        }
+        LiteralFlavor::Bool => Literal::Bool(child.syntax().kind() == TRUE_KW),
+        LiteralFlavor::Char => Literal::Char(Default::default()),
+    };
+    Some(lit)
+}
+
+fn label_name(label: Option<&ast::Label>) -> Option<Name> {
+    label.and_then(|it| it.lifetime()).map(AsName::as_name)
+}
+
 pub(crate) fn body_syntax_mapping(db: &impl HirDatabase, func: Function) -> Arc<BodySyntaxMapping> {
     let mut collector = ExprCollector::new(func);
 
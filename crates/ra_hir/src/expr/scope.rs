@@ -36,6 +36,8 @@ pub struct ScopeEntry {
 pub struct ScopeData {
     parent: Option<ScopeId>,
     entries: Vec<ScopeEntry>,
+    /// The label of the loop which introduced this scope, if any.
+    label: Option<Name>,
 }
 
 impl ExprScopes {
@@ -66,6 +68,10 @@ impl ExprScopes {
         &self.scopes[scope].entries
     }
 
+    pub fn label(&self, scope: ScopeId) -> Option<&Name> {
+        self.scopes[scope].label.as_ref()
+    }
+
     pub fn scope_chain_for<'a>(
         &'a self,
         scope: Option<ScopeId>,
@@ -74,11 +80,16 @@ impl ExprScopes {
     }
 
     fn root_scope(&mut self) -> ScopeId {
-        self.scopes.alloc(ScopeData { parent: None, entries: vec![] })
+        self.scopes.alloc(ScopeData { parent: None, entries: vec![], label: None })
     }
 
     fn new_scope(&mut self, parent: ScopeId) -> ScopeId {
-        self.scopes.alloc(ScopeData { parent: Some(parent), entries: vec![] })
+        self.scopes.alloc(ScopeData { parent: Some(parent), entries: vec![], label: None })
+    }
+
+    fn new_labeled_scope(&mut self, parent: ScopeId, label: Option<&Name>) -> ScopeId {
+        let label = label.cloned();
+        self.scopes.alloc(ScopeData { parent: Some(parent), entries: vec![], label })
     }
 
     fn add_bindings(&mut self, body: &Body, scope: ScopeId, pat: PatId) {
@@ -260,12 +271,16 @@ fn compute_expr_scopes(expr: ExprId, body: &Body, scopes: &mut ExprScopes, scope
         Expr::Block { statements, tail } => {
             compute_block_scopes(&statements, *tail, body, scopes, scope);
         }
-        Expr::For { iterable, pat, body: body_expr } => {
+        Expr::For { iterable, pat, body: body_expr, label } => {
             compute_expr_scopes(*iterable, body, scopes, scope);
-            let scope = scopes.new_scope(scope);
+            let scope = scopes.new_labeled_scope(scope, label.as_ref());
             scopes.add_bindings(body, scope, *pat);
             compute_expr_scopes(*body_expr, body, scopes, scope);
         }
+        Expr::Loop { label: Some(label), .. } | Expr::While { label: Some(label), .. } => {
+            let scope = scopes.new_labeled_scope(scope, Some(label));
+            body[expr].walk_child_exprs(|e| compute_expr_scopes(e, body, scopes, scope));
+        }
         Expr::Lambda { args, body: body_expr, .. } => {
             let scope = scopes.new_scope(scope);
             scopes.add_params_bindings(scope, &args);
@@ -381,6 +396,37 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_loop_labels() {
+        let code = r"
+            fn quux() {
+                'outer: for x in xs {
+                    loop {
+                        'inner: while true {
+                            marker
+                        }
+                    }
+                }
+            }";
+        let file = SourceFile::parse(code);
+        let off = TextUnit::from_usize(code.find("marker").unwrap());
+        let marker: &ast::PathExpr = find_node_at_offset(file.syntax(), off).unwrap();
+        let fn_def: &ast::FnDef = find_node_at_offset(file.syntax(), off).unwrap();
+        let irrelevant_function = Function { id: crate::ids::FunctionId::from_raw(0.into()) };
+        let body_hir = expr::collect_fn_body_syntax(irrelevant_function, fn_def);
+        let scopes = ExprScopes::new(Arc::clone(body_hir.body()));
+        let scopes = ScopesWithSyntaxMapping {
+            scopes: Arc::new(scopes),
+            syntax_mapping: Arc::new(body_hir),
+        };
+        let labels = scopes
+            .scope_chain(marker.syntax())
+            .filter_map(|scope| scopes.scopes.label(scope))
+            .map(|it| it.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(labels, vec!["'inner", "'outer"]);
+    }
+
     #[test]
     fn test_match() {
         do_check(
@@ -17,10 +17,30 @@ pub struct GenericParam {
     pub(crate) name: Name,
 }
 
+impl GenericParam {
+    pub fn name(&self) -> &Name {
+        &self.name
+    }
+}
+
+/// Data about a lifetime parameter, like `'a` in `fn foo<'a>()`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct LifetimeParam {
+    pub(crate) idx: u32,
+    pub(crate) name: Name,
+}
+
+impl LifetimeParam {
+    pub fn name(&self) -> &Name {
+        &self.name
+    }
+}
+
 /// Data about the generic parameters of a function, struct, impl, etc.
 #[derive(Clone, PartialEq, Eq, Debug, Default)]
 pub struct GenericParams {
     pub(crate) params: Vec<GenericParam>,
+    pub(crate) lifetimes: Vec<LifetimeParam>,
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
@@ -62,6 +82,23 @@ impl GenericParams {
             let param = GenericParam { idx: idx as u32, name };
             self.params.push(param);
         }
+        for (idx, lifetime_param) in params.lifetime_params().enumerate() {
+            let name = lifetime_param.lifetime().map(AsName::as_name).unwrap_or_else(Name::missing);
+            let param = LifetimeParam { idx: idx as u32, name };
+            self.lifetimes.push(param);
+        }
+    }
+
+    pub fn params(&self) -> &[GenericParam] {
+        &self.params
+    }
+
+    pub fn lifetimes(&self) -> &[LifetimeParam] {
+        &self.lifetimes
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.params.is_empty() && self.lifetimes.is_empty()
     }
 
     pub(crate) fn find_by_name(&self, name: &Name) -> Option<&GenericParam> {
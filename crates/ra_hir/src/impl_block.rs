@@ -36,6 +36,11 @@ impl ImplSourceMap {
 
         self.map[impl_id].to_node(file).to_owned()
     }
+
+    pub(crate) fn find(&self, impl_block: &ast::ImplBlock) -> Option<ImplId> {
+        let ptr = AstPtr::new(impl_block);
+        self.map.iter().find(|(_, it)| **it == ptr).map(|(impl_id, _)| impl_id)
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
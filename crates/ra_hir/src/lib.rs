@@ -29,6 +29,7 @@ mod name;
 mod module_tree;
 mod nameres;
 mod adt;
+mod traits;
 mod type_ref;
 mod ty;
 mod impl_block;
@@ -55,12 +56,15 @@ pub use self::{
     ids::{HirFileId, MacroCallId, MacroCallLoc, HirInterner},
     macros::{MacroDef, MacroInput, MacroExpansion},
     nameres::{ItemMap, PerNs, Namespace},
-    ty::Ty,
+    ty::{Ty, Substs, InferenceResult, MissingPattern},
     impl_block::{ImplBlock, ImplItem},
     docs::{Docs, Documentation},
-    adt::AdtDef,
-    expr::{ExprScopes, ScopesWithSyntaxMapping},
+    adt::{AdtDef, VariantDef},
+    traits::TraitItem,
+    generics::{GenericParams, GenericParam, LifetimeParam},
+    expr::{ExprScopes, ScopesWithSyntaxMapping, BodySyntaxMapping},
     resolve::{Resolver, Resolution},
+    type_ref::{Mutability, TypeRef},
 };
 
 pub use self::code_model_api::{
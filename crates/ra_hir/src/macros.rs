@@ -9,12 +9,13 @@
 /// those yet, so all macros are string based at the moment!
 use std::sync::Arc;
 
+use ra_db::FileId;
 use ra_syntax::{
     TextRange, TextUnit, SourceFile, AstNode, SyntaxNode, TreeArc, SyntaxNodePtr,
-    ast,
+    ast::{self, AttrsOwner},
 };
 
-use crate::{MacroCallId, PersistentHirDatabase};
+use crate::{Crate, MacroCallId, ModuleSource, PersistentHirDatabase};
 
 // Hard-coded defs for now :-(
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -133,3 +134,35 @@ pub(crate) fn expand_macro_invocation(
     let (def, input) = MacroDef::from_call(macro_call)?;
     def.expand(input).map(Arc::new)
 }
+
+/// Finds the `#[macro_export]` macros of the crate, which can be defined in
+/// any of its modules.
+pub(crate) fn exported_macros_query(
+    db: &impl PersistentHirDatabase,
+    krate: Crate,
+) -> Arc<Vec<(FileId, SyntaxNodePtr)>> {
+    let mut res = Vec::new();
+    let mut modules = krate.root_module(db).into_iter().collect::<Vec<_>>();
+    while let Some(module) = modules.pop() {
+        modules.extend(module.children(db));
+        let (file_id, source) = module.definition_source(db);
+        // Macro calls are not `ModuleItem`s, so they are looked up among the
+        // children directly.
+        let items = match &source {
+            ModuleSource::SourceFile(it) => it.syntax(),
+            ModuleSource::Module(it) => match it.item_list() {
+                Some(items) => items.syntax(),
+                None => continue,
+            },
+        };
+        for macro_call in items.children().filter_map(ast::MacroCall::cast) {
+            let is_exported = macro_call.attrs().any(|attr| {
+                attr.as_atom().map(|it| it.as_str() == "macro_export").unwrap_or(false)
+            });
+            if is_exported {
+                res.push((file_id, SyntaxNodePtr::new(macro_call.syntax())));
+            }
+        }
+    }
+    Arc::new(res)
+}
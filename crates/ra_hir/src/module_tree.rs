@@ -1,8 +1,8 @@
 use std::sync::Arc;
 
 use arrayvec::ArrayVec;
-use relative_path::RelativePathBuf;
-use ra_db::{FileId, SourceRoot};
+use relative_path::{RelativePath, RelativePathBuf};
+use ra_db::{FileId, SourceRoot, SourceRootId};
 use ra_syntax::{
     SyntaxNode, TreeArc,
     algo::generate,
@@ -14,7 +14,7 @@ use test_utils::tested_by;
 use crate::{
     Name, AsName, HirDatabase, SourceItemId, HirFileId, Problem, SourceFileItems, ModuleSource,
     PersistentHirDatabase,
-    Crate,
+    Crate, Module,
     ids::SourceFileItemId,
 };
 
@@ -254,6 +254,37 @@ impl ModuleId {
             Some((link.name.clone(), module))
         })
     }
+    /// The paths at which the file of the child module `name` may be, the
+    /// preferred one first.
+    pub(crate) fn child_file_candidates(
+        self,
+        tree: &ModuleTree,
+        db: &impl PersistentHirDatabase,
+        name: &str,
+    ) -> Vec<RelativePathBuf> {
+        let file_id = self.file_id(tree).original_file(db);
+        // The names of the inline modules from this one up to the file's one.
+        let mut inline_names = Vec::new();
+        let mut module = self;
+        while let (Some(_), Some(link)) = (module.decl_id(tree), module.parent_link(tree)) {
+            inline_names.push(link.name(tree).to_string());
+            module = link.owner(tree);
+        }
+        let is_root = module.parent(tree).is_none();
+        let outermost = match inline_names.pop() {
+            Some(it) => it,
+            None => return submodule_candidates(db, file_id, name, is_root).to_vec(),
+        };
+        // The children of `mod a { ... }` are in the directory in which the
+        // children of `a` would be if `a` were a `mod.rs` file.
+        let outermost_file = &submodule_candidates(db, file_id, &outermost, is_root)[0];
+        let root = RelativePathBuf::default();
+        let mut dir = outermost_file.parent().unwrap_or(&root).join(&outermost);
+        for inline_name in inline_names.iter().rev() {
+            dir = dir.join(inline_name);
+        }
+        vec![dir.join(format!("{}.rs", name)), dir.join(format!("{}/mod.rs", name))]
+    }
     pub(crate) fn problems(
         self,
         tree: &ModuleTree,
@@ -298,34 +329,101 @@ fn resolve_submodule(
     // FIXME: handle submodules of inline modules properly
     let file_id = file_id.original_file(db);
     let source_root_id = db.file_source_root(file_id);
+    let candidates = submodule_candidates(db, file_id, &name.to_string(), is_root);
+    let sr = db.source_root(source_root_id);
+    let points_to =
+        candidates.iter().filter_map(|path| sr.files.get(path)).map(|&it| it).collect::<Vec<_>>();
+    let problem = if points_to.is_empty() {
+        Some(Problem::UnresolvedModule { candidate: candidates[0].clone() })
+    } else {
+        None
+    };
+    (points_to, problem)
+}
+
+/// The files of the source root which no crate includes, with the modules
+/// which could declare them as children and the names they would get.
+pub(crate) fn orphan_files_query(
+    db: &impl PersistentHirDatabase,
+    source_root_id: SourceRootId,
+) -> Arc<Vec<(FileId, Module, Name)>> {
+    let source_root = db.source_root(source_root_id);
+    let module_trees = db
+        .source_root_crates(source_root_id)
+        .iter()
+        .map(|&crate_id| {
+            let krate = Crate { crate_id };
+            (krate, db.module_tree(krate))
+        })
+        .collect::<Vec<_>>();
+    let orphans = source_root
+        .files
+        .iter()
+        .filter(|(_, &file_id)| {
+            module_trees
+                .iter()
+                .all(|(_, tree)| tree.find_module_by_source(file_id.into(), None).is_none())
+        })
+        .filter_map(|(path, &file_id)| Some((path, submodule_name(path)?, file_id)))
+        .collect::<Vec<_>>();
+
+    let mut res = Vec::new();
+    if orphans.is_empty() {
+        return Arc::new(res);
+    }
+    for (krate, tree) in module_trees.iter() {
+        for module_id in tree.modules() {
+            for &(path, name, orphan) in orphans.iter() {
+                if module_id.child_file_candidates(tree, db, name).contains(path) {
+                    let module = Module { krate: *krate, module_id };
+                    res.push((orphan, module, Name::new(name.into())));
+                }
+            }
+        }
+    }
+    Arc::new(res)
+}
+
+/// Paths at which the file of the submodule `name` of the module defined in
+/// `file_id` may be, the preferred one first.
+fn submodule_candidates(
+    db: &impl PersistentHirDatabase,
+    file_id: FileId,
+    name: &str,
+    is_root: bool,
+) -> ArrayVec<[RelativePathBuf; 2]> {
     let path = db.file_relative_path(file_id);
     let root = RelativePathBuf::default();
     let dir_path = path.parent().unwrap_or(&root);
     let mod_name = path.file_stem().unwrap_or("unknown");
     let is_dir_owner = is_root || mod_name == "mod";
 
-    let file_mod = dir_path.join(format!("{}.rs", name));
-    let dir_mod = dir_path.join(format!("{}/mod.rs", name));
-    let file_dir_mod = dir_path.join(format!("{}/{}.rs", mod_name, name));
-    let mut candidates = ArrayVec::<[_; 2]>::new();
+    let mut candidates = ArrayVec::new();
     if is_dir_owner {
-        candidates.push(file_mod.clone());
-        candidates.push(dir_mod);
+        candidates.push(dir_path.join(format!("{}.rs", name)));
+        candidates.push(dir_path.join(format!("{}/mod.rs", name)));
     } else {
-        candidates.push(file_dir_mod.clone());
+        candidates.push(dir_path.join(format!("{}/{}.rs", mod_name, name)));
     };
-    let sr = db.source_root(source_root_id);
-    let points_to = candidates
-        .into_iter()
-        .filter_map(|path| sr.files.get(&path))
-        .map(|&it| it)
-        .collect::<Vec<_>>();
-    let problem = if points_to.is_empty() {
-        Some(Problem::UnresolvedModule {
-            candidate: if is_dir_owner { file_mod } else { file_dir_mod },
-        })
+    candidates
+}
+
+/// The name of the module which the file at `path` would define, if it were
+/// declared with `mod name;`.
+fn submodule_name(path: &RelativePath) -> Option<&str> {
+    if path.extension() != Some("rs") {
+        return None;
+    }
+    let name = match path.file_stem()? {
+        "mod" => path.parent()?.file_name()?,
+        stem => stem,
+    };
+    let mut chars = name.chars();
+    let is_ident = chars.next().map(|c| c == '_' || c.is_alphabetic()).unwrap_or(false)
+        && chars.all(|c| c == '_' || c.is_alphanumeric());
+    if is_ident {
+        Some(name)
     } else {
         None
-    };
-    (points_to, problem)
+    }
 }
@@ -43,6 +43,14 @@ impl Name {
         Name::new(idx.to_string().into())
     }
 
+    /// Checks whether this is the name of a primitive type, like `i32` or `str`.
+    pub fn is_primitive_type(&self) -> bool {
+        match self.as_known_name() {
+            None | Some(KnownName::SelfType) | Some(KnownName::SelfParam) => false,
+            Some(_) => true,
+        }
+    }
+
     pub(crate) fn as_known_name(&self) -> Option<KnownName> {
         let name = match self.text.as_str() {
             "isize" => KnownName::Isize,
@@ -86,6 +94,12 @@ impl AsName for ast::Name {
     }
 }
 
+impl AsName for ast::Lifetime {
+    fn as_name(&self) -> Name {
+        Name::new(ast::AstToken::text(self).clone())
+    }
+}
+
 impl AsName for ra_db::Dependency {
     fn as_name(&self) -> Name {
         Name::new(self.name.clone())
@@ -4,12 +4,13 @@ use ra_db::SourceDatabase;
 use test_utils::{assert_eq_text, covers};
 
 use crate::{
-    ItemMap,
+    ItemMap, ModuleDef, Name, Path, PathKind,
+    path::PathSegment,
     PersistentHirDatabase,
     mock::MockDatabase,
     module_tree::ModuleId,
 };
-use super::Resolution;
+use super::{PerNs, Resolution};
 
 fn item_map(fixture: &str) -> (Arc<ItemMap>, ModuleId) {
     let (db, pos) = MockDatabase::with_position(fixture);
@@ -400,6 +401,43 @@ fn item_map_enum_importing() {
     );
 }
 
+#[test]
+fn enum_variants_resolve_to_the_variant_in_both_namespaces() {
+    let (db, pos) = MockDatabase::with_position(
+        "
+        //- /lib.rs
+        enum E { V(u32) }
+        use self::E::V;
+        mod m {
+            pub use super::E::*;
+        }
+        <|>
+        ",
+    );
+    let module = crate::source_binder::module_from_position(&db, pos).unwrap();
+    let item_map = db.item_map(module.krate(&db).unwrap());
+    let name = |it: &str| Name::new(it.into());
+    let e = match item_map[module.module_id].items[&name("E")].def.take_types() {
+        Some(ModuleDef::Enum(it)) => it,
+        _ => panic!("expected an enum"),
+    };
+    let variant: ModuleDef = e.variant(&db, &name("V")).unwrap().into();
+    let variant = PerNs::both(variant, variant);
+
+    let imported = item_map[module.module_id].items[&name("V")].def;
+    assert_eq!(imported, variant);
+    let glob_imported = module.child(&db, &name("m")).unwrap();
+    assert_eq!(item_map[glob_imported.module_id].items[&name("V")].def, variant);
+    let path = Path {
+        kind: PathKind::Plain,
+        segments: vec![name("E"), name("V")]
+            .into_iter()
+            .map(|name| PathSegment { name, args_and_bindings: None })
+            .collect(),
+    };
+    assert_eq!(item_map.resolve_path(&db, module, &path), variant);
+}
+
 #[test]
 fn item_map_across_crates() {
     let mut db = MockDatabase::with_files(
@@ -98,6 +98,31 @@ impl Resolver {
         names
     }
 
+    /// The lifetime parameters which are in scope, innermost first.
+    pub fn all_lifetimes(&self) -> Vec<Name> {
+        self.scopes
+            .iter()
+            .rev()
+            .filter_map(|scope| match scope {
+                Scope::GenericParams(gp) => Some(gp),
+                _ => None,
+            })
+            .flat_map(|gp| gp.lifetimes.iter().map(|it| it.name.clone()))
+            .collect()
+    }
+
+    /// The labels of the enclosing loops, innermost first.
+    pub fn all_labels(&self) -> Vec<Name> {
+        self.scopes
+            .iter()
+            .rev()
+            .filter_map(|scope| match scope {
+                Scope::ExprScope(e) => e.expr_scopes.label(e.scope_id).cloned(),
+                _ => None,
+            })
+            .collect()
+    }
+
     fn module(&self) -> Option<(&ItemMap, Module)> {
         self.scopes.iter().rev().find_map(|scope| match scope {
             Scope::ModuleScope(m) => Some((&*m.item_map, m.module.clone())),
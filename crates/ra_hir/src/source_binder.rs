@@ -7,14 +7,14 @@
 /// purely for "IDE needs".
 use ra_db::{FileId, FilePosition};
 use ra_syntax::{
-    SmolStr, TextRange, SyntaxNode,
+    SmolStr, TextRange, SyntaxNode, TreeArc,
     ast::{self, AstNode, NameOwner},
     algo::{find_node_at_offset, find_leaf_at_offset},
 };
 
 use crate::{
     HirDatabase, Function, ModuleDef, Struct, Enum,
-    AsName, Module, HirFileId, Crate, Trait, Resolver,
+    AsName, Module, Name, HirFileId, Crate, Trait, Resolver, ImplBlock,
     ids::{LocationCtx, SourceFileItemId},
     expr
 };
@@ -24,6 +24,16 @@ pub fn module_from_file_id(db: &impl HirDatabase, file_id: FileId) -> Option<Mod
     module_from_source(db, file_id.into(), None)
 }
 
+/// The module defined by a whole file which could declare the file, which no
+/// crate includes, as its child, and the name of the child.
+pub fn parent_of_orphan_file(db: &impl HirDatabase, file_id: FileId) -> Option<(Module, Name)> {
+    db.orphan_files(db.file_source_root(file_id))
+        .iter()
+        .filter(|(it, _, _)| *it == file_id)
+        .find(|(_, module, _)| module.module_id.decl_id(&db.module_tree(module.krate)).is_none())
+        .map(|(_, module, name)| (*module, name.clone()))
+}
+
 /// Locates the child module by `mod child;` declaration.
 pub fn module_from_declaration(
     db: &impl HirDatabase,
@@ -42,7 +52,9 @@ pub fn module_from_declaration(
 pub fn module_from_position(db: &impl HirDatabase, position: FilePosition) -> Option<Module> {
     let file = db.parse(position.file_id);
     match find_node_at_offset::<ast::Module>(file.syntax(), position.offset) {
-        Some(m) if !m.has_semi() => module_from_inline(db, position.file_id.into(), m),
+        Some(m) if m.item_list().is_some() => module_from_inline(db, position.file_id.into(), m),
+        // `mod foo;` and the unfinished `mod foo` belong to the enclosing module.
+        Some(m) => module_from_child_node(db, position.file_id, m.syntax().parent()?),
         _ => module_from_file_id(db, position.file_id.into()),
     }
 }
@@ -152,6 +164,16 @@ pub fn trait_from_module(
     Trait { id: ctx.to_def(trait_def) }
 }
 
+pub fn impl_block_from_module(
+    db: &impl HirDatabase,
+    module: Module,
+    impl_block: &ast::ImplBlock,
+) -> Option<ImplBlock> {
+    let (module_impl_blocks, source_map) = db.impls_in_module_with_source_map(module);
+    let impl_id = source_map.find(impl_block)?;
+    Some(ImplBlock::from_id(module_impl_blocks, impl_id))
+}
+
 pub fn macro_symbols(db: &impl HirDatabase, file_id: FileId) -> Vec<(SmolStr, TextRange)> {
     let module = match module_from_file_id(db, file_id) {
         Some(it) => it,
@@ -190,6 +212,17 @@ pub fn macro_symbols(db: &impl HirDatabase, file_id: FileId) -> Vec<(SmolStr, Te
     res
 }
 
+/// The `#[macro_export]` macros of the crate.
+pub fn exported_macros(db: &impl HirDatabase, krate: Crate) -> Vec<TreeArc<ast::MacroCall>> {
+    db.exported_macros(krate)
+        .iter()
+        .filter_map(|(file_id, ptr)| {
+            let file = db.parse(*file_id);
+            ast::MacroCall::cast(ptr.to_node(&file)).map(|it| it.to_owned())
+        })
+        .collect()
+}
+
 pub fn resolver_for_position(db: &impl HirDatabase, position: FilePosition) -> Resolver {
     let file_id = position.file_id;
     let file = db.parse(file_id);
@@ -205,6 +238,9 @@ pub fn resolver_for_position(db: &impl HirDatabase, position: FilePosition) -> R
                         // TODO const/static/array length
                         None
                     }
+                } else if let Some(fn_def) = ast::FnDef::cast(node) {
+                    // The signature of a function, which sees its generic params.
+                    Some(function_from_source(db, file_id, fn_def)?.resolver(db))
                 } else if let Some(module) = ast::Module::cast(node) {
                     Some(module_from_declaration(db, file_id, module)?.resolver(db))
                 } else if let Some(_) = ast::SourceFile::cast(node) {
@@ -18,6 +18,8 @@ pub(crate) mod primitive;
 #[cfg(test)]
 mod tests;
 pub(crate) mod method_resolution;
+mod match_check;
+mod render;
 
 use std::borrow::Cow;
 use std::iter::repeat;
@@ -32,6 +34,9 @@ use rustc_hash::FxHashMap;
 
 use test_utils::tested_by;
 
+pub use self::match_check::MissingPattern;
+pub(crate) use self::match_check::{missing_match_arms, non_exhaustive_matches};
+
 use crate::{
     Function, Struct, StructField, Enum, EnumVariant, Path, Name,
     FnSignature, ModuleDef, AdtDef,
@@ -165,6 +170,16 @@ impl Substs {
     pub fn empty() -> Substs {
         Substs(Arc::new([]))
     }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Ty> {
+        self.0.iter()
+    }
+}
+
+impl From<Vec<Ty>> for Substs {
+    fn from(tys: Vec<Ty>) -> Substs {
+        Substs(tys.into())
+    }
 }
 
 /// A type. This is based on the `TyKind` enum in rustc (librustc/ty/sty.rs).
@@ -300,6 +315,16 @@ pub struct FnSig {
     output: Ty,
 }
 
+impl FnSig {
+    pub fn params(&self) -> &[Ty] {
+        &self.input
+    }
+
+    pub fn ret(&self) -> &Ty {
+        &self.output
+    }
+}
+
 impl Ty {
     pub(crate) fn from_hir(db: &impl HirDatabase, resolver: &Resolver, type_ref: &TypeRef) -> Self {
         match type_ref {
@@ -744,8 +769,14 @@ pub(super) fn type_for_field(db: &impl HirDatabase, field: StructField) -> Ty {
 pub struct InferenceResult {
     /// For each method call expr, records the function it resolves to.
     method_resolutions: FxHashMap<ExprId, Function>,
+    /// For each method call expr, records the inferred generic arguments of
+    /// the method.
+    method_substs: FxHashMap<ExprId, Substs>,
     /// For each field access expr, records the field it resolves to.
     field_resolutions: FxHashMap<ExprId, StructField>,
+    /// For each pattern naming a struct or an enum variant, records the
+    /// variant it resolves to.
+    variant_resolutions: FxHashMap<PatId, VariantDef>,
     type_of_expr: ArenaMap<ExprId, Ty>,
     type_of_pat: ArenaMap<PatId, Ty>,
 }
@@ -754,9 +785,15 @@ impl InferenceResult {
     pub fn method_resolution(&self, expr: ExprId) -> Option<Function> {
         self.method_resolutions.get(&expr).map(|it| *it)
     }
+    pub fn method_substs(&self, expr: ExprId) -> Option<Substs> {
+        self.method_substs.get(&expr).cloned()
+    }
     pub fn field_resolution(&self, expr: ExprId) -> Option<StructField> {
         self.field_resolutions.get(&expr).map(|it| *it)
     }
+    pub fn variant_resolution(&self, pat: PatId) -> Option<VariantDef> {
+        self.variant_resolutions.get(&pat).map(|it| *it)
+    }
 }
 
 impl Index<ExprId> for InferenceResult {
@@ -783,7 +820,9 @@ struct InferenceContext<'a, D: HirDatabase> {
     resolver: Resolver,
     var_unification_table: InPlaceUnificationTable<TypeVarId>,
     method_resolutions: FxHashMap<ExprId, Function>,
+    method_substs: FxHashMap<ExprId, Substs>,
     field_resolutions: FxHashMap<ExprId, StructField>,
+    variant_resolutions: FxHashMap<PatId, VariantDef>,
     type_of_expr: ArenaMap<ExprId, Ty>,
     type_of_pat: ArenaMap<PatId, Ty>,
     /// The return type of the function being inferred.
@@ -872,7 +911,9 @@ impl<'a, D: HirDatabase> InferenceContext<'a, D> {
     fn new(db: &'a D, body: Arc<Body>, resolver: Resolver) -> Self {
         InferenceContext {
             method_resolutions: FxHashMap::default(),
+            method_substs: FxHashMap::default(),
             field_resolutions: FxHashMap::default(),
+            variant_resolutions: FxHashMap::default(),
             type_of_expr: ArenaMap::default(),
             type_of_pat: ArenaMap::default(),
             var_unification_table: InPlaceUnificationTable::new(),
@@ -895,9 +936,20 @@ impl<'a, D: HirDatabase> InferenceContext<'a, D> {
             let resolved = self.resolve_ty_completely(&mut tv_stack, mem::replace(ty, Ty::Unknown));
             *ty = resolved;
         }
+        let mut method_substs = mem::replace(&mut self.method_substs, FxHashMap::default());
+        for substs in method_substs.values_mut() {
+            let resolved = substs
+                .0
+                .iter()
+                .map(|ty| self.resolve_ty_completely(&mut tv_stack, ty.clone()))
+                .collect::<Vec<_>>();
+            *substs = Substs(resolved.into());
+        }
         InferenceResult {
             method_resolutions: self.method_resolutions,
+            method_substs,
             field_resolutions: self.field_resolutions,
+            variant_resolutions: self.variant_resolutions,
             type_of_expr: expr_types,
             type_of_pat: pat_types,
         }
@@ -915,6 +967,10 @@ impl<'a, D: HirDatabase> InferenceContext<'a, D> {
         self.field_resolutions.insert(expr, field);
     }
 
+    fn write_variant_resolution(&mut self, pat: PatId, variant: VariantDef) {
+        self.variant_resolutions.insert(pat, variant);
+    }
+
     fn write_pat_ty(&mut self, pat: PatId, ty: Ty) {
         self.type_of_pat.insert(pat, ty);
     }
@@ -962,6 +1018,18 @@ impl<'a, D: HirDatabase> InferenceContext<'a, D> {
                 | (_, primitive::UncertainFloatTy::Unknown) => true,
                 _ => t1 == t2,
             },
+            (Ty::Infer(InferTy::TypeVar(tv1)), Ty::Infer(InferTy::TypeVar(tv2)))
+            | (Ty::Infer(InferTy::IntVar(tv1)), Ty::Infer(InferTy::IntVar(tv2)))
+            | (Ty::Infer(InferTy::FloatVar(tv1)), Ty::Infer(InferTy::FloatVar(tv2))) => {
+                // both type vars are unknown since we tried to resolve them
+                self.var_unification_table.union(*tv1, *tv2);
+                true
+            }
+            (Ty::Infer(InferTy::TypeVar(tv)), other) | (other, Ty::Infer(InferTy::TypeVar(tv))) => {
+                // the type var is unknown since we tried to resolve it
+                self.var_unification_table.union_value(*tv, TypeVarValue::Known(other.clone()));
+                true
+            }
             (Ty::Bool, _) | (Ty::Str, _) | (Ty::Never, _) | (Ty::Char, _) => ty1 == ty2,
             (
                 Ty::Adt { def_id: def_id1, substs: substs1, .. },
@@ -976,16 +1044,7 @@ impl<'a, D: HirDatabase> InferenceContext<'a, D> {
             (Ty::Tuple(ts1), Ty::Tuple(ts2)) if ts1.len() == ts2.len() => {
                 ts1.iter().zip(ts2.iter()).all(|(t1, t2)| self.unify_inner(t1, t2, depth + 1))
             }
-            (Ty::Infer(InferTy::TypeVar(tv1)), Ty::Infer(InferTy::TypeVar(tv2)))
-            | (Ty::Infer(InferTy::IntVar(tv1)), Ty::Infer(InferTy::IntVar(tv2)))
-            | (Ty::Infer(InferTy::FloatVar(tv1)), Ty::Infer(InferTy::FloatVar(tv2))) => {
-                // both type vars are unknown since we tried to resolve them
-                self.var_unification_table.union(*tv1, *tv2);
-                true
-            }
-            (Ty::Infer(InferTy::TypeVar(tv)), other)
-            | (other, Ty::Infer(InferTy::TypeVar(tv)))
-            | (Ty::Infer(InferTy::IntVar(tv)), other)
+            (Ty::Infer(InferTy::IntVar(tv)), other)
             | (other, Ty::Infer(InferTy::IntVar(tv)))
             | (Ty::Infer(InferTy::FloatVar(tv)), other)
             | (other, Ty::Infer(InferTy::FloatVar(tv))) => {
@@ -1131,6 +1190,40 @@ impl<'a, D: HirDatabase> InferenceContext<'a, D> {
         }
     }
 
+    /// If `path` refers to a struct or enum variant without fields, returns it.
+    fn resolve_unit_variant(&self, path: &Path) -> Option<VariantDef> {
+        let variant: VariantDef = match self.resolver.resolve_path(self.db, path).take_values()? {
+            Resolution::Def(ModuleDef::Struct(s)) => s.into(),
+            Resolution::Def(ModuleDef::EnumVariant(v)) => v.into(),
+            _ => return None,
+        };
+        if variant.variant_data(self.db).fields().is_none() {
+            Some(variant)
+        } else {
+            None
+        }
+    }
+
+    /// If `callee` is a path to a tuple struct or tuple enum variant, returns
+    /// the constructed variant.
+    fn resolve_tuple_constructor(&mut self, callee: ExprId) -> Option<VariantDef> {
+        let path = match &self.body[callee] {
+            Expr::Path(path) => path,
+            _ => return None,
+        };
+        let resolver = expr::resolver_for_expr(self.body.clone(), self.db, callee);
+        let variant: VariantDef = match resolver.resolve_path(self.db, path).take_values()? {
+            Resolution::Def(ModuleDef::Struct(s)) => s.into(),
+            Resolution::Def(ModuleDef::EnumVariant(v)) => v.into(),
+            _ => return None,
+        };
+        if variant.variant_data(self.db).is_tuple() {
+            Some(variant)
+        } else {
+            None
+        }
+    }
+
     fn resolve_variant(&mut self, path: Option<&Path>) -> (Ty, Option<VariantDef>) {
         let path = match path {
             Some(path) => path,
@@ -1177,11 +1270,15 @@ impl<'a, D: HirDatabase> InferenceContext<'a, D> {
 
     fn infer_tuple_struct_pat(
         &mut self,
+        pat: PatId,
         path: Option<&Path>,
         subpats: &[PatId],
         expected: &Ty,
     ) -> Ty {
         let (ty, def) = self.resolve_variant(path);
+        if let Some(def) = def {
+            self.write_variant_resolution(pat, def);
+        }
 
         self.unify(&ty, expected);
 
@@ -1198,8 +1295,17 @@ impl<'a, D: HirDatabase> InferenceContext<'a, D> {
         ty
     }
 
-    fn infer_struct_pat(&mut self, path: Option<&Path>, subpats: &[FieldPat], expected: &Ty) -> Ty {
+    fn infer_struct_pat(
+        &mut self,
+        pat: PatId,
+        path: Option<&Path>,
+        subpats: &[FieldPat],
+        expected: &Ty,
+    ) -> Ty {
         let (ty, def) = self.resolve_variant(path);
+        if let Some(def) = def {
+            self.write_variant_resolution(pat, def);
+        }
 
         self.unify(&ty, expected);
 
@@ -1218,6 +1324,15 @@ impl<'a, D: HirDatabase> InferenceContext<'a, D> {
     fn infer_pat(&mut self, pat: PatId, expected: &Ty) -> Ty {
         let body = Arc::clone(&self.body); // avoid borrow checker problem
 
+        // An identifier naming a unit struct or variant, like `None`, matches
+        // it instead of binding a variable.
+        let unit_variant = match &body[pat] {
+            Pat::Bind { mode: BindingAnnotation::Unannotated, name, subpat: None } => {
+                self.resolve_unit_variant(&name.clone().into())
+            }
+            _ => None,
+        };
+
         let ty = match &body[pat] {
             Pat::Tuple(ref args) => {
                 let expectations = match *expected {
@@ -1249,16 +1364,24 @@ impl<'a, D: HirDatabase> InferenceContext<'a, D> {
                 Ty::Ref(subty.into(), *mutability)
             }
             Pat::TupleStruct { path: ref p, args: ref subpats } => {
-                self.infer_tuple_struct_pat(p.as_ref(), subpats, expected)
+                self.infer_tuple_struct_pat(pat, p.as_ref(), subpats, expected)
             }
             Pat::Struct { path: ref p, args: ref fields } => {
-                self.infer_struct_pat(p.as_ref(), fields, expected)
+                self.infer_struct_pat(pat, p.as_ref(), fields, expected)
             }
             Pat::Path(path) => {
                 // TODO use correct resolver for the surrounding expression
                 let resolver = self.resolver.clone();
+                if let Some(variant) = self.resolve_unit_variant(path) {
+                    self.write_variant_resolution(pat, variant);
+                }
                 self.infer_path_expr(&resolver, &path).unwrap_or(Ty::Unknown)
             }
+            Pat::Bind { name, .. } if unit_variant.is_some() => {
+                self.write_variant_resolution(pat, unit_variant.unwrap());
+                let resolver = self.resolver.clone();
+                self.infer_path_expr(&resolver, &name.clone().into()).unwrap_or(Ty::Unknown)
+            }
             Pat::Bind { mode, name: _name, subpat } => {
                 let inner_ty = if let Some(subpat) = subpat {
                     self.infer_pat(*subpat, expected)
@@ -1276,6 +1399,7 @@ impl<'a, D: HirDatabase> InferenceContext<'a, D> {
                 self.write_pat_ty(pat, bound_ty);
                 return inner_ty;
             }
+            Pat::Lit(expr) => self.infer_expr(*expr, &Expectation::has_type(expected.clone())),
             _ => Ty::Unknown,
         };
         // use a new type variable if we got Ty::Unknown here
@@ -1306,18 +1430,18 @@ impl<'a, D: HirDatabase> InferenceContext<'a, D> {
                 then_ty
             }
             Expr::Block { statements, tail } => self.infer_block(statements, *tail, expected),
-            Expr::Loop { body } => {
+            Expr::Loop { body, .. } => {
                 self.infer_expr(*body, &Expectation::has_type(Ty::unit()));
                 // TODO handle break with value
                 Ty::Never
             }
-            Expr::While { condition, body } => {
+            Expr::While { condition, body, .. } => {
                 // while let is desugared to a match loop, so this is always simple while
                 self.infer_expr(*condition, &Expectation::has_type(Ty::Bool));
                 self.infer_expr(*body, &Expectation::has_type(Ty::unit()));
                 Ty::unit()
             }
-            Expr::For { iterable, body, pat } => {
+            Expr::For { iterable, body, pat, .. } => {
                 let _iterable_ty = self.infer_expr(*iterable, &Expectation::none());
                 self.infer_pat(*pat, &Ty::Unknown);
                 self.infer_expr(*body, &Expectation::has_type(Ty::unit()));
@@ -1350,11 +1474,23 @@ impl<'a, D: HirDatabase> InferenceContext<'a, D> {
                             sig.input.iter().map(|ty| ty.clone().subst(&substs)).collect();
                         (param_tys, ret_ty)
                     }
-                    _ => {
-                        // not callable
-                        // TODO report an error?
-                        (Vec::new(), Ty::Unknown)
-                    }
+                    _ => match self.resolve_tuple_constructor(*callee) {
+                        Some(variant) => {
+                            // the callee has the type of the constructed value
+                            let substs = callee_ty.substs().unwrap_or_else(Substs::empty);
+                            let param_tys = variant
+                                .fields(self.db)
+                                .iter()
+                                .map(|field| field.ty(self.db).subst(&substs))
+                                .collect();
+                            (param_tys, callee_ty.clone())
+                        }
+                        None => {
+                            // not callable
+                            // TODO report an error?
+                            (Vec::new(), Ty::Unknown)
+                        }
+                    },
                 };
                 let param_iter = param_tys.into_iter().chain(repeat(Ty::Unknown));
                 for (arg, param) in args.iter().zip(param_iter) {
@@ -1382,6 +1518,7 @@ impl<'a, D: HirDatabase> InferenceContext<'a, D> {
                         }
                     }
                     Ty::FnDef { substs, sig, .. } => {
+                        self.method_substs.insert(tgt_expr, substs.clone());
                         let ret_ty = sig.output.clone().subst(&substs);
 
                         if !sig.input.is_empty() {
@@ -0,0 +1,565 @@
+//! Checks whether the arms of a `match` cover all the values of the matched
+//! type, and finds the patterns which are missing.
+//!
+//! This is the usefulness algorithm from rustc (librustc_mir/hair/pattern/
+//! _match.rs), described in "Warnings for pattern matching" by Luc Maranget:
+//! the arms form a matrix of patterns, which is specialized by constructor
+//! one column at a time, while the witnesses of the values which are not
+//! matched are collected on the way back.
+
+use std::sync::Arc;
+
+use crate::{
+    Function, EnumVariant, HirDatabase, ModuleDef, AdtDef,
+    adt::VariantDef,
+    expr::{Body, Expr, ExprId, Literal, MatchArm, Pat, PatId},
+    resolve::{Resolver, Resolution},
+    ty::{InferenceResult, Substs, Ty},
+};
+
+/// A pattern which is not covered by the arms of a `match`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MissingPattern {
+    /// Any value, written as `_`.
+    Wild,
+    /// A struct or an enum variant, with the patterns for its fields.
+    Variant(VariantDef, Vec<MissingPattern>),
+    Tuple(Vec<MissingPattern>),
+    Ref(Box<MissingPattern>),
+    Bool(bool),
+}
+
+impl MissingPattern {
+    /// Renders the pattern as source code. Variants which `resolver` sees
+    /// under their own name, like `Some`, are not qualified with the enum,
+    /// and references are written without the `&`, relying on the default
+    /// binding modes.
+    pub fn to_source(&self, db: &impl HirDatabase, resolver: &Resolver) -> String {
+        match self {
+            MissingPattern::Wild => "_".to_string(),
+            MissingPattern::Bool(b) => b.to_string(),
+            MissingPattern::Ref(pat) => pat.to_source(db, resolver),
+            MissingPattern::Tuple(pats) => {
+                let pats = pats.iter().map(|it| it.to_source(db, resolver)).collect::<Vec<_>>();
+                if pats.len() == 1 {
+                    format!("({},)", pats[0])
+                } else {
+                    format!("({})", pats.join(", "))
+                }
+            }
+            MissingPattern::Variant(def, pats) => {
+                let path = variant_path(db, resolver, *def);
+                let data = def.variant_data(db);
+                if data.fields().is_none() {
+                    return path;
+                }
+                let pats = pats.iter().map(|it| it.to_source(db, resolver));
+                if data.is_tuple() {
+                    return format!("{}({})", path, pats.collect::<Vec<_>>().join(", "));
+                }
+                let fields = def
+                    .fields(db)
+                    .into_iter()
+                    .zip(pats)
+                    .map(|(field, pat)| match pat.as_str() {
+                        "_" => field.name(db).to_string(),
+                        _ => format!("{}: {}", field.name(db), pat),
+                    })
+                    .collect::<Vec<_>>();
+                format!("{} {{ {} }}", path, fields.join(", "))
+            }
+        }
+    }
+}
+
+fn variant_path(db: &impl HirDatabase, resolver: &Resolver, def: VariantDef) -> String {
+    let variant = match def {
+        VariantDef::Struct(it) => return it.name(db).map_or(String::new(), |it| it.to_string()),
+        VariantDef::EnumVariant(it) => it,
+    };
+    let name = match variant.name(db) {
+        Some(it) => it,
+        None => return String::new(),
+    };
+    let in_scope = resolver.resolve_name(&name).take_values()
+        == Some(Resolution::Def(ModuleDef::EnumVariant(variant)));
+    match variant.parent_enum(db).name(db) {
+        Some(enum_name) if !in_scope => format!("{}::{}", enum_name, name),
+        _ => name.to_string(),
+    }
+}
+
+/// The patterns which have to be added to the `match` to make it exhaustive.
+/// Nothing is reported for the parts of the patterns with unknown types.
+pub(crate) fn missing_match_arms(
+    db: &impl HirDatabase,
+    function: Function,
+    match_expr: ExprId,
+) -> Vec<MissingPattern> {
+    let body = function.body(db);
+    let infer = function.infer(db);
+    let cx = MatchCheckCtx { db, body: body.clone(), infer };
+    match &body[match_expr] {
+        Expr::Match { expr, arms } => cx.missing_patterns(*expr, arms),
+        _ => Vec::new(),
+    }
+}
+
+/// All the non-exhaustive `match` expressions of the function, together with
+/// the missing patterns.
+pub(crate) fn non_exhaustive_matches(
+    db: &impl HirDatabase,
+    function: Function,
+) -> Vec<(ExprId, Vec<MissingPattern>)> {
+    let body = function.body(db);
+    let infer = function.infer(db);
+    let cx = MatchCheckCtx { db, body: body.clone(), infer };
+    body.exprs()
+        .filter_map(|(id, expr)| match expr {
+            Expr::Match { expr, arms } => Some((id, cx.missing_patterns(*expr, arms))),
+            _ => None,
+        })
+        .filter(|(_, missing)| !missing.is_empty())
+        .collect()
+}
+
+/// The number of missing patterns above which a single `_` is reported
+/// instead, as nested enums and tuples have lots of them.
+const MAX_COMBINATIONS: usize = 64;
+
+/// A pattern, reduced to what matters for exhaustiveness.
+#[derive(Debug, Clone)]
+enum CheckedPat {
+    /// Matches everything, like `_` or a binding.
+    Wild,
+    Ctor(Ctor, Vec<CheckedPat>),
+}
+
+/// The ways to build a value of a type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Ctor {
+    Variant(EnumVariant),
+    /// The only constructor of structs, tuples and references.
+    Single,
+    Bool(bool),
+    /// A literal, which never covers a type on its own.
+    Opaque,
+    /// A range or a slice, which may cover a type together with others.
+    Range,
+}
+
+enum Ctors {
+    Finite(Vec<Ctor>),
+    /// Types like integers, whose range patterns are not checked.
+    Infinite,
+    Unknown,
+}
+
+struct MatchCheckCtx<'a, DB> {
+    db: &'a DB,
+    body: Arc<Body>,
+    infer: Arc<InferenceResult>,
+}
+
+impl<'a, DB: HirDatabase> MatchCheckCtx<'a, DB> {
+    fn missing_patterns(&self, scrutinee: ExprId, arms: &[MatchArm]) -> Vec<MissingPattern> {
+        let ty = &self.infer[scrutinee];
+        let rows = arms
+            .iter()
+            .filter(|arm| arm.guard.is_none())
+            .flat_map(|arm| arm.pats.iter())
+            .map(|&pat| vec![self.lower_pat(pat, ty)])
+            .collect::<Vec<_>>();
+        if rows.is_empty() {
+            return self.expand(ty);
+        }
+        self.witnesses(rows, std::slice::from_ref(ty))
+            .into_iter()
+            .filter_map(|mut it| it.pop())
+            .collect()
+    }
+
+    /// All the patterns for a `match` without arms: the variants of enums,
+    /// and the combinations of them for tuples.
+    fn expand(&self, ty: &Ty) -> Vec<MissingPattern> {
+        match ty {
+            Ty::Ref(inner, _) => {
+                self.expand(inner).into_iter().map(|it| MissingPattern::Ref(Box::new(it))).collect()
+            }
+            Ty::Tuple(tys) if !tys.is_empty() => {
+                let mut res = vec![Vec::new()];
+                for ty in tys.iter() {
+                    let pats = self.expand(ty);
+                    if res.len() * pats.len() > MAX_COMBINATIONS {
+                        return vec![MissingPattern::Wild];
+                    }
+                    res = res
+                        .into_iter()
+                        .flat_map(|prefix: Vec<MissingPattern>| {
+                            pats.iter().map(move |pat| {
+                                let mut prefix = prefix.clone();
+                                prefix.push(pat.clone());
+                                prefix
+                            })
+                        })
+                        .collect();
+                }
+                res.into_iter().map(MissingPattern::Tuple).collect()
+            }
+            _ => match self.ctors(ty) {
+                Ctors::Finite(ctors) if ctors != [Ctor::Single] => ctors
+                    .into_iter()
+                    .map(|ctor| {
+                        let wilds = vec![MissingPattern::Wild; self.ctor_tys(ctor, ty).len()];
+                        self.apply(ctor, ty, wilds)
+                    })
+                    .collect(),
+                Ctors::Unknown => Vec::new(),
+                _ => vec![MissingPattern::Wild],
+            },
+        }
+    }
+
+    /// The rows of values of `tys` which are not matched by any of `rows`.
+    fn witnesses(&self, rows: Vec<Vec<CheckedPat>>, tys: &[Ty]) -> Vec<Vec<MissingPattern>> {
+        if rows.is_empty() {
+            return vec![vec![MissingPattern::Wild; tys.len()]];
+        }
+        let (ty, rest_tys) = match tys.split_first() {
+            Some(it) => it,
+            None => return Vec::new(),
+        };
+        let has_ctor = rows.iter().any(|row| match row[0] {
+            CheckedPat::Ctor(..) => true,
+            CheckedPat::Wild => false,
+        });
+        let has_range = rows.iter().any(|row| match row[0] {
+            CheckedPat::Ctor(Ctor::Range, _) => true,
+            _ => false,
+        });
+        let ctors = match self.ctors(ty) {
+            Ctors::Finite(ctors) if has_ctor => ctors,
+            // Ranges may cover the type together, like `0..=127 | 128..=255`
+            // does for `u8`. That isn't checked, so they are assumed to,
+            // rather than reporting a false error.
+            Ctors::Infinite if has_range => {
+                let rows = rows.into_iter().map(|row| row[1..].to_vec()).collect();
+                return self.prepend_wild(self.witnesses(rows, rest_tys));
+            }
+            Ctors::Unknown => {
+                // Whatever the patterns are, they can't be checked.
+                let rows = rows.into_iter().map(|row| row[1..].to_vec()).collect();
+                return self.prepend_wild(self.witnesses(rows, rest_tys));
+            }
+            _ => {
+                let rows = rows
+                    .into_iter()
+                    .filter(|row| match row[0] {
+                        CheckedPat::Wild => true,
+                        CheckedPat::Ctor(..) => false,
+                    })
+                    .map(|row| row[1..].to_vec())
+                    .collect();
+                return self.prepend_wild(self.witnesses(rows, rest_tys));
+            }
+        };
+        let mut res = Vec::new();
+        for ctor in ctors {
+            let ctor_tys = self.ctor_tys(ctor, ty);
+            let arity = ctor_tys.len();
+            let rows = rows.iter().filter_map(|row| specialize(row, ctor, arity)).collect();
+            let tys = ctor_tys.into_iter().chain(rest_tys.iter().cloned()).collect::<Vec<_>>();
+            for mut witness in self.witnesses(rows, &tys) {
+                let rest = witness.split_off(arity);
+                let mut row = vec![self.apply(ctor, ty, witness)];
+                row.extend(rest);
+                res.push(row);
+            }
+            if res.len() > MAX_COMBINATIONS {
+                return vec![vec![MissingPattern::Wild; 1 + rest_tys.len()]];
+            }
+        }
+        res
+    }
+
+    fn prepend_wild(&self, witnesses: Vec<Vec<MissingPattern>>) -> Vec<Vec<MissingPattern>> {
+        witnesses
+            .into_iter()
+            .map(|witness| {
+                let mut row = vec![MissingPattern::Wild];
+                row.extend(witness);
+                row
+            })
+            .collect()
+    }
+
+    fn ctors(&self, ty: &Ty) -> Ctors {
+        match ty {
+            Ty::Adt { def_id: AdtDef::Enum(e), .. } => {
+                Ctors::Finite(e.variants(self.db).into_iter().map(Ctor::Variant).collect())
+            }
+            Ty::Adt { def_id: AdtDef::Struct(_), .. } | Ty::Tuple(_) | Ty::Ref(..) => {
+                Ctors::Finite(vec![Ctor::Single])
+            }
+            Ty::Bool => Ctors::Finite(vec![Ctor::Bool(true), Ctor::Bool(false)]),
+            Ty::Never => Ctors::Finite(Vec::new()),
+            Ty::Unknown | Ty::Infer(_) => Ctors::Unknown,
+            _ => Ctors::Infinite,
+        }
+    }
+
+    /// The types of the fields of `ctor`, when building a `ty`.
+    fn ctor_tys(&self, ctor: Ctor, ty: &Ty) -> Vec<Ty> {
+        let substs = ty.substs().unwrap_or_else(Substs::empty);
+        let fields = match (ctor, ty) {
+            (Ctor::Variant(v), _) => v.fields(self.db),
+            (Ctor::Single, Ty::Adt { def_id: AdtDef::Struct(s), .. }) => s.fields(self.db),
+            (Ctor::Single, Ty::Tuple(tys)) => return tys.to_vec(),
+            (Ctor::Single, Ty::Ref(inner, _)) => return vec![Ty::clone(inner)],
+            _ => return Vec::new(),
+        };
+        fields.into_iter().map(|field| field.ty(self.db).subst(&substs)).collect()
+    }
+
+    fn apply(&self, ctor: Ctor, ty: &Ty, mut args: Vec<MissingPattern>) -> MissingPattern {
+        match (ctor, ty) {
+            (Ctor::Variant(v), _) => MissingPattern::Variant(v.into(), args),
+            (Ctor::Single, Ty::Adt { def_id: AdtDef::Struct(s), .. }) => {
+                MissingPattern::Variant((*s).into(), args)
+            }
+            (Ctor::Single, Ty::Tuple(_)) => MissingPattern::Tuple(args),
+            (Ctor::Single, Ty::Ref(..)) => match args.pop() {
+                Some(inner) => MissingPattern::Ref(Box::new(inner)),
+                None => MissingPattern::Wild,
+            },
+            (Ctor::Bool(b), _) => MissingPattern::Bool(b),
+            _ => MissingPattern::Wild,
+        }
+    }
+
+    fn lower_pat(&self, pat: PatId, ty: &Ty) -> CheckedPat {
+        if let Some(variant) = self.infer.variant_resolution(pat) {
+            let ctor = match variant {
+                VariantDef::EnumVariant(v) => Ctor::Variant(v),
+                VariantDef::Struct(_) => Ctor::Single,
+            };
+            // Patterns on references to structs and enums.
+            if let Ty::Ref(inner, _) = ty {
+                return CheckedPat::Ctor(Ctor::Single, vec![self.lower_pat(pat, inner)]);
+            }
+            let field_tys = self.ctor_tys(ctor, ty);
+            let fields = variant.fields(self.db);
+            let args = match &self.body[pat] {
+                Pat::TupleStruct { args, .. } if args.len() == fields.len() => args
+                    .iter()
+                    .zip(field_tys.iter())
+                    .map(|(&it, ty)| self.lower_pat(it, ty))
+                    .collect(),
+                Pat::Struct { args, .. } => fields
+                    .iter()
+                    .zip(field_tys.iter())
+                    .map(|(field, ty)| {
+                        let name = field.name(self.db);
+                        match args.iter().find(|it| it.name == name) {
+                            Some(it) => self.lower_pat(it.pat, ty),
+                            None => CheckedPat::Wild,
+                        }
+                    })
+                    .collect(),
+                _ => vec![CheckedPat::Wild; field_tys.len()],
+            };
+            return CheckedPat::Ctor(ctor, args);
+        }
+        match (&self.body[pat], ty) {
+            (Pat::Bind { subpat: Some(subpat), .. }, _) => self.lower_pat(*subpat, ty),
+            (Pat::Ref { pat, .. }, Ty::Ref(inner, _)) => {
+                CheckedPat::Ctor(Ctor::Single, vec![self.lower_pat(*pat, inner)])
+            }
+            (Pat::Tuple(args), Ty::Tuple(tys)) if args.len() == tys.len() => {
+                let args = args.iter().zip(tys.iter()).map(|(&it, ty)| self.lower_pat(it, ty));
+                CheckedPat::Ctor(Ctor::Single, args.collect())
+            }
+            (Pat::Tuple(_), Ty::Ref(inner, _)) | (Pat::Lit(_), Ty::Ref(inner, _)) => {
+                CheckedPat::Ctor(Ctor::Single, vec![self.lower_pat(pat, inner)])
+            }
+            (Pat::Lit(expr), _) => match &self.body[*expr] {
+                Expr::Literal(Literal::Bool(b)) => CheckedPat::Ctor(Ctor::Bool(*b), Vec::new()),
+                _ => CheckedPat::Ctor(Ctor::Opaque, Vec::new()),
+            },
+            (Pat::Range { .. }, _) | (Pat::Slice { .. }, _) => {
+                CheckedPat::Ctor(Ctor::Range, Vec::new())
+            }
+            // Bindings, wildcards and the patterns we failed to resolve or
+            // to lower, like macro calls and, for now, ranges and slices.
+            // These are assumed to match anything, rather than reporting a
+            // false error.
+            _ => CheckedPat::Wild,
+        }
+    }
+}
+
+/// The rows of the matrix specialized by `ctor`: the patterns for its fields
+/// replace the first column, the rows for other constructors are dropped.
+fn specialize(row: &[CheckedPat], ctor: Ctor, arity: usize) -> Option<Vec<CheckedPat>> {
+    let mut res = match &row[0] {
+        CheckedPat::Wild => vec![CheckedPat::Wild; arity],
+        CheckedPat::Ctor(it, args) if *it == ctor && ctor != Ctor::Opaque => {
+            if args.len() == arity {
+                args.clone()
+            } else {
+                vec![CheckedPat::Wild; arity]
+            }
+        }
+        CheckedPat::Ctor(..) => return None,
+    };
+    res.extend(row[1..].iter().cloned());
+    Some(res)
+}
+
+#[cfg(test)]
+mod tests {
+    use ra_db::SourceDatabase;
+    use ra_syntax::{algo::find_node_at_offset, ast, AstNode};
+
+    use crate::{mock::MockDatabase, source_binder};
+
+    fn check_missing(fixture: &str, expected: &[&str]) {
+        let (db, pos) = MockDatabase::with_position(fixture);
+        let file = db.parse(pos.file_id);
+        let match_expr = find_node_at_offset::<ast::MatchExpr>(file.syntax(), pos.offset).unwrap();
+        let function =
+            source_binder::function_from_child_node(&db, pos.file_id, match_expr.syntax()).unwrap();
+        let resolver = function.resolver(&db);
+        let match_expr = ast::Expr::cast(match_expr.syntax()).unwrap();
+        let expr = function.body_syntax_mapping(&db).node_expr(match_expr).unwrap();
+        let missing = super::missing_match_arms(&db, function, expr)
+            .iter()
+            .map(|it| it.to_source(&db, &resolver))
+            .collect::<Vec<_>>();
+        assert_eq!(missing, expected);
+    }
+
+    #[test]
+    fn missing_variants() {
+        check_missing(
+            "
+            //- /lib.rs
+            enum E { A, B(u32), C { x: u32 } }
+            fn f(e: E) {
+                match e<|> {
+                    E::A => (),
+                }
+            }
+            ",
+            &["E::B(_)", "E::C { x }"],
+        );
+    }
+
+    #[test]
+    fn missing_nested_variants() {
+        check_missing(
+            "
+            //- /lib.rs
+            enum Option<T> { Some(T), None }
+            use Option::*;
+            enum E { A, B }
+            fn f(e: Option<E>) {
+                match e<|> {
+                    Some(E::A) => (),
+                }
+            }
+            ",
+            &["Some(E::B)", "None"],
+        );
+    }
+
+    #[test]
+    fn missing_tuple_and_reference_patterns() {
+        check_missing(
+            "
+            //- /lib.rs
+            enum E { A, B }
+            fn f(e: &E, b: bool) {
+                match (e, b)<|> {
+                    (E::A, _) => (),
+                    (E::B, true) => (),
+                }
+            }
+            ",
+            &["(E::B, false)"],
+        );
+    }
+
+    #[test]
+    fn exhaustive_matches() {
+        check_missing(
+            "
+            //- /lib.rs
+            enum Option<T> { Some(T), None }
+            use Option::*;
+            fn f(e: Option<u32>) {
+                match e<|> {
+                    Some(1) => (),
+                    Some(_) => (),
+                    None => (),
+                }
+            }
+            ",
+            &[],
+        );
+    }
+
+    #[test]
+    fn guarded_arms_and_literals_are_not_exhaustive() {
+        check_missing(
+            "
+            //- /lib.rs
+            fn f(x: u32) {
+                match x<|> {
+                    1 => (),
+                    y if y > 2 => (),
+                }
+            }
+            ",
+            &["_"],
+        );
+    }
+
+    #[test]
+    fn patterns_which_are_not_lowered_match_anything() {
+        check_missing(
+            "
+            //- /lib.rs
+            enum E { A, B }
+            fn f(e: E) {
+                match e<|> {
+                    E::A => (),
+                    m!() => (),
+                }
+            }
+            ",
+            &[],
+        );
+    }
+
+    #[test]
+    fn many_missing_patterns_are_reported_as_wildcards() {
+        check_missing(
+            "
+            //- /lib.rs
+            enum E { A, B, C, D, E, F }
+            fn f(e: E) {
+                match (e, e, e, e)<|> {
+                    (E::A, E::A, E::A, E::A) => (),
+                    (E::B, E::B, E::B, E::B) => (),
+                    (E::C, E::C, E::C, E::C) => (),
+                    (E::D, E::D, E::D, E::D) => (),
+                    (E::E, E::E, E::E, E::E) => (),
+                    (E::F, E::F, E::F, E::F) => (),
+                }
+            }
+            ",
+            &["(_, _, _, _)"],
+        );
+    }
+}
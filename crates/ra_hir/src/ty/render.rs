@@ -0,0 +1,316 @@
+//! Rendering of types as source code, as opposed to the `Display` impl, which
+//! is meant for humans and prints unresolved parts like `[unknown]`.
+
+use join_to_string::join;
+
+use crate::{
+    Module, ModuleDef, Name, AdtDef, HirDatabase,
+    resolve::Resolution,
+    code_model_impl::krate::def_visibility,
+    ty::{
+        Ty,
+        primitive::{UncertainIntTy, UncertainFloatTy},
+    },
+};
+
+impl Ty {
+    /// Renders the type as it would be written in `module`, with the paths
+    /// to the structs and enums shortened as far as the scope allows.
+    /// Returns `None` for types which can't be written down, like the
+    /// unknown ones or the types of functions.
+    pub fn to_source(&self, db: &impl HirDatabase, module: Module) -> Option<String> {
+        let res = match self {
+            Ty::Bool => "bool".to_string(),
+            Ty::Char => "char".to_string(),
+            Ty::Str => "str".to_string(),
+            Ty::Never => "!".to_string(),
+            // Integer and float variables which nothing constrains default to
+            // `i32` and `f64`.
+            Ty::Int(UncertainIntTy::Unknown) => "i32".to_string(),
+            Ty::Int(t) => t.ty_to_string().to_string(),
+            Ty::Float(UncertainFloatTy::Unknown) => "f64".to_string(),
+            Ty::Float(t) => t.ty_to_string().to_string(),
+            Ty::Slice(t) => format!("[{}]", t.to_source(db, module)?),
+            Ty::RawPtr(t, m) => format!("*{}{}", m.as_keyword_for_ptr(), t.to_source(db, module)?),
+            Ty::Ref(t, m) => format!("&{}{}", m.as_keyword_for_ref(), t.to_source(db, module)?),
+            Ty::Tuple(ts) => {
+                let ts = ts.iter().map(|t| t.to_source(db, module)).collect::<Option<Vec<_>>>()?;
+                if ts.len() == 1 {
+                    format!("({},)", ts[0])
+                } else {
+                    join(ts.iter()).surround_with("(", ")").separator(", ").to_string()
+                }
+            }
+            Ty::FnPtr(sig) => {
+                let input = sig
+                    .input
+                    .iter()
+                    .map(|t| t.to_source(db, module))
+                    .collect::<Option<Vec<_>>>()?;
+                let mut res =
+                    join(input.iter()).surround_with("fn(", ")").separator(", ").to_string();
+                if sig.output != Ty::unit() {
+                    res.push_str(" -> ");
+                    res.push_str(&sig.output.to_source(db, module)?);
+                }
+                res
+            }
+            Ty::Adt { def_id, name, substs } => {
+                let (def, def_module) = match *def_id {
+                    AdtDef::Struct(s) => (ModuleDef::Struct(s), s.module(db)),
+                    AdtDef::Enum(e) => (ModuleDef::Enum(e), e.module(db)),
+                };
+                let mut res = path_to_def(db, module, def, def_module, name)?;
+                if !substs.0.is_empty() {
+                    let substs = substs
+                        .iter()
+                        .map(|t| t.to_source(db, module))
+                        .collect::<Option<Vec<_>>>()?;
+                    join(substs.iter()).surround_with("<", ">").separator(", ").to_buf(&mut res);
+                }
+                res
+            }
+            Ty::Param { name, .. } => name.to_string(),
+            // The length of arrays is not known.
+            Ty::Array(_) | Ty::FnDef { .. } | Ty::Unknown | Ty::Infer(_) => return None,
+        };
+        Some(res)
+    }
+}
+
+/// The path to `def`, an item called `name` defined in `def_module`, from
+/// `from`. That's the name itself if it is in scope, a path relative to
+/// `from` for the items of its submodules, and an absolute one otherwise.
+/// Returns `None` if some segment of the path is not visible from `from`.
+fn path_to_def(
+    db: &impl HirDatabase,
+    from: Module,
+    def: ModuleDef,
+    def_module: Module,
+    name: &Name,
+) -> Option<String> {
+    if from.resolver(db).resolve_name(name).take_types() == Some(Resolution::Def(def)) {
+        return Some(name.to_string());
+    }
+    let krate = def_module.krate(db)?;
+    let from_krate = from.krate(db)?;
+    if krate != from_krate {
+        // Other crates can only be reached through their public paths.
+        let dep = from_krate.dependencies(db).into_iter().find(|it| it.krate == krate)?;
+        let path = krate.import_path(db, def)?;
+        let segments = Some(dep.name).into_iter().chain(path).map(|it| it.to_string());
+        return Some(segments.collect::<Vec<_>>().join("::"));
+    }
+    if !is_visible_from(db, def, def_module, from) {
+        return None;
+    }
+    let mut segments = vec![name.to_string()];
+    let mut module = def_module;
+    while module != from {
+        match module.parent(db) {
+            Some(parent) => {
+                if !is_visible_from(db, module.into(), parent, from) {
+                    return None;
+                }
+                segments.push(module.name(db)?.to_string());
+                module = parent;
+            }
+            None => {
+                segments.push("crate".to_string());
+                break;
+            }
+        }
+    }
+    segments.reverse();
+    Some(segments.join("::"))
+}
+
+/// Whether `def`, an item of `parent`, is visible from `from` in the same
+/// crate. Private items are visible in their module and its descendants;
+/// restrictions narrower than `pub(crate)` are treated as private.
+fn is_visible_from(db: &impl HirDatabase, def: ModuleDef, parent: Module, from: Module) -> bool {
+    match def_visibility(db, def) {
+        Some(ref it) if it == "pub" || it == "pub(crate)" => return true,
+        _ => (),
+    }
+    let mut module = Some(from);
+    while let Some(it) = module {
+        if it == parent {
+            return true;
+        }
+        module = it.parent(db);
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use ra_db::SourceDatabase;
+    use ra_syntax::{
+        ast::{self, NameOwner},
+        AstNode,
+    };
+
+    use ra_db::FileId;
+
+    use crate::{mock::MockDatabase, source_binder};
+
+    fn check_let_types(content: &str, expected: &[Option<&str>]) {
+        let (db, _, file_id) = MockDatabase::with_single_file(content);
+        check_let_types_in_file(&db, file_id, expected);
+    }
+
+    /// Renders the types of the `let` bindings of the function `test`.
+    fn check_let_types_in_file(db: &MockDatabase, file_id: FileId, expected: &[Option<&str>]) {
+        let file = db.parse(file_id);
+        let fn_def = file
+            .syntax()
+            .descendants()
+            .filter_map(ast::FnDef::cast)
+            .find(|it| it.name().map(|it| it.text().to_string()) == Some("test".to_string()))
+            .unwrap();
+        let function = source_binder::function_from_source(db, file_id, fn_def).unwrap();
+        let infer = function.infer(db);
+        let syntax_mapping = function.body_syntax_mapping(db);
+        let module = function.module(db);
+        let types = fn_def
+            .syntax()
+            .descendants()
+            .filter_map(ast::LetStmt::cast)
+            .map(|it| {
+                let pat = syntax_mapping.node_pat(it.pat().unwrap()).unwrap();
+                infer[pat].to_source(db, module)
+            })
+            .collect::<Vec<_>>();
+        let expected = expected.iter().map(|it| it.map(str::to_string)).collect::<Vec<_>>();
+        assert_eq!(types, expected);
+    }
+
+    #[test]
+    fn renders_paths_from_the_scope() {
+        check_let_types(
+            "
+mod a {
+    pub struct S<T>(T);
+    pub mod b {
+        pub struct U;
+    }
+}
+use a::S;
+
+fn test() {
+    let s = S(a::b::U);
+    let u = a::b::U;
+}
+",
+            &[Some("S<a::b::U>"), Some("a::b::U")],
+        );
+    }
+
+    #[test]
+    fn renders_absolute_paths_outside_of_the_module() {
+        check_let_types(
+            "
+struct S;
+
+mod m {
+    fn test() {
+        let s = crate::S;
+    }
+}
+",
+            &[Some("crate::S")],
+        );
+    }
+
+    #[test]
+    fn does_not_render_paths_through_private_items() {
+        check_let_types(
+            "
+mod a {
+    pub struct Pub;
+    struct Private;
+    mod inner {
+        pub struct Hidden;
+    }
+    pub(crate) mod open {
+        pub struct Shown;
+    }
+    pub fn make() -> (Pub, Private, inner::Hidden, open::Shown) { loop {} }
+}
+
+fn test() {
+    let (p, q, h, s) = a::make();
+    let p = p;
+    let q = q;
+    let h = h;
+    let s = s;
+}
+",
+            &[None, Some("a::Pub"), None, None, Some("a::open::Shown")],
+        );
+    }
+
+    #[test]
+    fn renders_public_paths_of_other_crates() {
+        let mut db = MockDatabase::with_files(
+            "
+            //- /main.rs
+            fn test() {
+                let s = dep::make();
+            }
+
+            //- /lib.rs
+            mod private {
+                pub struct S;
+            }
+            pub use crate::private::S;
+            pub fn make() -> S { S }
+            ",
+        );
+        db.set_crate_graph_from_fixture(crate_graph! {
+            "main": ("/main.rs", ["dep"]),
+            "dep": ("/lib.rs", []),
+        });
+        let file_id = db.file_id_of("/main.rs");
+        check_let_types_in_file(&db, file_id, &[Some("dep::S")]);
+    }
+
+    #[test]
+    fn renders_builtin_types() {
+        check_let_types(
+            "
+fn test(r: &u8, m: &mut [u32], f: fn(u8) -> bool, g: fn()) {
+    let t = (1, 2.0, true, 'c');
+    let one = (r,);
+    let m = m;
+    let f = f;
+    let g = g;
+}
+",
+            &[
+                Some("(i32, f64, bool, char)"),
+                Some("(&u8,)"),
+                Some("&mut [u32]"),
+                Some("fn(u8) -> bool"),
+                Some("fn()"),
+            ],
+        );
+    }
+
+    #[test]
+    fn does_not_render_unnameable_types() {
+        check_let_types(
+            "
+fn g() {}
+
+fn test() {
+    let f = g;
+    let x = unknown;
+    let a = [1, 2];
+}
+",
+            &[None, None, None],
+        );
+    }
+}
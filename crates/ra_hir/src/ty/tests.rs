@@ -819,3 +819,18 @@ fn typing_whitespace_inside_a_function_should_not_invalidate_types() {
         assert!(!format!("{:?}", events).contains("infer"), "{:#?}", events)
     }
 }
+
+#[test]
+fn infer_generics_from_bool_and_char_args() {
+    check_inference(
+        "infer_generics_from_bool_and_char_args",
+        r#"
+fn id<T>(t: T) -> T { t }
+
+fn test() {
+    id(true);
+    id('c');
+}
+"#,
+    );
+}
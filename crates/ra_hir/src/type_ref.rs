@@ -79,7 +79,10 @@ impl TypeRef {
             }
             PlaceholderType(_inner) => TypeRef::Placeholder,
             FnPointerType(inner) => {
-                let ret_ty = TypeRef::from_ast_opt(inner.ret_type().and_then(|rt| rt.type_ref()));
+                let ret_ty = match inner.ret_type() {
+                    Some(rt) => TypeRef::from_ast_opt(rt.type_ref()),
+                    None => TypeRef::unit(),
+                };
                 let mut params = if let Some(pl) = inner.param_list() {
                     pl.params().map(|p| p.type_ref()).map(TypeRef::from_ast_opt).collect()
                 } else {
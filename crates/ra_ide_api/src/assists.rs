@@ -1,17 +1,31 @@
 use ra_db::{FileRange, FilePosition};
 
-use crate::{SourceFileEdit, SourceChange, db::RootDatabase};
+use crate::{SourceFileEdit, SourceChange, FileSystemEdit, db::RootDatabase};
 
 pub(crate) fn assists(db: &RootDatabase, frange: FileRange) -> Vec<SourceChange> {
     ra_assists::assists(db, frange)
         .into_iter()
         .map(|(label, action)| {
             let file_id = frange.file_id;
-            let file_edit = SourceFileEdit { file_id, edit: action.edit };
+            let mut source_file_edits = vec![SourceFileEdit { file_id, edit: action.edit }];
+            source_file_edits.extend(
+                action
+                    .other_file_edits
+                    .into_iter()
+                    .map(|(file_id, edit)| SourceFileEdit { file_id, edit }),
+            );
             SourceChange {
                 label: label.label,
-                source_file_edits: vec![file_edit],
-                file_system_edits: vec![],
+                source_file_edits,
+                file_system_edits: action
+                    .new_files
+                    .into_iter()
+                    .map(|it| FileSystemEdit::CreateFile {
+                        source_root: it.source_root,
+                        path: it.path,
+                        text: it.text,
+                    })
+                    .collect(),
                 cursor_position: action
                     .cursor_position
                     .map(|offset| FilePosition { offset, file_id }),
@@ -1,12 +1,12 @@
 use test_utils::tested_by;
 use ra_db::SourceDatabase;
 use ra_syntax::{
-    AstNode, SyntaxNode, TextUnit, TextRange,
-    SyntaxKind::FN_DEF,
+    AstNode, SyntaxNode, TextUnit,
+    SyntaxKind::{FN_DEF, COMMA, ATTR},
     ast::{self, ArgListOwner},
-    algo::find_node_at_offset,
+    algo::find_leaf_at_offset,
 };
-use hir::Docs;
+use hir::{Docs, Ty, ModuleDef, Resolution, FieldSource, TraitItem, source_binder};
 
 use crate::{FilePosition, CallInfo, db::RootDatabase};
 
@@ -19,43 +19,54 @@ pub(crate) fn call_info(db: &RootDatabase, position: FilePosition) -> Option<Cal
     let calling_node = FnCallNode::with_node(syntax, position.offset)?;
     let name_ref = calling_node.name_ref()?;
 
-    // Resolve the function's NameRef (NOTE: this isn't entirely accurate).
-    let file_symbols = crate::symbol_index::index_resolve(db, name_ref);
-    let symbol = file_symbols.into_iter().find(|it| it.ptr.kind() == FN_DEF)?;
-    let fn_file = db.parse(symbol.file_id);
-    let fn_def = symbol.ptr.to_node(&fn_file);
-    let fn_def = ast::FnDef::cast(fn_def).unwrap();
-    let function = hir::source_binder::function_from_source(db, symbol.file_id, fn_def)?;
+    let (mut call_info, has_self) = match resolve_callable(db, position, &calling_node) {
+        Some(Callable::Function(function, types)) => {
+            let has_self = function.signature(db).has_self_param();
+            (CallInfo::with_function(db, function, types)?, has_self)
+        }
+        Some(Callable::Constructor(variant, types)) => {
+            (CallInfo::with_constructor(db, variant, types)?, false)
+        }
+        None => {
+            // Resolve the function's NameRef (NOTE: this isn't entirely accurate).
+            let file_symbols = crate::symbol_index::index_resolve(db, name_ref);
+            let symbol = file_symbols.into_iter().find(|it| it.ptr.kind() == FN_DEF)?;
+            let fn_file = db.parse(symbol.file_id);
+            let fn_def = symbol.ptr.to_node(&fn_file);
+            let fn_def = ast::FnDef::cast(fn_def).unwrap();
+            let function = hir::source_binder::function_from_source(db, symbol.file_id, fn_def)?;
+            let has_self = function.signature(db).has_self_param();
+            (CallInfo::with_function(db, function, declared_param_types(db, function))?, has_self)
+        }
+    };
+    // The receiver of a method call is not a part of the argument list.
+    let skip_self = has_self && calling_node.is_method_call();
 
-    let mut call_info = CallInfo::new(db, function, fn_def)?;
     // If we have a calling expression let's find which argument we are on
     let num_params = call_info.parameters.len();
-    let has_self = fn_def.param_list().and_then(|l| l.self_param()).is_some();
 
     if num_params == 1 {
-        if !has_self {
+        if !skip_self {
             call_info.active_parameter = Some(0);
         }
     } else if num_params > 1 {
-        // Count how many parameters into the call we are.
-        // TODO: This is best effort for now and should be fixed at some point.
-        // It may be better to see where we are in the arg_list and then check
-        // where offset is in that list (or beyond).
-        // Revisit this after we get documentation comments in.
         if let Some(ref arg_list) = calling_node.arg_list() {
             let arg_list_range = arg_list.syntax().range();
             if !arg_list_range.contains_inclusive(position.offset) {
                 tested_by!(call_info_bad_offset);
                 return None;
             }
-            let start = arg_list_range.start();
 
-            let range_search = TextRange::from_to(start, position.offset);
-            let mut commas: usize =
-                arg_list.syntax().text().slice(range_search).to_string().matches(',').count();
+            // Only the commas of this argument list count, not those of
+            // nested calls.
+            let mut commas = arg_list
+                .syntax()
+                .children()
+                .filter(|it| it.kind() == COMMA && it.range().end() <= position.offset)
+                .count();
 
             // If we have a method call eat the first param since it's just self.
-            if has_self {
+            if skip_self {
                 commas += 1;
             }
 
@@ -66,20 +77,134 @@ pub(crate) fn call_info(db: &RootDatabase, position: FilePosition) -> Option<Cal
     Some(call_info)
 }
 
+enum Callable {
+    /// A function along with the types of its parameters, including `self`.
+    Function(hir::Function, Vec<String>),
+    /// A tuple struct or variant along with the types of its fields.
+    Constructor(hir::VariantDef, Vec<String>),
+}
+
+/// Resolves the callee using type inference, so that inferred generic
+/// arguments are substituted in the parameter types.
+fn resolve_callable(
+    db: &RootDatabase,
+    position: FilePosition,
+    calling_node: &FnCallNode,
+) -> Option<Callable> {
+    let function =
+        source_binder::function_from_child_node(db, position.file_id, calling_node.syntax())?;
+    let infer = function.infer(db);
+    let syntax_mapping = function.body_syntax_mapping(db);
+    match calling_node {
+        FnCallNode::MethodCallExpr(call) => {
+            let expr = syntax_mapping.node_expr(ast::Expr::cast(call.syntax()).unwrap())?;
+            let method = infer.method_resolution(expr)?;
+            let types = match (method.ty(db), infer.method_substs(expr)) {
+                (Ty::FnDef { sig, .. }, Some(substs)) => sig
+                    .params()
+                    .iter()
+                    .map(|ty| display_substituted(ty, ty.clone().subst(&substs)))
+                    .collect(),
+                _ => declared_param_types(db, method),
+            };
+            Some(Callable::Function(method, types))
+        }
+        FnCallNode::CallExpr(call) => {
+            let callee = call.expr()?;
+            match &infer[syntax_mapping.node_expr(callee)?] {
+                Ty::FnDef { def, sig, substs, .. } => {
+                    let types = sig
+                        .params()
+                        .iter()
+                        .map(|ty| display_substituted(ty, ty.clone().subst(substs)))
+                        .collect();
+                    Some(Callable::Function(*def, types))
+                }
+                Ty::Adt { substs, .. } => {
+                    let path = match callee.kind() {
+                        ast::ExprKind::PathExpr(it) => hir::Path::from_ast(it.path()?)?,
+                        _ => return None,
+                    };
+                    let resolver =
+                        source_binder::resolver_for_node(db, position.file_id, callee.syntax());
+                    let variant: hir::VariantDef =
+                        match resolver.resolve_path(db, &path).take_values()? {
+                            Resolution::Def(ModuleDef::Struct(it)) => it.into(),
+                            Resolution::Def(ModuleDef::EnumVariant(it)) => it.into(),
+                            _ => return None,
+                        };
+                    let types = variant
+                        .fields(db)
+                        .iter()
+                        .map(|field| {
+                            let ty = field.ty(db);
+                            display_substituted(&ty, ty.clone().subst(substs))
+                        })
+                        .collect();
+                    Some(Callable::Constructor(variant, types))
+                }
+                _ => None,
+            }
+        }
+    }
+}
+
+fn declared_param_types(db: &RootDatabase, function: hir::Function) -> Vec<String> {
+    match function.ty(db) {
+        Ty::FnDef { sig, .. } => sig.params().iter().map(|ty| ty.to_string()).collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Shows the type with generics substituted, unless some of them were not
+/// inferred.
+fn display_substituted(declared: &Ty, substituted: Ty) -> String {
+    let mut has_unknown = false;
+    substituted.walk(&mut |ty| {
+        if *ty == Ty::Unknown {
+            has_unknown = true;
+        }
+    });
+    if has_unknown {
+        declared.to_string()
+    } else {
+        substituted.to_string()
+    }
+}
+
 enum FnCallNode<'a> {
     CallExpr(&'a ast::CallExpr),
     MethodCallExpr(&'a ast::MethodCallExpr),
 }
 
 impl<'a> FnCallNode<'a> {
+    /// Finds the innermost call around the offset.
     pub fn with_node(syntax: &'a SyntaxNode, offset: TextUnit) -> Option<FnCallNode<'a>> {
-        if let Some(expr) = find_node_at_offset::<ast::CallExpr>(syntax, offset) {
-            return Some(FnCallNode::CallExpr(expr));
+        find_leaf_at_offset(syntax, offset).find_map(|leaf| {
+            leaf.ancestors().find_map(|node| {
+                if let Some(expr) = ast::CallExpr::cast(node) {
+                    return Some(FnCallNode::CallExpr(expr));
+                }
+                if let Some(expr) = ast::MethodCallExpr::cast(node) {
+                    return Some(FnCallNode::MethodCallExpr(expr));
+                }
+                None
+            })
+        })
+    }
+
+    pub fn syntax(&self) -> &'a SyntaxNode {
+        match *self {
+            FnCallNode::CallExpr(expr) => expr.syntax(),
+            FnCallNode::MethodCallExpr(expr) => expr.syntax(),
         }
-        if let Some(expr) = find_node_at_offset::<ast::MethodCallExpr>(syntax, offset) {
-            return Some(FnCallNode::MethodCallExpr(expr));
+    }
+
+    pub fn is_method_call(&self) -> bool {
+        match *self {
+            FnCallNode::CallExpr(_) => false,
+            FnCallNode::MethodCallExpr(_) => true,
         }
-        None
     }
 
     pub fn name_ref(&self) -> Option<&'a ast::NameRef> {
@@ -104,14 +229,74 @@ impl<'a> FnCallNode<'a> {
 }
 
 impl CallInfo {
-    fn new(db: &RootDatabase, function: hir::Function, node: &ast::FnDef) -> Option<Self> {
-        let label = crate::completion::function_label(node)?;
-        let doc = function.docs(db);
+    fn with_function(
+        db: &RootDatabase,
+        function: hir::Function,
+        parameter_types: Vec<String>,
+    ) -> Option<Self> {
+        let node = function.source(db).1;
+        let label = crate::completion::function_label(&node)?;
+        let doc = function.docs(db).or_else(|| trait_method_docs(db, function));
+
+        Some(CallInfo {
+            parameters: param_list(&node),
+            parameter_types,
+            label,
+            doc,
+            active_parameter: None,
+        })
+    }
 
-        Some(CallInfo { parameters: param_list(node), label, doc, active_parameter: None })
+    fn with_constructor(
+        db: &RootDatabase,
+        variant: hir::VariantDef,
+        parameter_types: Vec<String>,
+    ) -> Option<Self> {
+        let parameters = variant
+            .fields(db)
+            .iter()
+            .map(|field| match field.source(db).1 {
+                FieldSource::Pos(it) => {
+                    it.type_ref().map(|it| it.syntax().text().to_string()).unwrap_or_default()
+                }
+                FieldSource::Named(it) => it.syntax().text().to_string(),
+            })
+            .collect();
+        let (label, doc) = match variant {
+            hir::VariantDef::Struct(it) => {
+                (constructor_label(it.source(db).1.syntax()), it.docs(db))
+            }
+            hir::VariantDef::EnumVariant(it) => {
+                let enum_name = it.parent_enum(db).name(db)?;
+                let label =
+                    format!("{}::{}", enum_name, constructor_label(it.source(db).1.syntax()));
+                (label, it.docs(db))
+            }
+        };
+        Some(CallInfo { parameters, parameter_types, label, doc, active_parameter: None })
     }
 }
 
+/// Documentation of the trait method implemented by `function`, if any.
+fn trait_method_docs(db: &RootDatabase, function: hir::Function) -> Option<hir::Documentation> {
+    let tr = function.impl_block(db)?.target_trait(db)?;
+    let name = function.name(db);
+    tr.items(db).into_iter().find_map(|item| match item {
+        TraitItem::Function(it) if it.name(db) == name => it.docs(db),
+        _ => None,
+    })
+}
+
+/// The declaration of a struct or variant without attributes and comments.
+fn constructor_label(node: &SyntaxNode) -> String {
+    let label: String = node
+        .children()
+        .filter(|child| child.kind() != ATTR && ast::Comment::cast(child).is_none())
+        .map(|node| node.text().to_string())
+        .collect();
+    label.trim().trim_end_matches(';').to_owned()
+}
+
 fn param_list(node: &ast::FnDef) -> Vec<String> {
     let mut res = vec![];
     if let Some(param_list) = node.param_list() {
@@ -374,6 +559,111 @@ By default this method stops actor's `Context`."#
         );
     }
 
+    #[test]
+    fn test_fn_signature_substitutes_generics() {
+        let info = call_info(
+            r#"struct Wrapper<T>(T);
+fn foo<T>(x: T, y: Wrapper<T>) -> T { x }
+fn bar() { foo(1u32, <|>); }"#,
+        );
+
+        assert_eq!(info.parameters, vec!["x".to_string(), "y".to_string()]);
+        assert_eq!(info.parameter_types, vec!["u32".to_string(), "Wrapper<u32>".to_string()]);
+        assert_eq!(info.active_parameter, Some(1));
+    }
+
+    #[test]
+    fn test_fn_signature_substitutes_generics_of_method() {
+        let info = call_info(
+            r#"struct Wrapper<T>(T);
+struct F;
+impl F {
+    fn wrap<T>(&self, x: T, y: Wrapper<T>) -> T { x }
+}
+fn bar(f: F) { f.wrap(1u32, <|>); }"#,
+        );
+
+        assert_eq!(info.parameters, vec!["&self".to_string(), "x".to_string(), "y".to_string()]);
+        assert_eq!(
+            info.parameter_types,
+            vec!["&F".to_string(), "u32".to_string(), "Wrapper<u32>".to_string()]
+        );
+        assert_eq!(info.active_parameter, Some(2));
+    }
+
+    #[test]
+    fn test_fn_signature_in_nested_call() {
+        let info = call_info(
+            r#"fn foo(x: u32, y: u32) -> u32 { x + y }
+fn bar(a: u32, b: u32, c: u32) -> u32 { a }
+fn baz() { foo(bar(1, 2, 3), <|>); }"#,
+        );
+
+        assert_eq!(info.label, "fn foo(x: u32, y: u32) -> u32".to_string());
+        assert_eq!(info.active_parameter, Some(1));
+    }
+
+    #[test]
+    fn test_fn_signature_for_inner_method_call() {
+        let info = call_info(
+            r#"struct F;
+impl F {
+    fn do_it(&self, x: u32, y: u32) -> u32 { x }
+}
+fn foo(x: u32) -> u32 { x }
+fn bar(f: F) { foo(f.do_it(1, <|>)); }"#,
+        );
+
+        assert_eq!(info.label, "fn do_it(&self, x: u32, y: u32) -> u32".to_string());
+        assert_eq!(info.active_parameter, Some(2));
+    }
+
+    #[test]
+    fn test_fn_signature_for_tuple_struct_constructor() {
+        let info = call_info(
+            r#"
+/// A pair.
+struct Pair<T>(T, u32);
+fn bar() { Pair(1u8, <|>); }"#,
+        );
+
+        assert_eq!(info.label, "struct Pair<T>(T, u32)".to_string());
+        assert_eq!(info.parameters, vec!["T".to_string(), "u32".to_string()]);
+        assert_eq!(info.parameter_types, vec!["u8".to_string(), "u32".to_string()]);
+        assert_eq!(info.active_parameter, Some(1));
+        assert_eq!(info.doc.map(|it| it.into()), Some("A pair.".to_string()));
+    }
+
+    #[test]
+    fn test_fn_signature_for_enum_variant_constructor() {
+        let info = call_info(
+            r#"enum E { V(u32, bool) }
+fn bar() { E::V(<|>); }"#,
+        );
+
+        assert_eq!(info.label, "E::V(u32, bool)".to_string());
+        assert_eq!(info.active_parameter, Some(0));
+    }
+
+    #[test]
+    fn test_fn_signature_for_trait_method_uses_trait_docs() {
+        let info = call_info(
+            r#"trait Greet {
+    /// Says hello.
+    fn greet(&self, name: u32);
+}
+struct S;
+impl Greet for S {
+    fn greet(&self, name: u32) {}
+}
+fn bar(s: S) { s.greet(<|>); }"#,
+        );
+
+        assert_eq!(info.parameters, vec!["&self".to_string(), "name".to_string()]);
+        assert_eq!(info.active_parameter, Some(1));
+        assert_eq!(info.doc.map(|it| it.into()), Some("Says hello.".to_string()));
+    }
+
     #[test]
     fn call_info_bad_offset() {
         covers!(call_info_bad_offset);
@@ -8,6 +8,13 @@ mod complete_snippet;
 mod complete_path;
 mod complete_scope;
 mod complete_postfix;
+mod complete_import;
+mod complete_record;
+mod complete_trait_impl;
+mod complete_attribute;
+mod complete_macro;
+mod complete_lifetime;
+mod complete_mod;
 
 use ra_db::SourceDatabase;
 use ra_syntax::ast::{self, AstNode};
@@ -21,7 +28,11 @@ use crate::{
     },
 };
 
-pub use crate::completion::completion_item::{CompletionItem, CompletionItemKind, InsertTextFormat};
+pub(crate) use crate::completion::completion_context::CompletionDetails;
+
+pub use crate::completion::completion_item::{
+    CompletionItem, CompletionItemKind, CompletionScore, InsertTextFormat,
+};
 
 /// Main entry point for completion. We run completion as a two-phase process.
 ///
@@ -45,9 +56,13 @@ pub use crate::completion::completion_item::{CompletionItem, CompletionItemKind,
 /// `foo` *should* be present among the completion variants. Filtering by
 /// identifier prefix/fuzzy match should be done higher in the stack, together
 /// with ordering of completions (currently this is done by the client).
-pub(crate) fn completions(db: &db::RootDatabase, position: FilePosition) -> Option<Completions> {
+pub(crate) fn completions(
+    db: &db::RootDatabase,
+    position: FilePosition,
+    details: CompletionDetails,
+) -> Option<Completions> {
     let original_file = db.parse(position.file_id);
-    let ctx = CompletionContext::new(db, &original_file, position)?;
+    let ctx = CompletionContext::new(db, &original_file, position, details)?;
 
     let mut acc = Completions::default();
 
@@ -60,6 +75,13 @@ pub(crate) fn completions(db: &db::RootDatabase, position: FilePosition) -> Opti
     complete_scope::complete_scope(&mut acc, &ctx);
     complete_dot::complete_dot(&mut acc, &ctx);
     complete_postfix::complete_postfix(&mut acc, &ctx);
+    complete_import::complete_import(&mut acc, &ctx);
+    complete_record::complete_record(&mut acc, &ctx);
+    complete_trait_impl::complete_trait_impl(&mut acc, &ctx);
+    complete_attribute::complete_attribute(&mut acc, &ctx);
+    complete_macro::complete_macro(&mut acc, &ctx);
+    complete_lifetime::complete_lifetime(&mut acc, &ctx);
+    complete_mod::complete_mod(&mut acc, &ctx);
     Some(acc)
 }
 
@@ -0,0 +1,192 @@
+use ra_syntax::{
+    AstNode,
+    SyntaxKind::{EXCL, IDENT},
+};
+
+use crate::completion::{CompletionContext, Completions, CompletionItem, CompletionItemKind};
+use crate::completion::completion_item::CompletionKind;
+
+/// Attributes which can be applied to items, together with the snippet to
+/// insert for them.
+const ATTRIBUTES: &[(&str, &str)] = &[
+    ("allow", "allow(${0:lint})"),
+    ("cfg", "cfg(${0:predicate})"),
+    ("cfg_attr", "cfg_attr(${1:predicate}, ${0:attr})"),
+    ("cold", "cold"),
+    ("deny", "deny(${0:lint})"),
+    ("deprecated", "deprecated = \"${0:reason}\""),
+    ("derive", "derive(${0:Debug})"),
+    ("doc", "doc = \"${0:docs}\""),
+    ("forbid", "forbid(${0:lint})"),
+    ("ignore", "ignore"),
+    ("inline", "inline"),
+    ("link", "link(name = \"${0:name}\")"),
+    ("macro_export", "macro_export"),
+    ("macro_use", "macro_use"),
+    ("must_use", "must_use"),
+    ("no_mangle", "no_mangle"),
+    ("path", "path = \"${0:path}\""),
+    ("repr", "repr(${0:C})"),
+    ("should_panic", "should_panic"),
+    ("test", "test"),
+    ("warn", "warn(${0:lint})"),
+];
+
+/// Attributes which only make sense at the crate level, as in `#![no_std]`.
+const INNER_ATTRIBUTES: &[(&str, &str)] = &[
+    ("crate_name", "crate_name = \"${0:name}\""),
+    ("feature", "feature(${0:feature})"),
+    ("no_std", "no_std"),
+    ("recursion_limit", "recursion_limit = \"${0:128}\""),
+];
+
+const DERIVES: &[&str] =
+    &["Clone", "Copy", "Debug", "Default", "Eq", "Hash", "Ord", "PartialEq", "PartialOrd"];
+
+const LINTS: &[&str] = &[
+    "dead_code",
+    "deprecated",
+    "missing_docs",
+    "non_camel_case_types",
+    "non_snake_case",
+    "non_upper_case_globals",
+    "unreachable_code",
+    "unreachable_patterns",
+    "unsafe_code",
+    "unused",
+    "unused_imports",
+    "unused_must_use",
+    "unused_mut",
+    "unused_variables",
+    "warnings",
+];
+
+/// Completes attribute names in `#[<|>]`, traits in `#[derive(<|>)]` and lint
+/// names in `#[allow(<|>)]` and friends.
+pub(super) fn complete_attribute(acc: &mut Completions, ctx: &CompletionContext) {
+    let attr = match ctx.attribute_syntax {
+        Some(it) => it,
+        None => return,
+    };
+    let value = match attr.value() {
+        Some(it) => it,
+        None => return,
+    };
+    let token_tree = match ctx.leaf.parent() {
+        Some(it) => it,
+        None => return,
+    };
+
+    if token_tree == value.syntax() {
+        // Only the first token of the attribute is its name.
+        let is_name =
+            value.syntax().children().filter(|it| it.kind() == IDENT).all(|it| it == ctx.leaf);
+        if !is_name {
+            return;
+        }
+        let is_inner = attr.syntax().children().any(|it| it.kind() == EXCL);
+        let inner_attributes = if is_inner { INNER_ATTRIBUTES } else { &[] };
+        for (name, snippet) in ATTRIBUTES.iter().chain(inner_attributes) {
+            CompletionItem::new(CompletionKind::Attribute, ctx.source_range(), *name)
+                .kind(CompletionItemKind::Attribute)
+                .snippet(*snippet)
+                .add_to(acc);
+        }
+        return;
+    }
+
+    let (attr_name, args) = match attr.as_call() {
+        Some(it) => it,
+        None => return,
+    };
+    if args.syntax() != token_tree {
+        return;
+    }
+    let present = args
+        .syntax()
+        .children()
+        .filter(|it| it.kind() == IDENT && *it != ctx.leaf)
+        .filter_map(|it| it.leaf_text())
+        .collect::<Vec<_>>();
+    let (candidates, kind) = match attr_name.as_str() {
+        "derive" => (DERIVES, CompletionItemKind::Trait),
+        "allow" | "warn" | "deny" | "forbid" => (LINTS, CompletionItemKind::Attribute),
+        _ => return,
+    };
+    for candidate in candidates.iter().filter(|it| !present.iter().any(|p| p.as_str() == **it)) {
+        CompletionItem::new(CompletionKind::Attribute, ctx.source_range(), *candidate)
+            .kind(kind)
+            .add_to(acc);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::completion::{CompletionItem, CompletionKind};
+    use crate::completion::completion_item::do_completion;
+
+    fn attribute_completion_labels(code: &str) -> Vec<String> {
+        do_completion(code, CompletionKind::Attribute)
+            .iter()
+            .map(CompletionItem::label)
+            .map(String::from)
+            .collect()
+    }
+
+    #[test]
+    fn completes_attribute_names() {
+        let labels = attribute_completion_labels(
+            r"
+            #[<|>]
+            struct S;
+            ",
+        );
+        assert!(labels.contains(&"derive".to_string()));
+        assert!(labels.contains(&"must_use".to_string()));
+        assert!(!labels.contains(&"no_std".to_string()));
+
+        let labels = attribute_completion_labels(
+            r"
+            #![no<|>]
+            ",
+        );
+        assert!(labels.contains(&"no_std".to_string()));
+    }
+
+    #[test]
+    fn completes_derivable_traits() {
+        let labels = attribute_completion_labels(
+            r"
+            #[derive(Debug, <|>)]
+            struct S;
+            ",
+        );
+        assert_eq!(
+            labels,
+            vec!["Clone", "Copy", "Default", "Eq", "Hash", "Ord", "PartialEq", "PartialOrd"]
+        );
+    }
+
+    #[test]
+    fn completes_lint_names() {
+        let labels = attribute_completion_labels(
+            r"
+            #[allow(dead_code, unu<|>)]
+            fn foo() {}
+            ",
+        );
+        assert!(labels.contains(&"unused_variables".to_string()));
+        assert!(!labels.contains(&"dead_code".to_string()));
+    }
+
+    #[test]
+    fn no_attribute_completion_after_attribute() {
+        let labels = attribute_completion_labels(
+            r"
+            #[derive(Debug)]<|>
+            struct S;
+            ",
+        );
+        assert!(labels.is_empty());
+    }
+}
@@ -1,7 +1,7 @@
 use hir::{Ty, AdtDef, Docs};
 
 use crate::completion::{CompletionContext, Completions, CompletionItem, CompletionItemKind};
-use crate::completion::completion_item::CompletionKind;
+use crate::completion::completion_item::{CompletionKind, compute_score};
 
 /// Complete dot accesses, i.e. fields or methods (currently only fields).
 pub(super) fn complete_dot(acc: &mut Completions, ctx: &CompletionContext) {
@@ -29,14 +29,17 @@ fn complete_fields(acc: &mut Completions, ctx: &CompletionContext, receiver: Ty)
                 match def_id {
                     AdtDef::Struct(s) => {
                         for field in s.fields(ctx.db) {
+                            let name = field.name(ctx.db).to_string();
+                            let ty = field.ty(ctx.db).subst(substs);
                             CompletionItem::new(
                                 CompletionKind::Reference,
                                 ctx.source_range(),
-                                field.name(ctx.db).to_string(),
+                                name.clone(),
                             )
                             .kind(CompletionItemKind::Field)
-                            .detail(field.ty(ctx.db).subst(substs).to_string())
-                            .set_documentation(field.docs(ctx.db))
+                            .detail(ty.to_string())
+                            .documentation_with(ctx, || field.docs(ctx.db))
+                            .set_score(compute_score(ctx, &ty, &name))
                             .add_to(acc);
                         }
                     }
@@ -54,6 +57,7 @@ fn complete_fields(acc: &mut Completions, ctx: &CompletionContext, receiver: Ty)
                     )
                     .kind(CompletionItemKind::Field)
                     .detail(ty.to_string())
+                    .set_score(compute_score(ctx, ty, &i.to_string()))
                     .add_to(acc);
                 }
             }
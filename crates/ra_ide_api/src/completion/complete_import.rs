@@ -0,0 +1,219 @@
+use std::iter;
+
+use rustc_hash::FxHashSet;
+use ra_db::SourceDatabase;
+use ra_syntax::{
+    SyntaxNode, SmolStr,
+    SyntaxKind::{IDENT, VISIBILITY},
+};
+use ra_text_edit::TextEditBuilder;
+use hir::{ModuleDef, PerNs, Resolution, source_binder};
+
+use crate::{
+    Query,
+    completion::{CompletionItem, Completions, CompletionKind, CompletionContext},
+    symbol_index::{self, FileSymbol},
+};
+
+/// The number of symbols we look at per query, to keep completion snappy.
+const SYMBOL_LIMIT: usize = 40;
+
+/// Completes public items from the dependencies of the current crate which
+/// are not in scope yet. Accepting such a completion also adds a `use` for
+/// the item.
+pub(super) fn complete_import(acc: &mut Completions, ctx: &CompletionContext) {
+    if !ctx.is_trivial_path || ctx.use_item_syntax.is_some() || ctx.leaf.kind() != IDENT {
+        return;
+    }
+    let krate = match ctx.module.and_then(|module| module.krate(ctx.db)) {
+        Some(it) => it,
+        None => return,
+    };
+    let dependencies = krate.dependencies(ctx.db);
+    if dependencies.is_empty() {
+        return;
+    }
+    let in_scope: FxHashSet<String> =
+        ctx.resolver.all_names().keys().map(|name| name.to_string()).collect();
+
+    let mut symbols = Vec::new();
+    for &libs in [false, true].iter() {
+        let mut query = Query::new(ctx.leaf.text().to_string());
+        if libs {
+            query.libs();
+        }
+        query.limit(SYMBOL_LIMIT);
+        symbols.extend(symbol_index::world_symbols(ctx.db, query));
+    }
+
+    let mut seen = Vec::new();
+    for symbol in symbols {
+        if in_scope.contains(symbol.name.as_str()) {
+            continue;
+        }
+        let def = match symbol_def(ctx, &symbol) {
+            Some(it) => it,
+            None => continue,
+        };
+        let module = match def_module(ctx, def) {
+            Some(it) => it,
+            None => continue,
+        };
+        let dep = dependencies.iter().find(|dep| module.krate(ctx.db) == Some(dep.krate));
+        let dep = match dep {
+            Some(it) => it,
+            None => continue,
+        };
+        // Only the items with a public path can be imported. The paths are
+        // computed once per crate, so the same items are completed with and
+        // without details, and resolving a completion finds the same item at
+        // its index.
+        let path = match dep.krate.import_path(ctx.db, def) {
+            Some(it) => it,
+            None => continue,
+        };
+        if seen.contains(&def) {
+            continue;
+        }
+        seen.push(def);
+
+        let label = symbol.name.to_string();
+        let mut item = CompletionItem::new(CompletionKind::Magic, ctx.source_range(), label)
+            .from_resolution(ctx, &PerNs::types(Resolution::Def(def)));
+        if ctx.wants_details(&symbol.name) {
+            let path = iter::once(&dep.name)
+                .chain(path.iter())
+                .map(|it| SmolStr::new(it.to_string()))
+                .collect::<Vec<_>>();
+            let mut edit = TextEditBuilder::default();
+            ra_assists::auto_import_text_edit(ctx.leaf, &path, &mut edit);
+            item = item
+                .detail(path.iter().map(SmolStr::as_str).collect::<Vec<_>>().join("::"))
+                .text_edit(edit.finish());
+        }
+        item.add_to(acc);
+    }
+}
+
+/// Finds the definition a public item from the symbol index refers to.
+fn symbol_def(ctx: &CompletionContext, symbol: &FileSymbol) -> Option<ModuleDef> {
+    let file = ctx.db.parse(symbol.file_id);
+    let node = symbol.ptr.to_node(&file);
+    if !is_public(node) {
+        return None;
+    }
+    let module = source_binder::module_from_child_node(ctx.db, symbol.file_id, node.parent()?)?;
+    let def = module
+        .scope(ctx.db)
+        .entries()
+        .find(|(name, res)| res.import.is_none() && name.to_string() == symbol.name.as_str())
+        .map(|(_, res)| res.def)?;
+    def.take_types().or_else(|| def.take_values())
+}
+
+fn def_module(ctx: &CompletionContext, def: ModuleDef) -> Option<hir::Module> {
+    let module = match def {
+        ModuleDef::Module(it) => it.parent(ctx.db)?,
+        ModuleDef::Function(it) => it.module(ctx.db),
+        ModuleDef::Struct(it) => it.module(ctx.db),
+        ModuleDef::Enum(it) => it.module(ctx.db),
+        ModuleDef::EnumVariant(it) => it.parent_enum(ctx.db).module(ctx.db),
+        ModuleDef::Const(it) => it.module(ctx.db),
+        ModuleDef::Static(it) => it.module(ctx.db),
+        ModuleDef::Trait(it) => it.module(ctx.db),
+        ModuleDef::Type(it) => it.module(ctx.db),
+    };
+    Some(module)
+}
+
+/// Whether the item is visible from other crates, that is, declared with a
+/// plain `pub`.
+fn is_public(node: &SyntaxNode) -> bool {
+    node.children().any(|child| child.kind() == VISIBILITY && child.text() == "pub")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::completion::CompletionKind;
+    use crate::completion::completion_item::check_completion;
+    use crate::mock_analysis::analysis_and_position;
+
+    fn check_magic_completion(name: &str, code: &str) {
+        check_completion(name, code, CompletionKind::Magic);
+    }
+
+    #[test]
+    fn completes_and_imports_items_from_dependencies() {
+        check_magic_completion(
+            "completes_and_imports_items_from_dependencies",
+            "
+            //- /lib.rs
+            fn main() {
+                let _ = Ha<|>
+            }
+
+            //- /dep/lib.rs
+            pub mod collections {
+                pub use self::hash_map::HashMap;
+                pub mod hash_map {
+                    pub struct HashMap;
+                }
+            }
+            mod private {
+                pub struct HashSet;
+            }
+            ",
+        );
+    }
+
+    #[test]
+    fn resolves_the_items_completed_without_details() {
+        let (analysis, position) = analysis_and_position(
+            "
+            //- /lib.rs
+            fn main() {
+                let _ = Ha<|>
+            }
+
+            //- /dep/lib.rs
+            mod private {
+                pub struct HashSet;
+            }
+            pub mod collections {
+                pub struct HashMap;
+            }
+            ",
+        );
+        let items = analysis.completions_without_details(position).unwrap().unwrap();
+        assert!(items.iter().all(|it| it.label() != "HashSet"));
+        let index = items.iter().position(|it| it.label() == "HashMap").unwrap();
+        let item = analysis.resolve_completion(position, index, "HashMap").unwrap().unwrap();
+        assert_eq!(item.detail(), Some("dep::collections::HashMap"));
+    }
+
+    #[test]
+    fn completes_items_reexported_from_private_modules() {
+        let (analysis, position) = analysis_and_position(
+            "
+            //- /lib.rs
+            fn main() {
+                let _ = Ha<|>
+            }
+
+            //- /dep/lib.rs
+            pub mod collections {
+                pub use crate::hash::map::HashMap;
+            }
+            mod hash {
+                pub mod map {
+                    pub struct HashMap;
+                }
+            }
+            ",
+        );
+        let items = analysis.completions_without_details(position).unwrap().unwrap();
+        let index = items.iter().position(|it| it.label() == "HashMap").unwrap();
+        let item = analysis.resolve_completion(position, index, "HashMap").unwrap().unwrap();
+        assert_eq!(item.detail(), Some("dep::collections::HashMap"));
+    }
+}
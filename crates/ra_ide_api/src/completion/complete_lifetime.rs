@@ -0,0 +1,78 @@
+use crate::completion::{CompletionContext, Completions, CompletionItem, CompletionItemKind};
+use crate::completion::completion_item::CompletionKind;
+
+/// Completes the lifetimes in scope in `&'<|> str` and the labels of the
+/// enclosing loops in `break '<|>`.
+pub(super) fn complete_lifetime(acc: &mut Completions, ctx: &CompletionContext) {
+    if ctx.lifetime_range.is_none() {
+        return;
+    }
+    if ctx.is_label_ref {
+        for label in ctx.resolver.all_labels() {
+            CompletionItem::new(CompletionKind::Reference, ctx.source_range(), label.to_string())
+                .kind(CompletionItemKind::Label)
+                .add_to(acc);
+        }
+        return;
+    }
+    for lifetime in ctx.resolver.all_lifetimes() {
+        CompletionItem::new(CompletionKind::Reference, ctx.source_range(), lifetime.to_string())
+            .kind(CompletionItemKind::Lifetime)
+            .add_to(acc);
+    }
+    CompletionItem::new(CompletionKind::Keyword, ctx.source_range(), "'static")
+        .kind(CompletionItemKind::Lifetime)
+        .add_to(acc);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::completion::{CompletionItem, CompletionKind};
+    use crate::completion::completion_item::do_completion;
+
+    fn lifetime_completion_labels(code: &str) -> Vec<String> {
+        let mut labels = Vec::new();
+        for kind in [CompletionKind::Reference, CompletionKind::Keyword].iter() {
+            labels.extend(
+                do_completion(code, *kind).iter().map(CompletionItem::label).map(String::from),
+            );
+        }
+        labels
+    }
+
+    #[test]
+    fn completes_lifetimes_in_scope() {
+        let labels = lifetime_completion_labels(
+            r"
+            fn foo<'a, 'b>(x: &'<|>) {}
+            ",
+        );
+        assert_eq!(labels, vec!["'a", "'b", "'static"]);
+    }
+
+    #[test]
+    fn completes_labels_of_enclosing_loops() {
+        let labels = lifetime_completion_labels(
+            r"
+            fn foo() {
+                'outer: loop {
+                    'inner: for x in xs {
+                        break '<|>
+                    }
+                }
+            }
+            ",
+        );
+        assert_eq!(labels, vec!["'inner", "'outer"]);
+    }
+
+    #[test]
+    fn no_completion_in_lifetime_declarations() {
+        let labels = lifetime_completion_labels(
+            r"
+            fn foo<'a<|>>() {}
+            ",
+        );
+        assert!(labels.is_empty());
+    }
+}
@@ -0,0 +1,366 @@
+use rustc_hash::FxHashSet;
+use ra_syntax::{
+    AstNode, SmolStr, SyntaxNode, TextUnit,
+    ast::{self, AstToken, AttrsOwner, NameOwner},
+    SyntaxKind::*,
+};
+use hir::{Documentation, ModuleSource, source_binder};
+
+use crate::completion::{
+    CompletionItem, CompletionItemKind, Completions, CompletionKind, CompletionContext,
+};
+
+/// Completes macros: the `macro_rules!` defined before the cursor or in the
+/// `#[macro_use]` modules declared before it, and the ones exported from std
+/// and the `#[macro_use] extern crate`s.
+pub(super) fn complete_macro(acc: &mut Completions, ctx: &CompletionContext) {
+    if !(ctx.is_trivial_path || ctx.is_new_item) || ctx.use_item_syntax.is_some() {
+        return;
+    }
+    let mut seen = FxHashSet::default();
+
+    // Macros are scoped textually, so only the definitions which come before
+    // the cursor in one of the enclosing blocks or modules are visible. For a
+    // module in its own file, this includes the definitions which come before
+    // its `mod` declaration in the parent module.
+    let mut module = ctx.module;
+    add_macros_in_scope(acc, ctx, ctx.leaf, ctx.offset, &mut module, &mut seen);
+    while let Some((_, decl)) = module.and_then(|it| it.declaration_source(ctx.db)) {
+        let parent = match decl.syntax().parent() {
+            Some(it) => it,
+            None => break,
+        };
+        module = module.and_then(|it| it.parent(ctx.db));
+        let offset = decl.syntax().range().start();
+        add_macros_in_scope(acc, ctx, parent, offset, &mut module, &mut seen);
+    }
+
+    let krate = match ctx.module.and_then(|module| module.krate(ctx.db)) {
+        Some(it) => it,
+        None => return,
+    };
+    let macro_use_crates = macro_use_extern_crates(ctx, krate);
+    for dep in krate.dependencies(ctx.db) {
+        let name = dep.name.to_string();
+        if name != "std" && name != "core" && !macro_use_crates.contains(&name) {
+            continue;
+        }
+        for macro_call in source_binder::exported_macros(ctx.db, dep.krate) {
+            add_macro(acc, ctx, &macro_call, &mut seen);
+        }
+    }
+}
+
+/// Adds the macros defined before `offset` in `node` and in its ancestors.
+/// `module` follows the inline modules the walk leaves, and ends up at the
+/// module of the file.
+fn add_macros_in_scope(
+    acc: &mut Completions,
+    ctx: &CompletionContext,
+    node: &SyntaxNode,
+    offset: TextUnit,
+    module: &mut Option<hir::Module>,
+    seen: &mut FxHashSet<SmolStr>,
+) {
+    for node in node.ancestors() {
+        let items = node.children().take_while(|it| it.range().end() <= offset);
+        for item in items {
+            if let Some(macro_call) = ast::MacroCall::cast(item) {
+                add_macro(acc, ctx, macro_call, seen);
+            } else if let (Some(decl), Some(module)) = (ast::Module::cast(item), *module) {
+                if has_atom_attr(decl, "macro_use") {
+                    if let Some(child) = child_module(ctx, module, decl) {
+                        add_module_macros(acc, ctx, child, seen);
+                    }
+                }
+            }
+        }
+        if ast::Module::cast(node).is_some() {
+            *module = module.and_then(|it| it.parent(ctx.db));
+        }
+    }
+}
+
+/// Adds the macros of a `#[macro_use]` module, including the ones of its own
+/// `#[macro_use]` children.
+fn add_module_macros(
+    acc: &mut Completions,
+    ctx: &CompletionContext,
+    module: hir::Module,
+    seen: &mut FxHashSet<SmolStr>,
+) {
+    let source = module.definition_source(ctx.db).1;
+    let items = match module_items(&source) {
+        Some(it) => it,
+        None => return,
+    };
+    for item in items.children() {
+        if let Some(macro_call) = ast::MacroCall::cast(item) {
+            add_macro(acc, ctx, macro_call, seen);
+        } else if let Some(decl) = ast::Module::cast(item) {
+            if has_atom_attr(decl, "macro_use") {
+                if let Some(child) = child_module(ctx, module, decl) {
+                    add_module_macros(acc, ctx, child, seen);
+                }
+            }
+        }
+    }
+}
+
+fn child_module(
+    ctx: &CompletionContext,
+    parent: hir::Module,
+    decl: &ast::Module,
+) -> Option<hir::Module> {
+    let name = decl.name()?.text().clone();
+    parent
+        .children(ctx.db)
+        .find(|child| child.name(ctx.db).map(|it| it.to_string() == name.as_str()) == Some(true))
+}
+
+/// The names of the crates imported with `#[macro_use] extern crate`, which is
+/// only allowed in the crate root.
+fn macro_use_extern_crates(ctx: &CompletionContext, krate: hir::Crate) -> Vec<String> {
+    let source = match krate.root_module(ctx.db) {
+        Some(root) => root.definition_source(ctx.db).1,
+        None => return Vec::new(),
+    };
+    let items = match module_items(&source) {
+        Some(it) => it,
+        None => return Vec::new(),
+    };
+    items
+        .children()
+        .filter_map(ast::ExternCrateItem::cast)
+        .filter(|it| has_atom_attr(*it, "macro_use"))
+        .filter_map(|it| it.name_ref())
+        .map(|it| it.text().to_string())
+        .collect()
+}
+
+fn has_atom_attr(node: &impl AttrsOwner, atom: &str) -> bool {
+    node.attrs().any(|attr| attr.as_atom().map(|it| it.as_str() == atom).unwrap_or(false))
+}
+
+/// Macro calls are not `ModuleItem`s, so they are looked up among the
+/// children of the module's syntax directly.
+fn module_items(source: &ModuleSource) -> Option<&SyntaxNode> {
+    match source {
+        ModuleSource::SourceFile(it) => Some(it.syntax()),
+        ModuleSource::Module(it) => it.item_list().map(|it| it.syntax()),
+    }
+}
+
+fn add_macro(
+    acc: &mut Completions,
+    ctx: &CompletionContext,
+    macro_call: &ast::MacroCall,
+    seen: &mut FxHashSet<SmolStr>,
+) {
+    let is_macro_rules = macro_call
+        .path()
+        .and_then(|it| it.segment())
+        .and_then(|it| it.name_ref())
+        .map(|it| it.text().as_str() == "macro_rules")
+        .unwrap_or(false);
+    if !is_macro_rules {
+        return;
+    }
+    let name = match macro_call.name() {
+        Some(it) => it.text().clone(),
+        None => return,
+    };
+    if !seen.insert(name.clone()) {
+        return;
+    }
+    // By convention, macros producing collections use square brackets.
+    let (bra, ket) = if name.as_str() == "vec" { ("[", "]") } else { ("(", ")") };
+    CompletionItem::new(CompletionKind::Reference, ctx.source_range(), format!("{}!", name))
+        .kind(CompletionItemKind::Macro)
+        .lookup_by(name.to_string())
+        .snippet(format!("{}!{}$0{}", name, bra, ket))
+        .detail(format!("macro_rules! {}", name))
+        .documentation_with(ctx, || doc_comment_text(macro_call).map(|it| Documentation::new(&it)))
+        .add_to(acc);
+}
+
+/// The parser doesn't attach comments to macro calls, so the doc comments of
+/// a macro definition are the siblings right before it.
+fn doc_comment_text(macro_call: &ast::MacroCall) -> Option<String> {
+    let mut lines = Vec::new();
+    let mut node = macro_call.syntax().prev_sibling();
+    while let Some(it) = node {
+        match it.kind() {
+            WHITESPACE if !it.leaf_text()?.contains("\n\n") => (),
+            COMMENT => match ast::Comment::cast(it) {
+                Some(comment) if comment.flavor() == ast::CommentFlavor::Doc => {
+                    let text = comment.text().as_str()[comment.prefix().len()..].to_string();
+                    lines.push(if text.starts_with(' ') { text[1..].to_string() } else { text });
+                }
+                _ => break,
+            },
+            _ => break,
+        }
+        node = it.prev_sibling();
+    }
+    if lines.is_empty() {
+        return None;
+    }
+    lines.reverse();
+    Some(lines.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::completion::{CompletionItemKind, CompletionKind};
+    use crate::completion::completion_item::do_completion;
+
+    fn macro_completions(code: &str) -> Vec<(String, String)> {
+        do_completion(code, CompletionKind::Reference)
+            .into_iter()
+            .filter(|it| it.kind() == Some(CompletionItemKind::Macro))
+            .map(|it| (it.label().to_string(), it.insert_text()))
+            .collect()
+    }
+
+    #[test]
+    fn completes_macros_defined_before_the_cursor() {
+        let completions = macro_completions(
+            r"
+            /// Makes a vector.
+            macro_rules! vec { () => {} }
+            macro_rules! foo { () => {} }
+            fn main() {
+                f<|>
+            }
+            macro_rules! bar { () => {} }
+            ",
+        );
+        assert_eq!(
+            completions,
+            vec![
+                ("foo!".to_string(), "foo!($0)".to_string()),
+                ("vec!".to_string(), "vec![$0]".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn completes_macros_with_their_docs() {
+        let completions = do_completion(
+            r"
+            /// Makes a vector.
+            /// Or an empty one.
+            macro_rules! vec { () => {} }
+            fn main() {
+                v<|>
+            }
+            ",
+            CompletionKind::Reference,
+        );
+        let vec = completions.iter().find(|it| it.label() == "vec!").unwrap();
+        assert_eq!(
+            vec.documentation().map(|it| it.contents().to_string()),
+            Some("Makes a vector.\nOr an empty one.".to_string())
+        );
+    }
+
+    #[test]
+    fn completes_macros_exported_from_dependencies() {
+        let completions = macro_completions(
+            "
+            //- /lib.rs
+            #[macro_use]
+            extern crate dep;
+            fn main() {
+                d<|>
+            }
+            //- /dep/lib.rs
+            #[macro_export]
+            macro_rules! dep_macro { () => {} }
+            macro_rules! private_macro { () => {} }
+            ",
+        );
+        assert_eq!(completions, vec![("dep_macro!".to_string(), "dep_macro!($0)".to_string())]);
+    }
+
+    #[test]
+    fn does_not_complete_macros_of_dependencies_without_macro_use() {
+        let completions = macro_completions(
+            "
+            //- /lib.rs
+            fn main() {
+                d<|>
+            }
+            //- /dep/lib.rs
+            #[macro_export]
+            macro_rules! dep_macro { () => {} }
+            ",
+        );
+        assert_eq!(completions, vec![]);
+    }
+
+    #[test]
+    fn completes_macros_from_macro_use_modules() {
+        let completions = macro_completions(
+            "
+            //- /lib.rs
+            #[macro_use]
+            mod macros;
+            mod inline {
+                #[macro_use]
+                mod nested {
+                    macro_rules! nested_macro { () => {} }
+                }
+                fn f() {
+                    m<|>
+                }
+            }
+            mod hidden;
+            //- /macros.rs
+            macro_rules! file_macro { () => {} }
+            #[macro_use]
+            mod inner {
+                macro_rules! inner_macro { () => {} }
+            }
+            //- /hidden.rs
+            macro_rules! hidden_macro { () => {} }
+            ",
+        );
+        assert_eq!(
+            completions,
+            vec![
+                ("file_macro!".to_string(), "file_macro!($0)".to_string()),
+                ("inner_macro!".to_string(), "inner_macro!($0)".to_string()),
+                ("nested_macro!".to_string(), "nested_macro!($0)".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn completes_macros_declared_before_the_module_in_its_parent() {
+        let completions = macro_completions(
+            "
+            //- /lib.rs
+            macro_rules! before { () => {} }
+            #[macro_use]
+            mod macros;
+            mod foo;
+            macro_rules! after { () => {} }
+            //- /macros.rs
+            macro_rules! file_macro { () => {} }
+            //- /foo.rs
+            fn f() {
+                m<|>
+            }
+            ",
+        );
+        assert_eq!(
+            completions,
+            vec![
+                ("before!".to_string(), "before!($0)".to_string()),
+                ("file_macro!".to_string(), "file_macro!($0)".to_string()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,92 @@
+use ra_db::SourceDatabase;
+
+use crate::completion::{CompletionContext, Completions, CompletionItem, CompletionItemKind};
+use crate::completion::completion_item::CompletionKind;
+
+/// Completes the names of the files which could be declared as children of
+/// the current module in `mod <|>`, but which no module includes yet.
+pub(super) fn complete_mod(acc: &mut Completions, ctx: &CompletionContext) {
+    if !ctx.is_module_decl {
+        return;
+    }
+    let module = match ctx.module {
+        Some(it) => it,
+        None => return,
+    };
+    for (name, file_id) in module.unclaimed_children(ctx.db) {
+        CompletionItem::new(CompletionKind::Reference, ctx.source_range(), name.to_string())
+            .kind(CompletionItemKind::Module)
+            .detail(ctx.db.file_relative_path(file_id).as_str())
+            .add_to(acc);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::completion::{CompletionItem, CompletionKind};
+    use crate::completion::completion_item::do_completion;
+
+    fn mod_completion_labels(code: &str) -> Vec<String> {
+        do_completion(code, CompletionKind::Reference)
+            .iter()
+            .map(CompletionItem::label)
+            .map(String::from)
+            .collect()
+    }
+
+    #[test]
+    fn completes_files_not_declared_as_modules() {
+        let labels = mod_completion_labels(
+            "
+            //- /lib.rs
+            mod foo;
+            mod <|>
+            //- /foo.rs
+            // foo
+            //- /bar.rs
+            // bar
+            //- /baz/mod.rs
+            // baz
+            //- /qux/quux.rs
+            // quux
+            //- /not-a-module.rs
+            // not a module
+            ",
+        );
+        assert_eq!(labels, vec!["bar", "baz"]);
+    }
+
+    #[test]
+    fn completes_files_in_the_module_directory() {
+        let labels = mod_completion_labels(
+            "
+            //- /lib.rs
+            mod foo;
+            //- /foo.rs
+            mod b<|>
+            //- /foo/bar.rs
+            // bar
+            //- /baz.rs
+            // baz
+            ",
+        );
+        assert_eq!(labels, vec!["bar"]);
+    }
+
+    #[test]
+    fn completes_files_in_the_directory_of_inline_modules() {
+        let labels = mod_completion_labels(
+            "
+            //- /lib.rs
+            mod tests {
+                mod <|>
+            }
+            //- /tests/foo.rs
+            // foo
+            //- /bar.rs
+            // bar
+            ",
+        );
+        assert_eq!(labels, vec!["foo"]);
+    }
+}
@@ -52,7 +52,7 @@ pub(super) fn complete_path(acc: &mut Completions, ctx: &CompletionContext) {
                         name.to_string(),
                     )
                     .kind(CompletionItemKind::EnumVariant)
-                    .set_documentation(variant.docs(ctx.db))
+                    .documentation_with(ctx, || variant.docs(ctx.db))
                     .set_detail(Some(detail))
                     .add_to(acc)
                 }
@@ -10,10 +10,11 @@ use crate::{
     CompletionItem
 };
 use ra_syntax::{
-    ast::AstNode,
+    ast::{AstNode, StructFlavor},
     TextRange
 };
 use ra_text_edit::TextEditBuilder;
+use hir::{Ty, AdtDef};
 
 fn postfix_snippet(ctx: &CompletionContext, label: &str, snippet: &str) -> Builder {
     let replace_range = ctx.source_range();
@@ -27,25 +28,101 @@ fn postfix_snippet(ctx: &CompletionContext, label: &str, snippet: &str) -> Build
 }
 
 pub(super) fn complete_postfix(acc: &mut Completions, ctx: &CompletionContext) {
-    if let Some(dot_receiver) = ctx.dot_receiver {
-        let receiver_text = dot_receiver.syntax().text().to_string();
+    let dot_receiver = match ctx.dot_receiver {
+        Some(it) => it,
+        None => return,
+    };
+    let receiver_text = dot_receiver.syntax().text().to_string();
+    let receiver_ty = receiver_ty(ctx).unwrap_or(Ty::Unknown);
+    // Without type information, we don't know which templates make sense.
+    let is_bool = match receiver_ty {
+        Ty::Bool | Ty::Unknown => true,
+        _ => false,
+    };
+    let receiver_enum = receiver_ty.autoderef(ctx.db).find_map(|ty| match ty {
+        Ty::Adt { def_id: AdtDef::Enum(it), .. } => Some(it),
+        _ => None,
+    });
+
+    if is_bool {
         postfix_snippet(ctx, "not", &format!("!{}", receiver_text)).add_to(acc);
         postfix_snippet(ctx, "if", &format!("if {} {{$0}}", receiver_text)).add_to(acc);
+        postfix_snippet(ctx, "while", &format!("while {} {{\n$0\n}}", receiver_text)).add_to(acc);
+    }
+    let match_arms = match receiver_enum {
+        Some(it) => variant_patterns(ctx, it),
+        None => Vec::new(),
+    };
+    let match_snippet = if match_arms.is_empty() {
+        format!("match {} {{\n${{1:_}} => {{$0\\}},\n}}", receiver_text)
+    } else {
+        let arms = match_arms
+            .iter()
+            .enumerate()
+            .map(|(i, pat)| format!("{} => {{{}}},\n", pat, if i == 0 { "$0" } else { "" }))
+            .collect::<String>();
+        format!("match {} {{\n{}}}", receiver_text, arms)
+    };
+    postfix_snippet(ctx, "match", &match_snippet).add_to(acc);
+    let wrapper = receiver_enum.and_then(|it| it.name(ctx.db)).map(|it| it.to_string());
+    let wrapped_pat = match wrapper.as_ref().map(String::as_str) {
+        Some("Option") => Some("Some"),
+        Some("Result") => Some("Ok"),
+        _ => None,
+    };
+    if let Some(pat) = wrapped_pat {
         postfix_snippet(
             ctx,
-            "match",
-            &format!("match {} {{\n${{1:_}} => {{$0\\}},\n}}", receiver_text),
+            "ifl",
+            &format!("if let {}(${{1:x}}) = {} {{\n$0\n}}", pat, receiver_text),
+        )
+        .add_to(acc);
+        postfix_snippet(
+            ctx,
+            "while",
+            &format!("while let {}(${{1:x}}) = {} {{\n$0\n}}", pat, receiver_text),
         )
         .add_to(acc);
-        postfix_snippet(ctx, "while", &format!("while {} {{\n$0\n}}", receiver_text)).add_to(acc);
-        postfix_snippet(ctx, "dbg", &format!("dbg!({})", receiver_text)).add_to(acc);
     }
+    postfix_snippet(ctx, "ref", &format!("&{}", receiver_text)).add_to(acc);
+    postfix_snippet(ctx, "refm", &format!("&mut {}", receiver_text)).add_to(acc);
+    postfix_snippet(ctx, "box", &format!("Box::new({})", receiver_text)).add_to(acc);
+    postfix_snippet(ctx, "ok", &format!("Ok({})", receiver_text)).add_to(acc);
+    postfix_snippet(ctx, "some", &format!("Some({})", receiver_text)).add_to(acc);
+    postfix_snippet(ctx, "dbg", &format!("dbg!({})", receiver_text)).add_to(acc);
+}
+
+fn receiver_ty(ctx: &CompletionContext) -> Option<Ty> {
+    let function = ctx.function?;
+    let expr = function.body_syntax_mapping(ctx.db).node_expr(ctx.dot_receiver?)?;
+    Some(function.infer(ctx.db)[expr].clone())
+}
+
+/// Patterns matching each variant of the enum, like `Foo::Bar(..)`.
+fn variant_patterns(ctx: &CompletionContext, enum_: hir::Enum) -> Vec<String> {
+    let enum_name = match enum_.name(ctx.db) {
+        Some(it) => it,
+        None => return Vec::new(),
+    };
+    enum_
+        .variants(ctx.db)
+        .into_iter()
+        .filter_map(|variant| {
+            let name = variant.name(ctx.db)?;
+            let fields = match variant.source(ctx.db).1.flavor() {
+                StructFlavor::Tuple(_) => "(..)",
+                StructFlavor::Named(_) => " { .. }",
+                StructFlavor::Unit => "",
+            };
+            Some(format!("{}::{}{}", enum_name, name, fields))
+        })
+        .collect()
 }
 
 #[cfg(test)]
 mod tests {
     use crate::completion::completion_item::CompletionKind;
-    use crate::completion::completion_item::check_completion;
+    use crate::completion::completion_item::{check_completion, do_completion};
 
     fn check_snippet_completion(test_name: &str, code: &str) {
         check_completion(test_name, code, CompletionKind::Postfix);
@@ -63,4 +140,41 @@ mod tests {
             "#,
         );
     }
+
+    fn postfix_insert_text(code: &str, label: &str) -> Option<String> {
+        do_completion(code, CompletionKind::Postfix)
+            .into_iter()
+            .find(|it| it.label() == label)
+            .map(|it| it.insert_text())
+    }
+
+    #[test]
+    fn postfix_match_completion_lists_enum_variants() {
+        let code = r"
+            enum E { A, B(u32), C { x: u32 } }
+            fn main(e: E) {
+                e.<|>
+            }
+            ";
+        assert_eq!(
+            postfix_insert_text(code, "match").unwrap(),
+            "match e {\nE::A => {$0},\nE::B(..) => {},\nE::C { .. } => {},\n}",
+        );
+        assert_eq!(postfix_insert_text(code, "not"), None);
+    }
+
+    #[test]
+    fn postfix_if_let_completion_for_option() {
+        let code = r"
+            enum Option<T> { Some(T), None }
+            fn main(o: Option<u32>) {
+                o.<|>
+            }
+            ";
+        assert_eq!(postfix_insert_text(code, "ifl").unwrap(), "if let Some(${1:x}) = o {\n$0\n}");
+        assert_eq!(
+            postfix_insert_text(code, "while").unwrap(),
+            "while let Some(${1:x}) = o {\n$0\n}",
+        );
+    }
 }
@@ -0,0 +1,167 @@
+use hir::{Docs, ModuleDef, Resolution, Ty, VariantDef};
+use ra_syntax::{
+    AstNode,
+    ast::{self, NameOwner},
+};
+
+use crate::completion::{CompletionContext, Completions, CompletionItem, CompletionItemKind};
+use crate::completion::completion_item::{CompletionKind, compute_score};
+
+/// Completes the fields of a struct or an enum variant which are not written
+/// yet, in both struct literals (`Foo { <|> }`) and patterns
+/// (`let Foo { <|> } = foo`).
+pub(super) fn complete_record(acc: &mut Completions, ctx: &CompletionContext) {
+    let function = match ctx.function {
+        Some(it) => it,
+        None => return,
+    };
+    let infer_result = function.infer(ctx.db);
+    let syntax_mapping = function.body_syntax_mapping(ctx.db);
+
+    let (path, present, ty, is_pattern) = if let Some(lit) = ctx.struct_lit_syntax {
+        let present = lit
+            .named_field_list()
+            .into_iter()
+            .flat_map(|it| it.fields())
+            .filter(|it| !it.syntax().range().contains_inclusive(ctx.offset))
+            .filter_map(|it| it.name_ref())
+            .map(|it| it.text().to_string())
+            .collect::<Vec<_>>();
+        let ty = syntax_mapping
+            .node_expr(ast::Expr::cast(lit.syntax()).unwrap())
+            .map(|expr| infer_result[expr].clone());
+        (lit.path(), present, ty, false)
+    } else if let Some(pat) = ctx.struct_pat_syntax {
+        let field_pats = pat.field_pat_list();
+        let field_pat_names =
+            field_pats.into_iter().flat_map(|it| it.field_pats()).map(|it| it.syntax());
+        let bind_pat_names =
+            field_pats.into_iter().flat_map(|it| it.bind_pats()).map(|it| it.syntax());
+        let present = field_pat_names
+            .chain(bind_pat_names)
+            .filter(|it| !it.range().contains_inclusive(ctx.offset))
+            .filter_map(|it| {
+                let name = ast::FieldPat::cast(it)
+                    .and_then(|it| it.name())
+                    .or_else(|| ast::BindPat::cast(it).and_then(|it| it.name()))?;
+                Some(name.text().to_string())
+            })
+            .collect::<Vec<_>>();
+        let ty = syntax_mapping
+            .node_pat(ast::Pat::cast(pat.syntax()).unwrap())
+            .map(|pat| infer_result[pat].clone());
+        (pat.path(), present, ty, true)
+    } else {
+        return;
+    };
+
+    let variant = match path.and_then(|path| resolve_variant(ctx, path)) {
+        Some(it) => it,
+        None => return,
+    };
+    let substs = match ty {
+        Some(Ty::Adt { substs, .. }) => Some(substs),
+        _ => None,
+    };
+
+    let mut missing = Vec::new();
+    for field in variant.fields(ctx.db) {
+        let name = field.name(ctx.db).to_string();
+        if present.contains(&name) {
+            continue;
+        }
+        let ty = match &substs {
+            Some(substs) => field.ty(ctx.db).subst(substs),
+            None => field.ty(ctx.db),
+        };
+        CompletionItem::new(CompletionKind::Reference, ctx.source_range(), name.clone())
+            .kind(CompletionItemKind::Field)
+            .detail(ty.to_string())
+            .documentation_with(ctx, || field.docs(ctx.db))
+            .set_score(compute_score(ctx, &ty, &name))
+            .add_to(acc);
+        missing.push(name);
+    }
+
+    if missing.len() < 2 {
+        return;
+    }
+    let builder =
+        CompletionItem::new(CompletionKind::Snippet, ctx.source_range(), "fill remaining fields")
+            .kind(CompletionItemKind::Snippet)
+            .lookup_by(missing[0].clone());
+    let builder = if is_pattern {
+        builder.insert_text(missing.join(", "))
+    } else {
+        let fields = missing
+            .iter()
+            .enumerate()
+            .map(|(i, name)| format!("{}: ${}", name, i + 1))
+            .collect::<Vec<_>>();
+        builder.snippet(fields.join(", "))
+    };
+    builder.add_to(acc);
+}
+
+fn resolve_variant(ctx: &CompletionContext, path: &ast::Path) -> Option<VariantDef> {
+    let path = hir::Path::from_ast(path)?;
+    let resolution = ctx.resolver.resolve_path(ctx.db, &path);
+    match resolution.clone().take_types().or_else(|| resolution.take_values())? {
+        Resolution::Def(ModuleDef::Struct(it)) => Some(VariantDef::Struct(it)),
+        Resolution::Def(ModuleDef::EnumVariant(it)) => Some(VariantDef::EnumVariant(it)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::completion::CompletionKind;
+    use crate::completion::completion_item::check_completion;
+
+    fn check_ref_completion(name: &str, code: &str) {
+        check_completion(name, code, CompletionKind::Reference);
+    }
+
+    fn check_snippet_completion(name: &str, code: &str) {
+        check_completion(name, code, CompletionKind::Snippet);
+    }
+
+    #[test]
+    fn completes_missing_fields_in_struct_literal() {
+        check_ref_completion(
+            "completes_missing_fields_in_struct_literal",
+            r"
+            struct S { foo: u32, bar: u8, baz: bool }
+            fn main() {
+                let s = S { foo: 92, <|> };
+            }
+            ",
+        );
+    }
+
+    #[test]
+    fn completes_missing_fields_in_struct_pattern() {
+        check_ref_completion(
+            "completes_missing_fields_in_struct_pattern",
+            r"
+            enum E { V { foo: u32, bar: i64 } }
+            fn main(e: E) {
+                if let E::V { foo, <|> } = e {}
+            }
+            ",
+        );
+    }
+
+    #[test]
+    fn completes_fill_remaining_fields_snippet() {
+        check_snippet_completion(
+            "completes_fill_remaining_fields_snippet",
+            r"
+            struct S { foo: u32, bar: u32, baz: u32 }
+            fn main() {
+                let s = S { bar: 1, <|> };
+            }
+            ",
+        );
+    }
+}
@@ -0,0 +1,171 @@
+use hir::{ModuleDef, Resolution, TraitItem};
+use ra_syntax::{
+    AstNode, SyntaxNode, SmolStr,
+    ast::{self, NameOwner},
+    SyntaxKind::*,
+};
+use rustc_hash::FxHashMap;
+
+use crate::completion::{CompletionContext, Completions, CompletionItem, CompletionItemKind};
+use crate::completion::completion_item::CompletionKind;
+
+/// Completes the items of the implemented trait which are missing from
+/// `impl Trait for Type { <|> }`, with the trait's type parameters and `Self`
+/// replaced by the actual types of the impl.
+pub(super) fn complete_trait_impl(acc: &mut Completions, ctx: &CompletionContext) {
+    let impl_block = match ctx.impl_block_syntax {
+        Some(it) => it,
+        None => return,
+    };
+    let trait_path = match impl_block.target_trait().map(|it| it.kind()) {
+        Some(ast::TypeRefKind::PathType(it)) => it.path(),
+        _ => None,
+    };
+    let trait_path = match trait_path {
+        Some(it) => it,
+        None => return,
+    };
+    let trait_ = match hir::Path::from_ast(trait_path)
+        .and_then(|path| ctx.resolver.resolve_path(ctx.db, &path).take_types())
+    {
+        Some(Resolution::Def(ModuleDef::Trait(it))) => it,
+        _ => return,
+    };
+
+    let mut substs = FxHashMap::default();
+    if let Some(self_ty) = impl_block.target_type() {
+        substs.insert(SmolStr::new("Self"), self_ty.syntax().text().to_string());
+    }
+    let type_args = trait_path
+        .segment()
+        .and_then(|it| it.type_arg_list())
+        .into_iter()
+        .flat_map(|it| it.type_args())
+        .filter_map(|it| it.type_ref());
+    for (param, arg) in trait_.generic_params(ctx.db).params().iter().zip(type_args) {
+        substs.insert(SmolStr::new(param.name().to_string()), arg.syntax().text().to_string());
+    }
+
+    let present = impl_block
+        .item_list()
+        .into_iter()
+        .flat_map(|it| it.impl_items())
+        .filter_map(|it| match it.kind() {
+            ast::ImplItemKind::FnDef(it) => item_name(it),
+            ast::ImplItemKind::TypeDef(it) => item_name(it),
+            ast::ImplItemKind::ConstDef(it) => item_name(it),
+        })
+        .collect::<Vec<_>>();
+
+    for item in trait_.items(ctx.db) {
+        let (name, kind, label, snippet) = match item {
+            TraitItem::Function(it) => {
+                let fn_def = it.source(ctx.db).1;
+                let label = render_signature(fn_def.syntax(), &substs);
+                let snippet = format!("{} {{\n    $0\n}}", label);
+                (item_name(&*fn_def), CompletionItemKind::Function, label, snippet)
+            }
+            TraitItem::Const(it) => {
+                let const_def = it.source(ctx.db).1;
+                let label = render_signature(const_def.syntax(), &substs);
+                let snippet = format!("{} = $0;", label);
+                (item_name(&*const_def), CompletionItemKind::Const, label, snippet)
+            }
+            TraitItem::Type(it) => {
+                let type_def = it.source(ctx.db).1;
+                let name = item_name(&*type_def);
+                let label = format!("type {}", name.clone().unwrap_or_default());
+                let snippet = format!("{} = $0;", label);
+                (name, CompletionItemKind::TypeAlias, label, snippet)
+            }
+        };
+        let name = match name {
+            Some(it) => it,
+            None => continue,
+        };
+        if present.contains(&name) {
+            continue;
+        }
+        CompletionItem::new(CompletionKind::Magic, ctx.source_range(), label)
+            .kind(kind)
+            .lookup_by(name.to_string())
+            .snippet(snippet)
+            .add_to(acc);
+    }
+}
+
+fn item_name(item: &impl NameOwner) -> Option<SmolStr> {
+    item.name().map(|it| it.text().clone())
+}
+
+/// Renders the declaration of a trait item up to its body or default value,
+/// replacing the names from `substs`.
+fn render_signature(node: &SyntaxNode, substs: &FxHashMap<SmolStr, String>) -> String {
+    let mut buf = String::new();
+    let children = node
+        .children()
+        .filter(|child| child.kind() != COMMENT && child.kind() != ATTR)
+        .take_while(|child| child.kind() != BLOCK && child.kind() != EQ && child.kind() != SEMI);
+    for child in children {
+        for leaf in child.descendants().filter(|it| it.first_child().is_none()) {
+            let text = match leaf.leaf_text() {
+                Some(it) => it,
+                None => continue,
+            };
+            match substs.get(text) {
+                Some(subst) if is_unqualified_path(leaf) => buf.push_str(subst),
+                _ => buf.push_str(text),
+            }
+        }
+    }
+    buf.trim().to_string()
+}
+
+/// Whether the identifier is a whole path like `T`, as opposed to a part of
+/// `T::Item` or `foo::T`.
+fn is_unqualified_path(ident: &SyntaxNode) -> bool {
+    let path = ident
+        .parent()
+        .filter(|it| it.kind() == NAME_REF)
+        .and_then(|it| it.parent())
+        .and_then(ast::PathSegment::cast)
+        .map(|it| it.parent_path());
+    match path {
+        Some(path) => {
+            path.qualifier().is_none()
+                && path.syntax().parent().map(|it| it.kind() != PATH).unwrap_or(true)
+        }
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::completion::CompletionKind;
+    use crate::completion::completion_item::check_completion;
+
+    fn check_magic_completion(name: &str, code: &str) {
+        check_completion(name, code, CompletionKind::Magic);
+    }
+
+    #[test]
+    fn completes_missing_trait_items_in_impl() {
+        check_magic_completion(
+            "completes_missing_trait_items_in_impl",
+            r"
+            trait Convert<T> {
+                type Error;
+                const LIMIT: T;
+                /// Converts the value.
+                fn convert(&self, other: Self) -> Result<T, Self::Error>;
+                fn check(&self) -> bool { true }
+            }
+            struct S;
+            impl Convert<u32> for S {
+                fn check(&self) -> bool { false }
+                <|>
+            }
+            ",
+        );
+    }
+}
@@ -1,11 +1,11 @@
 use ra_text_edit::AtomTextEdit;
 use ra_syntax::{
     AstNode, SyntaxNode, SourceFile, TextUnit, TextRange,
-    ast,
+    ast::{self, NameOwner},
     algo::{find_leaf_at_offset, find_covering_node, find_node_at_offset},
     SyntaxKind::*,
 };
-use hir::{source_binder, Resolver};
+use hir::{source_binder, Resolver, Ty, AdtDef};
 
 use crate::{db, FilePosition};
 
@@ -31,10 +31,41 @@ pub(crate) struct CompletionContext<'a> {
     pub(super) can_be_stmt: bool,
     /// Something is typed at the "top" level, in module or impl/trait.
     pub(super) is_new_item: bool,
+    /// The name of a module is being typed, i.e. `mod <|>`.
+    pub(super) is_module_decl: bool,
+    /// The impl block, if a new item is typed inside of `impl Trait for Type`.
+    pub(super) impl_block_syntax: Option<&'a ast::ImplBlock>,
     /// The receiver if this is a field or method access, i.e. writing something.<|>
     pub(super) dot_receiver: Option<&'a ast::Expr>,
     /// If this is a call (method or function) in particular, i.e. the () are already there.
     pub(super) is_call: bool,
+    /// The attribute the cursor is in, i.e. `#[<|>]` or `#[derive(<|>)]`.
+    pub(super) attribute_syntax: Option<&'a ast::Attr>,
+    /// The range of the lifetime being typed, i.e. `&'a<|> str`.
+    pub(super) lifetime_range: Option<TextRange>,
+    /// If the lifetime is a label of a `break` or a `continue`.
+    pub(super) is_label_ref: bool,
+    /// The struct literal whose field is being completed, i.e. `Foo { <|> }`.
+    pub(super) struct_lit_syntax: Option<&'a ast::StructLit>,
+    /// The struct pattern whose field is being completed, i.e. `let Foo { <|> } = foo`.
+    pub(super) struct_pat_syntax: Option<&'a ast::StructPat>,
+    /// The type of the expression expected at the cursor, like the type of
+    /// the parameter an argument is passed to.
+    pub(super) expected_type: Option<Ty>,
+    /// The name expected at the cursor, like the name of the parameter or of
+    /// the field the expression is assigned to.
+    pub(super) expected_name: Option<String>,
+    pub(super) details: CompletionDetails,
+}
+
+/// The items whose docs and details are computed. Those are expensive, so
+/// the editor asks for them only for the item being selected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum CompletionDetails {
+    All,
+    None,
+    /// The items with this label.
+    Label(String),
 }
 
 impl<'a> CompletionContext<'a> {
@@ -42,6 +73,7 @@ impl<'a> CompletionContext<'a> {
         db: &'a db::RootDatabase,
         original_file: &'a SourceFile,
         position: FilePosition,
+        details: CompletionDetails,
     ) -> Option<CompletionContext<'a>> {
         let resolver = source_binder::resolver_for_position(db, position);
         let module = source_binder::module_from_position(db, position);
@@ -61,8 +93,18 @@ impl<'a> CompletionContext<'a> {
             after_if: false,
             can_be_stmt: false,
             is_new_item: false,
+            is_module_decl: false,
+            impl_block_syntax: None,
             dot_receiver: None,
             is_call: false,
+            attribute_syntax: None,
+            lifetime_range: None,
+            is_label_ref: false,
+            struct_lit_syntax: None,
+            struct_pat_syntax: None,
+            expected_type: None,
+            expected_name: None,
+            details,
         };
         ctx.fill(original_file, position.offset);
         Some(ctx)
@@ -70,6 +112,9 @@ impl<'a> CompletionContext<'a> {
 
     // The range of the identifier that is being completed.
     pub(crate) fn source_range(&self) -> TextRange {
+        if let Some(range) = self.lifetime_range {
+            return range;
+        }
         match self.leaf.kind() {
             // workaroud when completion is triggered by trigger characters.
             IDENT => self.leaf.range(),
@@ -77,7 +122,29 @@ impl<'a> CompletionContext<'a> {
         }
     }
 
+    /// Whether the docs and the details of the item labeled `label` have to
+    /// be computed.
+    pub(super) fn wants_details(&self, label: &str) -> bool {
+        match &self.details {
+            CompletionDetails::All => true,
+            CompletionDetails::None => false,
+            CompletionDetails::Label(it) => it == label,
+        }
+    }
+
     fn fill(&mut self, original_file: &'a SourceFile, offset: TextUnit) {
+        // Attributes consist of plain tokens, so there are no names to find.
+        self.attribute_syntax = self.leaf.ancestors().find_map(ast::Attr::cast).filter(|attr| {
+            let range = match attr.value() {
+                Some(tt) => tt.syntax().range(),
+                None => return false,
+            };
+            range.start() < offset && offset < range.end()
+        });
+        if self.attribute_syntax.is_some() {
+            return;
+        }
+
         // Insert a fake ident to get a valid parse tree. We will use this file
         // to determine context, though the original_file will be used for
         // actual completion.
@@ -86,6 +153,14 @@ impl<'a> CompletionContext<'a> {
             original_file.reparse(&edit)
         };
 
+        // Lifetimes and labels are tokens, not names.
+        if let Some(lifetime) = find_leaf_at_offset(file.syntax(), offset)
+            .find(|leaf| leaf.kind() == LIFETIME && leaf.range().start() < offset)
+        {
+            self.classify_lifetime(lifetime, offset);
+            return;
+        }
+
         // First, let's try to complete a reference to some declaration.
         if let Some(name_ref) = find_node_at_offset::<ast::NameRef>(file.syntax(), offset) {
             // Special case, `trait T { fn foo(i_am_a_name_ref) {} }`.
@@ -95,6 +170,7 @@ impl<'a> CompletionContext<'a> {
                 return;
             }
             self.classify_name_ref(original_file, name_ref);
+            self.fill_expected_type(original_file, name_ref);
         }
 
         // Otherwise, see if this is a declaration. We can use heuristics to
@@ -104,8 +180,41 @@ impl<'a> CompletionContext<'a> {
                 self.is_param = true;
                 return;
             }
+            if let Some(module) = name.syntax().parent().and_then(ast::Module::cast) {
+                self.is_module_decl = module.item_list().is_none();
+                return;
+            }
+            // `let Foo { <|> } = foo`, the fields are parsed as bindings.
+            let struct_pat = name
+                .syntax()
+                .parent()
+                .and_then(ast::BindPat::cast)
+                .and_then(|it| it.syntax().parent())
+                .and_then(ast::FieldPatList::cast)
+                .and_then(|it| it.syntax().parent())
+                .and_then(ast::StructPat::cast);
+            if let Some(path) = struct_pat.and_then(|it| it.path()) {
+                self.fill_function();
+                self.struct_pat_syntax =
+                    find_node_with_range(original_file.syntax(), path.syntax().range());
+            }
+        }
+    }
+    fn classify_lifetime(&mut self, lifetime: &SyntaxNode, offset: TextUnit) {
+        let parent = match lifetime.parent() {
+            Some(it) => it,
+            None => return,
+        };
+        match parent.kind() {
+            // `'a: loop {}` and `fn foo<'a>()` declare new names.
+            LABEL => return,
+            LIFETIME_PARAM if parent.first_child() == Some(lifetime) => return,
+            BREAK_EXPR | CONTINUE_EXPR => self.is_label_ref = true,
+            _ => (),
         }
+        self.lifetime_range = Some(TextRange::from_to(lifetime.range().start(), offset));
     }
+
     fn classify_name_ref(&mut self, original_file: &'a SourceFile, name_ref: &ast::NameRef) {
         let name_range = name_ref.syntax().range();
         let top_node =
@@ -114,6 +223,13 @@ impl<'a> CompletionContext<'a> {
         match top_node.parent().map(|it| it.kind()) {
             Some(SOURCE_FILE) | Some(ITEM_LIST) => {
                 self.is_new_item = true;
+                self.impl_block_syntax = self
+                    .leaf
+                    .ancestors()
+                    .find(|it| it.kind() == ITEM_LIST)
+                    .and_then(|it| it.parent())
+                    .and_then(ast::ImplBlock::cast)
+                    .filter(|it| it.target_trait().is_some());
                 return;
             }
             _ => (),
@@ -121,20 +237,26 @@ impl<'a> CompletionContext<'a> {
 
         self.use_item_syntax = self.leaf.ancestors().find_map(ast::UseItem::cast);
 
-        self.function_syntax = self
-            .leaf
-            .ancestors()
-            .take_while(|it| it.kind() != SOURCE_FILE && it.kind() != MODULE)
-            .find_map(ast::FnDef::cast);
-        if let (Some(module), Some(fn_def)) = (self.module, self.function_syntax) {
-            let function = source_binder::function_from_module(self.db, module, fn_def);
-            self.function = Some(function);
-        }
+        self.fill_function();
 
         let parent = match name_ref.syntax().parent() {
             Some(it) => it,
             None => return,
         };
+        if let Some(field) = ast::NamedField::cast(parent) {
+            // `Foo { <|> }`, the name of the field is being typed.
+            if field.expr().is_none() {
+                let path = field
+                    .syntax()
+                    .ancestors()
+                    .find_map(ast::StructLit::cast)
+                    .and_then(|it| it.path());
+                if let Some(path) = path {
+                    self.struct_lit_syntax =
+                        find_node_with_range(original_file.syntax(), path.syntax().range());
+                }
+            }
+        }
         if let Some(segment) = ast::PathSegment::cast(parent) {
             let path = segment.parent_path();
             if let Some(mut path) = hir::Path::from_ast(path) {
@@ -200,6 +322,127 @@ impl<'a> CompletionContext<'a> {
             self.is_call = true;
         }
     }
+
+    fn fill_function(&mut self) {
+        self.function_syntax = self
+            .leaf
+            .ancestors()
+            .take_while(|it| it.kind() != SOURCE_FILE && it.kind() != MODULE)
+            .find_map(ast::FnDef::cast);
+        if let (Some(module), Some(fn_def)) = (self.module, self.function_syntax) {
+            let function = source_binder::function_from_module(self.db, module, fn_def);
+            self.function = Some(function);
+        }
+    }
+
+    fn fill_expected_type(&mut self, original_file: &'a SourceFile, name_ref: &ast::NameRef) {
+        let expr = match name_ref.syntax().ancestors().find_map(ast::Expr::cast) {
+            Some(it) => it,
+            None => return,
+        };
+        if let Some((ty, name)) = self.expected_type_and_name(original_file, expr) {
+            // Nothing stands out when a unit is expected.
+            if ty != Ty::Unknown && ty != Ty::unit() {
+                self.expected_type = Some(ty);
+                self.expected_name = name;
+            }
+        }
+    }
+
+    /// Looks at the parent of `expr` to find out what type it should have.
+    /// Nodes which precede the cursor have the same ranges in the original
+    /// file, so we use them to get back to the inferred types.
+    fn expected_type_and_name(
+        &self,
+        original_file: &'a SourceFile,
+        expr: &ast::Expr,
+    ) -> Option<(Ty, Option<String>)> {
+        let function = self.function?;
+        let infer = function.infer(self.db);
+        let syntax_mapping = function.body_syntax_mapping(self.db);
+        let parent = expr.syntax().parent()?;
+
+        if let Some(let_stmt) = ast::LetStmt::cast(parent) {
+            // `let x: T = <|>`
+            let pat = let_stmt.pat()?.syntax().range();
+            let pat = find_node_with_range::<ast::Pat>(original_file.syntax(), pat)?;
+            return Some((infer[syntax_mapping.node_pat(pat)?].clone(), None));
+        }
+        if let Some(arg_list) = ast::ArgList::cast(parent) {
+            // `foo(<|>)` or `bar.foo(<|>)`
+            let idx = arg_list.args().position(|arg| arg.syntax() == expr.syntax())?;
+            let call = arg_list.syntax().parent()?;
+            let (callee, param_tys) = if let Some(call) = ast::CallExpr::cast(call) {
+                let callee = call.expr()?.syntax().range();
+                let callee = find_node_with_range::<ast::Expr>(original_file.syntax(), callee)?;
+                match &infer[syntax_mapping.node_expr(callee)?] {
+                    Ty::FnDef { def, sig, substs, .. } => {
+                        let tys = sig.params().iter().map(|ty| ty.clone().subst(substs));
+                        (*def, tys.collect::<Vec<_>>())
+                    }
+                    _ => return None,
+                }
+            } else {
+                let call = ast::MethodCallExpr::cast(call)?;
+                let receiver = call.expr()?.syntax().range();
+                let receiver = find_node_with_range::<ast::Expr>(original_file.syntax(), receiver)?;
+                let call = ast::Expr::cast(receiver.syntax().parent()?)?;
+                let call = syntax_mapping.node_expr(call)?;
+                let method = infer.method_resolution(call)?;
+                let substs = infer.method_substs(call)?;
+                match method.ty(self.db) {
+                    // The first parameter is the receiver.
+                    Ty::FnDef { sig, .. } => {
+                        let tys = sig.params().iter().skip(1).map(|ty| ty.clone().subst(&substs));
+                        (method, tys.collect::<Vec<_>>())
+                    }
+                    _ => return None,
+                }
+            };
+            let name = callee
+                .source(self.db)
+                .1
+                .param_list()
+                .and_then(|it| it.params().nth(idx))
+                .and_then(|it| it.pat())
+                .and_then(|it| ast::BindPat::cast(it.syntax()))
+                .and_then(|it| it.name())
+                .map(|it| it.text().to_string());
+            return Some((param_tys.get(idx)?.clone(), name));
+        }
+        if let Some(field) = ast::NamedField::cast(parent) {
+            // `Foo { bar: <|> }`
+            let field_name = field.name_ref()?.text().to_string();
+            let struct_lit = field.syntax().ancestors().find_map(ast::StructLit::cast)?;
+            let path = struct_lit.path()?.syntax().range();
+            let struct_lit = find_node_with_range::<ast::StructLit>(original_file.syntax(), path)?;
+            let struct_lit = ast::Expr::cast(struct_lit.syntax())?;
+            let (s, substs) = match &infer[syntax_mapping.node_expr(struct_lit)?] {
+                Ty::Adt { def_id: AdtDef::Struct(s), substs, .. } => (*s, substs.clone()),
+                _ => return None,
+            };
+            let field = s
+                .fields(self.db)
+                .into_iter()
+                .find(|it| it.name(self.db).to_string() == field_name)?;
+            return Some((field.ty(self.db).subst(&substs), Some(field_name)));
+        }
+
+        let is_returned = ast::ReturnExpr::cast(parent).is_some()
+            || ast::Block::cast(parent)
+                .filter(|block| block.expr().map(|it| it.syntax()) == Some(expr.syntax()))
+                .and_then(|block| block.syntax().parent())
+                .and_then(ast::FnDef::cast)
+                .is_some();
+        if is_returned {
+            // `return <|>` or `fn foo() -> T { <|> }`
+            return match function.ty(self.db) {
+                Ty::FnDef { sig, .. } => Some((sig.ret().clone(), None)),
+                _ => None,
+            };
+        }
+        None
+    }
 }
 
 fn find_node_with_range<N: AstNode>(syntax: &SyntaxNode, range: TextRange) -> Option<&N> {
@@ -1,4 +1,4 @@
-use hir::{Docs, Documentation, PerNs, Resolution};
+use hir::{Docs, Documentation, PerNs, Resolution, Ty};
 use ra_syntax::{
     TextRange,
 };
@@ -31,6 +31,8 @@ pub struct CompletionItem {
     /// `insert_text` should start with what `source_range` points to, or VSCode
     /// will filter out the completion silently.
     source_range: TextRange,
+    /// How well the item fits the type expected at the cursor.
+    score: Option<CompletionScore>,
     /// Additional text edit, ranges in `text_edit` must never intersect with `source_range`.
     /// Or VSCode will drop it silently.
     text_edit: Option<TextEdit>,
@@ -53,6 +55,21 @@ pub enum CompletionItemKind {
     TypeAlias,
     Method,
     TypeParam,
+    Attribute,
+    Macro,
+    Lifetime,
+    Label,
+}
+
+/// Marks items which fit the context of the completion well. Such items should
+/// be ranked above all the others.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CompletionScore {
+    /// The type of the item is the type expected at the cursor.
+    TypeMatch,
+    /// Besides the type, the name of the item matches the expected name, like
+    /// the name of the parameter the item is passed to.
+    TypeAndNameMatch,
 }
 
 #[derive(Debug, PartialEq, Eq, Copy, Clone)]
@@ -65,6 +82,8 @@ pub(crate) enum CompletionKind {
     Magic,
     Snippet,
     Postfix,
+    /// Attribute names, derivable traits and lints inside of `#[...]`.
+    Attribute,
 }
 
 #[derive(Debug, PartialEq, Eq, Copy, Clone)]
@@ -90,6 +109,7 @@ impl CompletionItem {
             documentation: None,
             lookup: None,
             kind: None,
+            score: None,
             text_edit: None,
         }
     }
@@ -128,6 +148,9 @@ impl CompletionItem {
     pub fn source_range(&self) -> TextRange {
         self.source_range
     }
+    pub fn score(&self) -> Option<CompletionScore> {
+        self.score
+    }
 }
 
 /// A helper to make `CompletionItem`s.
@@ -142,6 +165,7 @@ pub(crate) struct Builder {
     documentation: Option<Documentation>,
     lookup: Option<String>,
     kind: Option<CompletionItemKind>,
+    score: Option<CompletionScore>,
     text_edit: Option<TextEdit>,
 }
 
@@ -160,6 +184,7 @@ impl Builder {
             lookup: self.lookup,
             kind: self.kind,
             completion_kind: self.completion_kind,
+            score: self.score,
             text_edit: self.text_edit,
             insert_text: self.insert_text,
         }
@@ -185,12 +210,10 @@ impl Builder {
         self.kind = Some(kind);
         self
     }
-    #[allow(unused)]
     pub(crate) fn text_edit(mut self, edit: TextEdit) -> Builder {
         self.text_edit = Some(edit);
         self
     }
-    #[allow(unused)]
     pub(crate) fn detail(self, detail: impl Into<String>) -> Builder {
         self.set_detail(Some(detail))
     }
@@ -206,6 +229,22 @@ impl Builder {
         self.documentation = docs.map(Into::into);
         self
     }
+    /// Sets the docs computed by `docs`, if the context asks for them.
+    pub(crate) fn documentation_with(
+        self,
+        ctx: &CompletionContext,
+        docs: impl FnOnce() -> Option<Documentation>,
+    ) -> Builder {
+        if ctx.wants_details(&self.label) {
+            self.set_documentation(docs())
+        } else {
+            self
+        }
+    }
+    pub(crate) fn set_score(mut self, score: Option<CompletionScore>) -> Builder {
+        self.score = score;
+        self
+    }
     pub(super) fn from_resolution(
         mut self,
         ctx: &CompletionContext,
@@ -218,25 +257,43 @@ impl Builder {
             None => return self,
             Some(it) => it,
         };
-        let (kind, docs) = match def {
-            Resolution::Def(Module(it)) => (CompletionItemKind::Module, it.docs(ctx.db)),
+        let kind = match def {
+            Resolution::Def(Module(_)) => CompletionItemKind::Module,
             Resolution::Def(Function(func)) => return self.from_function(ctx, *func),
-            Resolution::Def(Struct(it)) => (CompletionItemKind::Struct, it.docs(ctx.db)),
-            Resolution::Def(Enum(it)) => (CompletionItemKind::Enum, it.docs(ctx.db)),
-            Resolution::Def(EnumVariant(it)) => (CompletionItemKind::EnumVariant, it.docs(ctx.db)),
-            Resolution::Def(Const(it)) => (CompletionItemKind::Const, it.docs(ctx.db)),
-            Resolution::Def(Static(it)) => (CompletionItemKind::Static, it.docs(ctx.db)),
-            Resolution::Def(Trait(it)) => (CompletionItemKind::Trait, it.docs(ctx.db)),
-            Resolution::Def(Type(it)) => (CompletionItemKind::TypeAlias, it.docs(ctx.db)),
-            Resolution::GenericParam(..) => (CompletionItemKind::TypeParam, None),
-            Resolution::LocalBinding(..) => (CompletionItemKind::Binding, None),
-            Resolution::SelfType(..) => (
-                CompletionItemKind::TypeParam, // (does this need its own kind?)
-                None,
-            ),
+            Resolution::Def(Struct(_)) => CompletionItemKind::Struct,
+            Resolution::Def(Enum(_)) => CompletionItemKind::Enum,
+            Resolution::Def(EnumVariant(_)) => CompletionItemKind::EnumVariant,
+            Resolution::Def(Const(_)) => CompletionItemKind::Const,
+            Resolution::Def(Static(_)) => CompletionItemKind::Static,
+            Resolution::Def(Trait(_)) => CompletionItemKind::Trait,
+            Resolution::Def(Type(_)) => CompletionItemKind::TypeAlias,
+            Resolution::GenericParam(..) => CompletionItemKind::TypeParam,
+            Resolution::LocalBinding(..) => CompletionItemKind::Binding,
+            // (does this need its own kind?)
+            Resolution::SelfType(..) => CompletionItemKind::TypeParam,
         };
         self.kind = Some(kind);
-        self.documentation = docs;
+        if ctx.wants_details(&self.label) {
+            self.documentation = match def {
+                Resolution::Def(Module(it)) => it.docs(ctx.db),
+                Resolution::Def(Struct(it)) => it.docs(ctx.db),
+                Resolution::Def(Enum(it)) => it.docs(ctx.db),
+                Resolution::Def(EnumVariant(it)) => it.docs(ctx.db),
+                Resolution::Def(Const(it)) => it.docs(ctx.db),
+                Resolution::Def(Static(it)) => it.docs(ctx.db),
+                Resolution::Def(Trait(it)) => it.docs(ctx.db),
+                Resolution::Def(Type(it)) => it.docs(ctx.db),
+                _ => None,
+            };
+        }
+
+        let ty = match def {
+            Resolution::Def(Struct(it)) => Some(it.ty(ctx.db)),
+            Resolution::Def(EnumVariant(it)) => Some(it.parent_enum(ctx.db).ty(ctx.db)),
+            Resolution::LocalBinding(pat) => ctx.function.map(|it| it.infer(ctx.db)[*pat].clone()),
+            _ => None,
+        };
+        self.score = ty.and_then(|ty| compute_score(ctx, &ty, &self.label));
 
         self
     }
@@ -258,12 +315,13 @@ impl Builder {
             self.insert_text_format = InsertTextFormat::Snippet;
         }
 
-        if let Some(docs) = function.docs(ctx.db) {
-            self.documentation = Some(docs);
+        if ctx.wants_details(&self.label) {
+            self.documentation = function.docs(ctx.db);
+            self.detail = function_item_label(ctx, function);
         }
 
-        if let Some(label) = function_item_label(ctx, function) {
-            self.detail = Some(label);
+        if let Ty::FnDef { sig, .. } = function.ty(ctx.db) {
+            self.score = compute_score(ctx, sig.ret(), &self.label);
         }
 
         self.kind = Some(CompletionItemKind::Function);
@@ -271,22 +329,20 @@ impl Builder {
     }
 
     pub(super) fn from_const(mut self, ctx: &CompletionContext, ct: hir::Const) -> Builder {
-        if let Some(docs) = ct.docs(ctx.db) {
-            self.documentation = Some(docs);
+        if ctx.wants_details(&self.label) {
+            self.documentation = ct.docs(ctx.db);
+            self.detail = Some(const_item_label(ctx, ct));
         }
-
-        self.detail = Some(const_item_label(ctx, ct));
         self.kind = Some(CompletionItemKind::Const);
 
         self
     }
 
     pub(super) fn from_type(mut self, ctx: &CompletionContext, ty: hir::Type) -> Builder {
-        if let Some(docs) = ty.docs(ctx.db) {
-            self.documentation = Some(docs);
+        if ctx.wants_details(&self.label) {
+            self.documentation = ty.docs(ctx.db);
+            self.detail = Some(type_item_label(ctx, ty));
         }
-
-        self.detail = Some(type_item_label(ctx, ty));
         self.kind = Some(CompletionItemKind::TypeAlias);
 
         self
@@ -324,6 +380,39 @@ impl Into<Vec<CompletionItem>> for Completions {
     }
 }
 
+/// Ranks an item of type `ty` named `name` against the expectations at the
+/// cursor.
+pub(super) fn compute_score(
+    ctx: &CompletionContext,
+    ty: &Ty,
+    name: &str,
+) -> Option<CompletionScore> {
+    let expected_type = ctx.expected_type.as_ref()?;
+    if !types_match(ty, expected_type) {
+        return None;
+    }
+    if ctx.expected_name.as_ref().map(String::as_str) == Some(name) {
+        Some(CompletionScore::TypeAndNameMatch)
+    } else {
+        Some(CompletionScore::TypeMatch)
+    }
+}
+
+/// The generic arguments of a struct or an enum are not known until it is
+/// used, so `Option<{unknown}>` matches `Option<u32>`.
+fn types_match(ty: &Ty, expected: &Ty) -> bool {
+    match (ty, expected) {
+        (Ty::Adt { def_id, .. }, Ty::Adt { def_id: expected_def_id, .. })
+            if def_id == expected_def_id =>
+        {
+            let mut has_unknown = false;
+            ty.walk(&mut |it| has_unknown |= *it == Ty::Unknown);
+            has_unknown || ty == expected
+        }
+        _ => ty == expected,
+    }
+}
+
 fn function_item_label(ctx: &CompletionContext, function: hir::Function) -> Option<String> {
     let node = function.source(ctx.db).1;
     function_label(&node)
@@ -342,13 +431,13 @@ fn type_item_label(ctx: &CompletionContext, ty: hir::Type) -> String {
 #[cfg(test)]
 pub(crate) fn do_completion(code: &str, kind: CompletionKind) -> Vec<CompletionItem> {
     use crate::mock_analysis::{single_file_with_position, analysis_and_position};
-    use crate::completion::completions;
+    use crate::completion::{completions, CompletionDetails};
     let (analysis, position) = if code.contains("//-") {
         analysis_and_position(code)
     } else {
         single_file_with_position(code)
     };
-    let completions = completions(&analysis.db, position).unwrap();
+    let completions = completions(&analysis.db, position, CompletionDetails::All).unwrap();
     let completion_items: Vec<CompletionItem> = completions.into();
     let mut kind_completions: Vec<CompletionItem> =
         completion_items.into_iter().filter(|c| c.completion_kind == kind).collect();
@@ -373,6 +462,27 @@ mod tests {
         check_completion(code, expected_completions, CompletionKind::Reference);
     }
 
+    #[test]
+    fn computes_details_only_for_the_resolved_item() {
+        let (analysis, position) = crate::mock_analysis::single_file_with_position(
+            r"
+            /// Does foo.
+            fn foo() {}
+            /// Does bar.
+            fn bar() {}
+            fn main() { <|> }
+            ",
+        );
+        let items = analysis.completions_without_details(position).unwrap().unwrap();
+        assert!(items.iter().all(|it| it.documentation().is_none() && it.detail().is_none()));
+        let index = items.iter().position(|it| it.label() == "foo").unwrap();
+
+        let item = analysis.resolve_completion(position, index, "foo").unwrap().unwrap();
+        assert_eq!(item.documentation().unwrap().contents(), "Does foo.");
+        assert_eq!(item.detail(), Some("fn foo()"));
+        assert!(analysis.resolve_completion(position, index, "bar").unwrap().is_none());
+    }
+
     #[test]
     fn inserts_parens_for_function_calls() {
         covers!(inserts_parens_for_function_calls);
@@ -430,4 +540,88 @@ mod tests {
         )
     }
 
+    fn scores(code: &str) -> Vec<(String, Option<CompletionScore>)> {
+        do_completion(code, CompletionKind::Reference)
+            .into_iter()
+            .map(|it| (it.label().to_string(), it.score()))
+            .collect()
+    }
+
+    #[test]
+    fn scores_arguments_by_parameter_type_and_name() {
+        let scores = scores(
+            r"
+            struct Foo;
+            fn take(foo: Foo, n: u32) {}
+            fn main() {
+                let foo = Foo;
+                let bar = Foo;
+                let n = 1u32;
+                take(<|>)
+            }
+            ",
+        );
+        assert_eq!(
+            scores,
+            vec![
+                ("Foo".to_string(), Some(CompletionScore::TypeMatch)),
+                ("bar".to_string(), Some(CompletionScore::TypeMatch)),
+                ("foo".to_string(), Some(CompletionScore::TypeAndNameMatch)),
+                ("main".to_string(), None),
+                ("n".to_string(), None),
+                ("take".to_string(), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn scores_method_arguments_by_substituted_type_and_binding_name() {
+        let scores = scores(
+            r"
+            struct S;
+            impl S {
+                fn put<T>(&self, key: T, mut count: T) {}
+            }
+            fn main() {
+                let s = S;
+                let count = 1u32;
+                let flag = true;
+                s.put(2u32, <|>)
+            }
+            ",
+        );
+        assert_eq!(
+            scores,
+            vec![
+                ("S".to_string(), None),
+                ("count".to_string(), Some(CompletionScore::TypeAndNameMatch)),
+                ("flag".to_string(), None),
+                ("main".to_string(), None),
+                ("s".to_string(), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn scores_struct_literal_fields_by_field_type() {
+        let scores = scores(
+            r"
+            struct S { count: u32 }
+            fn main() {
+                let count = 1u32;
+                let flag = true;
+                S { count: <|> }
+            }
+            ",
+        );
+        assert_eq!(
+            scores,
+            vec![
+                ("S".to_string(), None),
+                ("count".to_string(), Some(CompletionScore::TypeAndNameMatch)),
+                ("flag".to_string(), None),
+                ("main".to_string(), None),
+            ]
+        );
+    }
 }
@@ -1,8 +1,13 @@
-use hir::{Problem, source_binder};
+use hir::{Name, Problem, source_binder};
 use ra_ide_api_light::Severity;
 use ra_db::SourceDatabase;
+use ra_syntax::{
+    AstNode, SourceFile, TextRange,
+    ast::{self, ModuleItemOwner},
+};
+use ra_text_edit::{TextEdit, TextEditBuilder};
 
-use crate::{Diagnostic, FileId, FileSystemEdit, SourceChange, db::RootDatabase};
+use crate::{Diagnostic, FileId, FileSystemEdit, SourceChange, SourceFileEdit, db::RootDatabase};
 
 pub(crate) fn diagnostics(db: &RootDatabase, file_id: FileId) -> Vec<Diagnostic> {
     let syntax = db.parse(file_id);
@@ -21,8 +26,11 @@ pub(crate) fn diagnostics(db: &RootDatabase, file_id: FileId) -> Vec<Diagnostic>
             let source_root = db.file_source_root(file_id);
             let diag = match problem {
                 Problem::UnresolvedModule { candidate } => {
-                    let create_file =
-                        FileSystemEdit::CreateFile { source_root, path: candidate.clone() };
+                    let create_file = FileSystemEdit::CreateFile {
+                        source_root,
+                        path: candidate.clone(),
+                        text: String::new(),
+                    };
                     let fix = SourceChange {
                         label: "create module".to_string(),
                         source_file_edits: Vec::new(),
@@ -42,8 +50,11 @@ pub(crate) fn diagnostics(db: &RootDatabase, file_id: FileId) -> Vec<Diagnostic>
                         dst_source_root: source_root,
                         dst_path: move_to.clone(),
                     };
-                    let create_file =
-                        FileSystemEdit::CreateFile { source_root, path: move_to.join(candidate) };
+                    let create_file = FileSystemEdit::CreateFile {
+                        source_root,
+                        path: move_to.join(candidate),
+                        text: String::new(),
+                    };
                     let fix = SourceChange {
                         label: "move file and create module".to_string(),
                         source_file_edits: Vec::new(),
@@ -60,6 +71,159 @@ pub(crate) fn diagnostics(db: &RootDatabase, file_id: FileId) -> Vec<Diagnostic>
             };
             res.push(diag)
         }
+    } else if let Some(diag) = orphan_file_diagnostic(db, file_id) {
+        res.push(diag);
     };
+    res.extend(non_exhaustive_match_diagnostics(db, file_id, &syntax));
+    res
+}
+
+/// Reports the `match` expressions which don't cover all the values of the
+/// matched type, at their scrutinees.
+fn non_exhaustive_match_diagnostics(
+    db: &RootDatabase,
+    file_id: FileId,
+    file: &SourceFile,
+) -> Vec<Diagnostic> {
+    let mut res = Vec::new();
+    for fn_def in file.syntax().descendants().filter_map(ast::FnDef::cast) {
+        let function = match source_binder::function_from_source(db, file_id, fn_def) {
+            Some(it) => it,
+            None => continue,
+        };
+        let syntax_mapping = function.body_syntax_mapping(db);
+        let resolver = function.resolver(db);
+        for (expr, missing) in function.non_exhaustive_matches(db) {
+            let scrutinee = syntax_mapping
+                .expr_syntax(expr)
+                .and_then(|ptr| ast::MatchExpr::cast(ptr.to_node(file)))
+                .and_then(|it| it.expr());
+            let range = match scrutinee {
+                Some(it) => it.syntax().range(),
+                None => continue,
+            };
+            let pats = missing
+                .iter()
+                .map(|it| format!("`{}`", it.to_source(db, &resolver)))
+                .collect::<Vec<_>>();
+            res.push(Diagnostic {
+                range,
+                message: format!("non-exhaustive patterns: {} not covered", pats.join(", ")),
+                // The check doesn't see through everything, like macro patterns.
+                severity: Severity::WeakWarning,
+                fix: None,
+            });
+        }
+    }
     res
 }
+
+/// Warns about a file which no crate includes, but which could be declared as
+/// a child of some module, and offers to add the `mod` declaration.
+fn orphan_file_diagnostic(db: &RootDatabase, file_id: FileId) -> Option<Diagnostic> {
+    let (parent, name) = source_binder::parent_of_orphan_file(db, file_id)?;
+    let (parent_file_id, parent_source) = parent.definition_source(db);
+    let edit = match parent_source {
+        hir::ModuleSource::SourceFile(file) => add_mod_declaration(&file, &name),
+        hir::ModuleSource::Module(_) => return None,
+    };
+    let fix = SourceChange {
+        label: format!("add `mod {};` to the parent module", name),
+        source_file_edits: vec![SourceFileEdit { file_id: parent_file_id, edit }],
+        file_system_edits: Vec::new(),
+        cursor_position: None,
+    };
+    Some(Diagnostic {
+        // Marking the whole file would obscure the other diagnostics.
+        range: TextRange::offset_len(0.into(), 0.into()),
+        message: "file is not included in any crate".to_string(),
+        severity: Severity::WeakWarning,
+        fix: Some(fix),
+    })
+}
+
+/// Adds `mod name;` after the last module declaration in the file, or before
+/// the first item if there are no declarations.
+fn add_mod_declaration(file: &ast::SourceFile, name: &Name) -> TextEdit {
+    let mut edit = TextEditBuilder::default();
+    let last_mod_decl = file
+        .items()
+        .filter_map(|item| match item.kind() {
+            ast::ModuleItemKind::Module(it) if it.has_semi() => Some(it),
+            _ => None,
+        })
+        .last();
+    match (last_mod_decl, file.items().next()) {
+        (Some(decl), _) => edit.insert(decl.syntax().range().end(), format!("\nmod {};", name)),
+        (None, Some(item)) => {
+            edit.insert(item.syntax().range().start(), format!("mod {};\n\n", name))
+        }
+        (None, None) => edit.insert(file.syntax().range().end(), format!("mod {};\n", name)),
+    }
+    edit.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use test_utils::assert_eq_text;
+
+    use crate::mock_analysis::MockAnalysis;
+
+    #[test]
+    fn suggests_declaring_orphan_files_as_modules() {
+        let mut mock = MockAnalysis::new();
+        let lib = mock.add_file("/lib.rs", "mod foo;\n\nfn main() {}\n");
+        mock.add_file("/foo.rs", "");
+        let orphan = mock.add_file("/bar.rs", "struct Bar;\n");
+        let analysis = mock.analysis();
+
+        assert!(analysis.diagnostics(lib).unwrap().is_empty());
+        let diagnostics = analysis.diagnostics(orphan).unwrap();
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].range.is_empty());
+        let fix = diagnostics[0].fix.as_ref().unwrap();
+        assert_eq!(fix.label, "add `mod bar;` to the parent module");
+        assert_eq!(fix.source_file_edits[0].file_id, lib);
+        let actual = fix.source_file_edits[0].edit.apply(&analysis.file_text(lib));
+        assert_eq_text!("mod foo;\nmod bar;\n\nfn main() {}\n", &actual);
+    }
+
+    #[test]
+    fn does_not_suggest_declaring_orphan_files_in_inline_modules() {
+        let mut mock = MockAnalysis::new();
+        mock.add_file("/lib.rs", "mod tests {}\n");
+        let orphan = mock.add_file("/tests/foo.rs", "struct Foo;\n");
+        let analysis = mock.analysis();
+
+        assert!(analysis.diagnostics(orphan).unwrap().is_empty());
+    }
+
+    #[test]
+    fn reports_non_exhaustive_matches() {
+        let mut mock = MockAnalysis::new();
+        let text =
+            "enum E { A, B(u32) }\n\nfn f(e: E) {\n    match e {\n        E::A => (),\n    }\n}\n";
+        let file_id = mock.add_file("/lib.rs", text);
+        let analysis = mock.analysis();
+
+        let diagnostics = analysis.diagnostics(file_id).unwrap();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].message, "non-exhaustive patterns: `E::B(_)` not covered");
+        assert_eq!(&text[diagnostics[0].range], "e");
+    }
+
+    #[test]
+    fn does_not_report_matches_on_integer_ranges() {
+        let mut mock = MockAnalysis::new();
+        let text = "fn f(x: u8) {
+    match x {
+        0..=127 | 128..=255 => (),
+    }
+}
+";
+        let file_id = mock.add_file("/lib.rs", text);
+        let analysis = mock.analysis();
+
+        assert!(analysis.diagnostics(file_id).unwrap().is_empty());
+    }
+}
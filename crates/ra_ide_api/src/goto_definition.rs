@@ -1,7 +1,8 @@
 use ra_db::{FileId, SourceDatabase};
 use ra_syntax::{
-    AstNode, ast,
-    algo::find_node_at_offset,
+    AstNode, SyntaxNode, ast,
+    algo::{find_node_at_offset, find_leaf_at_offset},
+    SyntaxKind::{LIFETIME, BREAK_EXPR, CONTINUE_EXPR, FN_DEF, LAMBDA_EXPR},
 };
 use test_utils::tested_by;
 use hir::Resolution;
@@ -22,6 +23,12 @@ pub(crate) fn goto_definition(
         let navs = name_definition(db, position.file_id, name)?;
         return Some(RangeInfo::new(name.syntax().range(), navs));
     }
+    if let Some(lifetime) =
+        find_leaf_at_offset(syntax, position.offset).find(|leaf| leaf.kind() == LIFETIME)
+    {
+        let nav = lifetime_definition(position.file_id, lifetime)?;
+        return Some(RangeInfo::new(lifetime.range(), vec![nav]));
+    }
     None
 }
 
@@ -127,6 +134,42 @@ fn name_definition(
     None
 }
 
+/// Finds the declaration of a lifetime among the generic parameters of the
+/// enclosing items, or of a label among the enclosing loops.
+fn lifetime_definition(file_id: FileId, lifetime: &SyntaxNode) -> Option<NavigationTarget> {
+    let name = lifetime.leaf_text()?;
+    let is_label = lifetime
+        .parent()
+        .map(|it| it.kind() == BREAK_EXPR || it.kind() == CONTINUE_EXPR)
+        .unwrap_or(false);
+    if is_label {
+        return lifetime
+            .ancestors()
+            .take_while(|it| it.kind() != FN_DEF && it.kind() != LAMBDA_EXPR)
+            .filter_map(|it| {
+                let label = ast::LoopExpr::cast(it)
+                    .and_then(|it| it.label())
+                    .or_else(|| ast::WhileExpr::cast(it).and_then(|it| it.label()))
+                    .or_else(|| ast::ForExpr::cast(it).and_then(|it| it.label()))?;
+                Some((label.syntax(), label.lifetime()?))
+            })
+            .find(|(_, it)| it.syntax().leaf_text() == Some(name))
+            .map(|(label, it)| NavigationTarget::from_lifetime(file_id, label, it));
+    }
+    if let Some(label) = lifetime.parent().and_then(ast::Label::cast) {
+        let lifetime = label.lifetime()?;
+        return Some(NavigationTarget::from_lifetime(file_id, label.syntax(), lifetime));
+    }
+    lifetime
+        .ancestors()
+        .flat_map(|it| it.children())
+        .filter_map(ast::TypeParamList::cast)
+        .flat_map(|it| it.lifetime_params())
+        .filter_map(|it| Some((it.syntax(), it.lifetime()?)))
+        .find(|(_, it)| it.syntax().leaf_text() == Some(name))
+        .map(|(param, it)| NavigationTarget::from_lifetime(file_id, param, it))
+}
+
 #[cfg(test)]
 mod tests {
     use test_utils::covers;
@@ -231,4 +274,31 @@ mod tests {
             "spam NAMED_FIELD_DEF FileId(1) [17; 26) [17; 21)",
         );
     }
+    #[test]
+    fn goto_definition_works_for_lifetimes() {
+        check_goto(
+            "
+            //- /lib.rs
+            struct Foo<'a> { x: &'a<|> u32 }
+            ",
+            "'a LIFETIME_PARAM FileId(1) [11; 13) [11; 13)",
+        );
+    }
+
+    #[test]
+    fn goto_definition_works_for_labels() {
+        check_goto(
+            "
+            //- /lib.rs
+            fn foo() {
+                'outer: loop {
+                    loop {
+                        break 'outer<|>;
+                    }
+                }
+            }
+            ",
+            "'outer LABEL FileId(1) [15; 22) [15; 21)",
+        );
+    }
 }
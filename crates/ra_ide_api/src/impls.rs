@@ -1,9 +1,12 @@
+use std::iter;
+
 use ra_db::SourceDatabase;
 use ra_syntax::{
-    AstNode, ast,
+    AstNode,
+    ast::{self, NameOwner},
     algo::find_node_at_offset,
 };
-use hir::{db::HirDatabase, source_binder};
+use hir::{db::HirDatabase, source_binder, ImplItem, TraitItem};
 
 use crate::{FilePosition, NavigationTarget, db::RootDatabase, RangeInfo};
 
@@ -21,7 +24,14 @@ pub(crate) fn goto_implementation(
             nominal_def.syntax().range(),
             impls_for_def(db, nominal_def, module)?,
         ));
-    } else if let Some(trait_def) = find_node_at_offset::<ast::TraitDef>(syntax, position.offset) {
+    } else if let Some(fn_def) = find_node_at_offset::<ast::Name>(syntax, position.offset)
+        .and_then(|name| ast::FnDef::cast(name.syntax().parent()?))
+    {
+        if let Some(navs) = impls_for_method(db, position, fn_def, module) {
+            return Some(RangeInfo::new(fn_def.syntax().range(), navs));
+        }
+    }
+    if let Some(trait_def) = find_node_at_offset::<ast::TraitDef>(syntax, position.offset) {
         return Some(RangeInfo::new(
             trait_def.syntax().range(),
             impls_for_trait(db, trait_def, module)?,
@@ -31,6 +41,12 @@ pub(crate) fn goto_implementation(
     None
 }
 
+/// Impls can be in the crate of the definition, or in any crate depending on it.
+fn crates_to_search(db: &RootDatabase, module: hir::Module) -> Option<Vec<hir::Crate>> {
+    let krate = module.krate(db)?;
+    Some(iter::once(krate).chain(krate.reverse_dependencies(db)).collect())
+}
+
 fn impls_for_def(
     db: &RootDatabase,
     node: &ast::NominalDef,
@@ -45,15 +61,16 @@ fn impls_for_def(
         }
     };
 
-    let krate = module.krate(db)?;
-    let impls = db.impls_in_crate(krate);
-
-    Some(
-        impls
-            .lookup_impl_blocks(db, &ty)
-            .map(|(module, imp)| NavigationTarget::from_impl_block(db, module, &imp))
-            .collect(),
-    )
+    let mut res = Vec::new();
+    for krate in crates_to_search(db, module)? {
+        let impls = db.impls_in_crate(krate);
+        res.extend(
+            impls
+                .lookup_impl_blocks(db, &ty)
+                .map(|(module, imp)| NavigationTarget::from_impl_block(db, module, &imp)),
+        );
+    }
+    Some(res)
 }
 
 fn impls_for_trait(
@@ -63,15 +80,63 @@ fn impls_for_trait(
 ) -> Option<Vec<NavigationTarget>> {
     let tr = source_binder::trait_from_module(db, module, node);
 
-    let krate = module.krate(db)?;
-    let impls = db.impls_in_crate(krate);
-
-    Some(
-        impls
-            .lookup_impl_blocks_for_trait(db, &tr)
-            .map(|(module, imp)| NavigationTarget::from_impl_block(db, module, &imp))
-            .collect(),
-    )
+    let mut res = Vec::new();
+    for krate in crates_to_search(db, module)? {
+        let impls = db.impls_in_crate(krate);
+        res.extend(
+            impls
+                .lookup_impl_blocks_for_trait(db, &tr)
+                .map(|(module, imp)| NavigationTarget::from_impl_block(db, module, &imp)),
+        );
+    }
+    Some(res)
+}
+
+/// For a method declared in a trait, finds the methods implementing it; for a
+/// method in a trait impl, finds the trait method it implements.
+fn impls_for_method(
+    db: &RootDatabase,
+    position: FilePosition,
+    node: &ast::FnDef,
+    module: hir::Module,
+) -> Option<Vec<NavigationTarget>> {
+    let name = node.name()?.text().clone();
+    let container = node.syntax().parent().and_then(ast::ItemList::cast)?.syntax().parent()?;
+
+    if let Some(trait_def) = ast::TraitDef::cast(container) {
+        let tr = source_binder::trait_from_module(db, module, trait_def);
+        let mut res = Vec::new();
+        for krate in crates_to_search(db, module)? {
+            let impls = db.impls_in_crate(krate);
+            for (_, imp) in impls.lookup_impl_blocks_for_trait(db, &tr) {
+                res.extend(imp.items().iter().filter_map(|item| match item {
+                    ImplItem::Method(f) if f.name(db).to_string() == name => {
+                        Some(NavigationTarget::from_function(db, *f))
+                    }
+                    _ => None,
+                }));
+            }
+        }
+        return Some(res);
+    }
+
+    if ast::ImplBlock::cast(container).is_some() {
+        let function = source_binder::function_from_source(db, position.file_id, node)?;
+        let tr = function.impl_block(db)?.target_trait(db)?;
+        let res = tr
+            .items(db)
+            .into_iter()
+            .filter_map(|item| match item {
+                TraitItem::Function(f) if f.name(db).to_string() == name => {
+                    Some(NavigationTarget::from_function(db, f))
+                }
+                _ => None,
+            })
+            .collect();
+        return Some(res);
+    }
+
+    None
 }
 
 #[cfg(test)]
@@ -175,4 +240,59 @@ mod tests {
             &["impl IMPL_BLOCK FileId(2) [0; 31)", "impl IMPL_BLOCK FileId(3) [0; 31)"],
         );
     }
+
+    #[test]
+    fn goto_implementation_for_trait_in_dependent_crate() {
+        check_goto(
+            "
+            //- /lib.rs
+            struct S;
+            impl foo::T for S {}
+            //- /foo/lib.rs
+            pub trait T<|> {}
+            ",
+            &["impl IMPL_BLOCK FileId(1) [10; 30)"],
+        );
+    }
+
+    #[test]
+    fn goto_implementation_for_trait_method() {
+        check_goto(
+            "
+            //- /lib.rs
+            trait T { fn foo<|>(&self); }
+            struct A;
+            struct B;
+            impl T for A { fn foo(&self) {} }
+            impl T for B { fn foo(&self) {} }
+            ",
+            &["foo FN_DEF FileId(1) [62; 78) [65; 68)", "foo FN_DEF FileId(1) [96; 112) [99; 102)"],
+        );
+    }
+
+    #[test]
+    fn goto_implementation_from_impl_method_to_trait_method() {
+        check_goto(
+            "
+            //- /lib.rs
+            trait T { fn foo(&self); }
+            struct A;
+            impl T for A { fn foo<|>(&self) {} }
+            ",
+            &["foo FN_DEF FileId(1) [10; 24) [13; 16)"],
+        );
+    }
+
+    #[test]
+    fn goto_implementation_from_trait_method_body_to_trait_impls() {
+        check_goto(
+            "
+            //- /lib.rs
+            trait T { fn foo(&self) { <|> } }
+            struct A;
+            impl T for A {}
+            ",
+            &["impl IMPL_BLOCK FileId(1) [41; 56)"],
+        );
+    }
 }
@@ -0,0 +1,273 @@
+use ra_db::SourceDatabase;
+use ra_syntax::{
+    AstNode, SmolStr, TextRange,
+    ast::{self, ArgListOwner, NameOwner},
+    SyntaxKind::{WHITESPACE, METHOD_CALL_EXPR},
+};
+use hir::{Ty, InferenceResult, BodySyntaxMapping, source_binder};
+
+use crate::{FileId, db::RootDatabase};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum InlayKind {
+    /// The type of a `let` binding without an explicit type annotation.
+    LetBindingType,
+    /// The type of a closure parameter without an explicit type annotation.
+    ClosureParameterType,
+    /// The type of an intermediate method call in a multi-line chain.
+    ChainedCallType,
+    /// The name of the parameter which the argument is passed to.
+    ParameterName,
+}
+
+/// `InlayHint` is a short piece of text which an editor displays inline,
+/// next to (but not as a part of) the code.
+#[derive(Debug)]
+pub struct InlayHint {
+    pub range: TextRange,
+    pub kind: InlayKind,
+    pub label: SmolStr,
+}
+
+pub(crate) fn inlay_hints(db: &RootDatabase, file_id: FileId) -> Vec<InlayHint> {
+    let file = db.parse(file_id);
+    let mut res = Vec::new();
+    for fn_def in file.syntax().descendants().filter_map(ast::FnDef::cast) {
+        if let Some(function) = source_binder::function_from_source(db, file_id, fn_def) {
+            hints_for_function(db, function, fn_def, &mut res);
+        }
+    }
+    res.sort_by_key(|hint| hint.range.start());
+    res
+}
+
+fn hints_for_function(
+    db: &RootDatabase,
+    function: hir::Function,
+    fn_def: &ast::FnDef,
+    acc: &mut Vec<InlayHint>,
+) {
+    let body = match fn_def.body() {
+        Some(it) => it,
+        None => return,
+    };
+    let infer = function.infer(db);
+    let syntax_mapping = function.body_syntax_mapping(db);
+
+    for node in body.syntax().descendants() {
+        if let Some(let_stmt) = ast::LetStmt::cast(node) {
+            if let_stmt.type_ref().is_some() {
+                continue;
+            }
+            if let Some(pat) = let_stmt.pat() {
+                binding_hints(&infer, &syntax_mapping, pat, InlayKind::LetBindingType, acc);
+            }
+        } else if let Some(lambda) = ast::LambdaExpr::cast(node) {
+            let params = lambda.param_list().into_iter().flat_map(|it| it.params());
+            for param in params.filter(|it| it.type_ref().is_none()) {
+                if let Some(pat) = param.pat() {
+                    binding_hints(
+                        &infer,
+                        &syntax_mapping,
+                        pat,
+                        InlayKind::ClosureParameterType,
+                        acc,
+                    );
+                }
+            }
+        } else if let Some(call) = ast::MethodCallExpr::cast(node) {
+            let expr = ast::Expr::cast(call.syntax()).unwrap();
+            chained_call_hint(&infer, &syntax_mapping, call, acc);
+            if let Some(arg_list) = call.arg_list() {
+                param_name_hints(db, &infer, &syntax_mapping, expr, arg_list, acc);
+            }
+        } else if let Some(call) = ast::CallExpr::cast(node) {
+            let expr = ast::Expr::cast(call.syntax()).unwrap();
+            if let Some(arg_list) = call.arg_list() {
+                param_name_hints(db, &infer, &syntax_mapping, expr, arg_list, acc);
+            }
+        }
+    }
+}
+
+fn binding_hints(
+    infer: &InferenceResult,
+    syntax_mapping: &BodySyntaxMapping,
+    pat: &ast::Pat,
+    kind: InlayKind,
+    acc: &mut Vec<InlayHint>,
+) {
+    for bind_pat in pat.syntax().descendants().filter_map(ast::BindPat::cast) {
+        let pat = ast::Pat::cast(bind_pat.syntax()).expect("bind pat is a pat");
+        let ty = match syntax_mapping.node_pat(pat) {
+            Some(pat_id) => &infer[pat_id],
+            None => continue,
+        };
+        if let Some(label) = type_label(ty) {
+            acc.push(InlayHint { range: bind_pat.syntax().range(), kind, label });
+        }
+    }
+}
+
+/// Shows the type of `foo.bar()` in
+///
+/// ```no-run
+/// foo.bar()
+///     .baz()
+/// ```
+fn chained_call_hint(
+    infer: &InferenceResult,
+    syntax_mapping: &BodySyntaxMapping,
+    call: &ast::MethodCallExpr,
+    acc: &mut Vec<InlayHint>,
+) {
+    let is_receiver_of_call = call
+        .syntax()
+        .parent()
+        .filter(|parent| parent.kind() == METHOD_CALL_EXPR)
+        .and_then(|parent| parent.first_child())
+        .map(|receiver| receiver == call.syntax())
+        .unwrap_or(false);
+    let followed_by_newline = call
+        .syntax()
+        .next_sibling()
+        .filter(|it| it.kind() == WHITESPACE)
+        .map(|it| it.text().contains('\n'))
+        .unwrap_or(false);
+    if !is_receiver_of_call || !followed_by_newline {
+        return;
+    }
+    let expr = ast::Expr::cast(call.syntax()).unwrap();
+    let ty = match syntax_mapping.node_expr(expr) {
+        Some(expr_id) => &infer[expr_id],
+        None => return,
+    };
+    if let Some(label) = type_label(ty) {
+        acc.push(InlayHint {
+            range: call.syntax().range(),
+            kind: InlayKind::ChainedCallType,
+            label,
+        });
+    }
+}
+
+fn param_name_hints(
+    db: &RootDatabase,
+    infer: &InferenceResult,
+    syntax_mapping: &BodySyntaxMapping,
+    call: &ast::Expr,
+    arg_list: &ast::ArgList,
+    acc: &mut Vec<InlayHint>,
+) {
+    let (function, skip_self) = match resolve_callee(infer, syntax_mapping, call) {
+        Some(it) => it,
+        None => return,
+    };
+    let fn_def = function.source(db).1;
+    let param_list = match fn_def.param_list() {
+        Some(it) => it,
+        None => return,
+    };
+    let self_param = if param_list.self_param().is_some() && !skip_self {
+        Some(SmolStr::new("self"))
+    } else {
+        None
+    };
+    let param_names = self_param.into_iter().chain(param_list.params().map(|param| {
+        match param.pat().map(|pat| pat.kind()) {
+            Some(ast::PatKind::BindPat(bind_pat)) => {
+                bind_pat.name().map(|it| it.text().clone()).unwrap_or_default()
+            }
+            _ => SmolStr::default(),
+        }
+    }));
+
+    for (arg, param_name) in arg_list.args().zip(param_names) {
+        if param_name.is_empty() || param_name.starts_with('_') {
+            continue;
+        }
+        if arg.syntax().text() == param_name.as_str() {
+            continue;
+        }
+        acc.push(InlayHint {
+            range: arg.syntax().range(),
+            kind: InlayKind::ParameterName,
+            label: param_name,
+        });
+    }
+}
+
+/// Returns the called function and whether its `self` parameter is passed as
+/// a receiver (and hence does not appear in the argument list).
+fn resolve_callee(
+    infer: &InferenceResult,
+    syntax_mapping: &BodySyntaxMapping,
+    call: &ast::Expr,
+) -> Option<(hir::Function, bool)> {
+    match call.kind() {
+        ast::ExprKind::MethodCallExpr(_) => {
+            let expr_id = syntax_mapping.node_expr(call)?;
+            Some((infer.method_resolution(expr_id)?, true))
+        }
+        ast::ExprKind::CallExpr(call) => {
+            let callee = syntax_mapping.node_expr(call.expr()?)?;
+            match &infer[callee] {
+                Ty::FnDef { def, .. } => Some((*def, false)),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+fn type_label(ty: &Ty) -> Option<SmolStr> {
+    match ty {
+        Ty::Unknown => None,
+        ty => Some(ty.to_string().into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use insta::assert_debug_snapshot_matches;
+
+    use crate::mock_analysis::single_file;
+
+    #[test]
+    fn inlay_hints_for_bindings_and_arguments() {
+        let (analysis, file_id) = single_file(
+            "
+            struct Foo<T> { x: T }
+            fn foo(x: i32, y: i32) -> i32 { x + y }
+            fn main() {
+                let a = foo(1, 2);
+                let b = Foo { x: 1u8 };
+                let g = |z| foo(z, 1);
+                let _ = foo(a, 3);
+            }
+            ",
+        );
+        let hints = analysis.inlay_hints(file_id).unwrap();
+        assert_debug_snapshot_matches!("inlay_hints_for_bindings_and_arguments", &hints);
+    }
+
+    #[test]
+    fn inlay_hints_for_chained_calls() {
+        let (analysis, file_id) = single_file(
+            "
+            struct A;
+            struct B;
+            impl A { fn b(&self) -> B { B } }
+            impl B { fn a(&self) -> A { A } }
+            fn main(a: A) {
+                let b = a.b()
+                    .a()
+                    .b();
+                let c = a.b().a();
+            }
+            ",
+        );
+        let hints = analysis.inlay_hints(file_id).unwrap();
+        assert_debug_snapshot_matches!("inlay_hints_for_chained_calls", &hints);
+    }
+}
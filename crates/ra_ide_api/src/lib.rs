@@ -32,6 +32,7 @@ mod references;
 mod impls;
 mod assists;
 mod diagnostics;
+mod inlay_hints;
 
 #[cfg(test)]
 mod marks;
@@ -49,13 +50,16 @@ use relative_path::RelativePathBuf;
 use crate::{
     symbol_index::FileSymbol,
     db::LineIndexDatabase,
+    completion::CompletionDetails,
 };
 
 pub use crate::{
     change::{AnalysisChange, LibraryData},
-    completion::{CompletionItem, CompletionItemKind, InsertTextFormat},
+    completion::{CompletionItem, CompletionItemKind, CompletionScore, InsertTextFormat},
     runnables::{Runnable, RunnableKind},
     navigation_target::NavigationTarget,
+    inlay_hints::{InlayHint, InlayKind},
+    references::{HighlightedReference, ReferenceAccess},
 };
 pub use ra_ide_api_light::{
     Fold, FoldKind, HighlightedRange, Severity, StructureNode, LocalEdit,
@@ -90,7 +94,9 @@ pub struct SourceFileEdit {
 
 #[derive(Debug)]
 pub enum FileSystemEdit {
-    CreateFile { source_root: SourceRootId, path: RelativePathBuf },
+    /// Creates a file, which initially contains `text`.
+    CreateFile { source_root: SourceRootId, path: RelativePathBuf, text: String },
+    /// Moves the file to `dst_path` in `dst_source_root`.
     MoveFile { src: FileId, dst_source_root: SourceRootId, dst_path: RelativePathBuf },
 }
 
@@ -159,6 +165,7 @@ pub struct CallInfo {
     pub label: String,
     pub doc: Option<Documentation>,
     pub parameters: Vec<String>,
+    pub parameter_types: Vec<String>,
     pub active_parameter: Option<usize>,
 }
 
@@ -316,6 +323,15 @@ impl Analysis {
         self.with_db(|db| references::find_all_refs(db, position))
     }
 
+    /// Finds all occurrences of the item or local under the cursor in the
+    /// current file, classified as definitions, reads or writes.
+    pub fn highlight_references(
+        &self,
+        position: FilePosition,
+    ) -> Cancelable<Vec<HighlightedReference>> {
+        self.with_db(|db| references::highlight_references(db, position))
+    }
+
     /// Returns a short text descrbing element at position.
     pub fn hover(&self, position: FilePosition) -> Cancelable<Option<RangeInfo<String>>> {
         self.with_db(|db| hover::hover(db, position))
@@ -326,6 +342,12 @@ impl Analysis {
         self.with_db(|db| call_info::call_info(db, position))
     }
 
+    /// Returns inferred types and parameter names to display inline next to
+    /// the code of the given file.
+    pub fn inlay_hints(&self, file_id: FileId) -> Cancelable<Vec<InlayHint>> {
+        self.with_db(|db| inlay_hints::inlay_hints(db, file_id))
+    }
+
     /// Returns a `mod name;` declaration which created the current module.
     pub fn parent_module(&self, position: FilePosition) -> Cancelable<Vec<NavigationTarget>> {
         self.with_db(|db| parent_module::parent_module(db, position))
@@ -353,7 +375,35 @@ impl Analysis {
 
     /// Computes completions at the given position.
     pub fn completions(&self, position: FilePosition) -> Cancelable<Option<Vec<CompletionItem>>> {
-        self.with_db(|db| completion::completions(db, position).map(Into::into))
+        self.with_db(|db| {
+            completion::completions(db, position, CompletionDetails::All).map(Into::into)
+        })
+    }
+
+    /// Computes completions at the given position without their docs and
+    /// details, which `resolve_completion` computes for a single item.
+    pub fn completions_without_details(
+        &self,
+        position: FilePosition,
+    ) -> Cancelable<Option<Vec<CompletionItem>>> {
+        self.with_db(|db| {
+            completion::completions(db, position, CompletionDetails::None).map(Into::into)
+        })
+    }
+
+    /// Computes the `index`th completion at the given position with its docs
+    /// and details, provided it is still labeled `label`.
+    pub fn resolve_completion(
+        &self,
+        position: FilePosition,
+        index: usize,
+        label: &str,
+    ) -> Cancelable<Option<CompletionItem>> {
+        self.with_db(|db| {
+            let details = CompletionDetails::Label(label.to_string());
+            let items: Vec<CompletionItem> = completion::completions(db, position, details)?.into();
+            items.into_iter().nth(index).filter(|it| it.label() == label)
+        })
     }
 
     /// Computes assists (aks code actons aka intentions) for the given
@@ -1,6 +1,7 @@
 use ra_db::FileId;
 use ra_syntax::{
-    SyntaxNode, SyntaxNodePtr, AstNode, SmolStr, TextRange, ast,
+    SyntaxNode, SyntaxNodePtr, AstNode, SmolStr, TextRange,
+    ast::{self, AstToken},
     SyntaxKind::{self, NAME},
 };
 use hir::{ModuleSource, FieldSource, Name};
@@ -152,6 +153,17 @@ impl NavigationTarget {
         NavigationTarget::from_syntax(file_id, "impl".into(), None, node.syntax())
     }
 
+    /// Navigates to the declaration of a lifetime or a label, `node` being a
+    /// `LIFETIME_PARAM` or a `LABEL`.
+    pub(crate) fn from_lifetime(
+        file_id: FileId,
+        node: &SyntaxNode,
+        lifetime: &ast::Lifetime,
+    ) -> NavigationTarget {
+        let name = lifetime.text().clone();
+        NavigationTarget::from_syntax(file_id, name, Some(lifetime.syntax().range()), node)
+    }
+
     #[cfg(test)]
     pub(crate) fn assert_match(&self, expected: &str) {
         let actual = self.debug_render();
@@ -13,8 +13,27 @@ use crate::{
     FileSystemEdit,
     SourceChange,
     SourceFileEdit,
+    NavigationTarget,
+    goto_definition::{reference_definition, ReferenceResult},
 };
 
+/// How a highlighted occurrence of a name uses the thing it refers to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReferenceAccess {
+    /// The name is being defined.
+    Definition,
+    /// The value is read, or the name is used outside of an expression.
+    Read,
+    /// The value is assigned to or borrowed mutably.
+    Write,
+}
+
+#[derive(Debug)]
+pub struct HighlightedReference {
+    pub range: TextRange,
+    pub access: ReferenceAccess,
+}
+
 pub(crate) fn find_all_refs(db: &RootDatabase, position: FilePosition) -> Vec<(FileId, TextRange)> {
     let file = db.parse(position.file_id);
     // Find the binding associated with the offset
@@ -60,6 +79,95 @@ pub(crate) fn find_all_refs(db: &RootDatabase, position: FilePosition) -> Vec<(F
     }
 }
 
+/// Finds all occurrences in the current file of the item or local binding
+/// under the cursor, and classifies each of them.
+pub(crate) fn highlight_references(
+    db: &RootDatabase,
+    position: FilePosition,
+) -> Vec<HighlightedReference> {
+    let file = db.parse(position.file_id);
+    let syntax = file.syntax();
+    let (text, target) =
+        if let Some(name_ref) = find_node_at_offset::<ast::NameRef>(syntax, position.offset) {
+            match reference_definition(db, position.file_id, name_ref) {
+                ReferenceResult::Exact(nav) => (name_ref.text().clone(), nav_key(&nav)),
+                ReferenceResult::Approximate(_) => return Vec::new(),
+            }
+        } else if let Some(name) = find_node_at_offset::<ast::Name>(syntax, position.offset) {
+            (name.text().clone(), name_key(position.file_id, name))
+        } else {
+            return Vec::new();
+        };
+
+    let mut res = Vec::new();
+    for name in syntax.descendants().filter_map(ast::Name::cast) {
+        if *name.text() == text && name_key(position.file_id, name) == target {
+            res.push(HighlightedReference {
+                range: name.syntax().range(),
+                access: ReferenceAccess::Definition,
+            });
+        }
+    }
+    for name_ref in syntax.descendants().filter_map(ast::NameRef::cast) {
+        if *name_ref.text() != text {
+            continue;
+        }
+        match reference_definition(db, position.file_id, name_ref) {
+            ReferenceResult::Exact(nav) if nav_key(&nav) == target => {}
+            _ => continue,
+        }
+        res.push(HighlightedReference {
+            range: name_ref.syntax().range(),
+            access: reference_access(db, position.file_id, name_ref),
+        });
+    }
+    res.sort_by_key(|it| it.range.start());
+    res
+}
+
+/// Identifies a definition by the range of its name, or of the whole binding
+/// pattern for locals, to match what `NavigationTarget`s point to.
+fn name_key(file_id: FileId, name: &ast::Name) -> (FileId, TextRange) {
+    match name.syntax().parent().and_then(ast::BindPat::cast) {
+        Some(bind_pat) => (file_id, bind_pat.syntax().range()),
+        None => (file_id, name.syntax().range()),
+    }
+}
+
+fn nav_key(nav: &NavigationTarget) -> (FileId, TextRange) {
+    (nav.file_id(), nav.focus_range().unwrap_or_else(|| nav.full_range()))
+}
+
+fn reference_access(
+    db: &RootDatabase,
+    file_id: FileId,
+    name_ref: &ast::NameRef,
+) -> ReferenceAccess {
+    let parent = name_ref.syntax().parent();
+    let expr =
+        parent.and_then(ast::FieldExpr::cast).map(|it| ast::Expr::cast(it.syntax()).unwrap());
+    let expr = expr.or_else(|| {
+        let path = parent.and_then(ast::PathSegment::cast)?.syntax().parent()?;
+        let path_expr = ast::PathExpr::cast(path.parent()?)?;
+        Some(ast::Expr::cast(path_expr.syntax()).unwrap())
+    });
+    let expr = match expr {
+        Some(it) => it,
+        None => return ReferenceAccess::Read,
+    };
+    let function = match source_binder::function_from_child_node(db, file_id, expr.syntax()) {
+        Some(it) => it,
+        None => return ReferenceAccess::Read,
+    };
+    let syntax_mapping = function.body_syntax_mapping(db);
+    match syntax_mapping.node_expr(expr) {
+        Some(expr_id) if syntax_mapping.body().written_places().contains(&expr_id) => {
+            ReferenceAccess::Write
+        }
+        _ => ReferenceAccess::Read,
+    }
+}
+
 pub(crate) fn rename(
     db: &RootDatabase,
     position: FilePosition,
@@ -1,5 +1,8 @@
-use ra_syntax::{ast, AstNode,};
+use rustc_hash::FxHashMap;
+
+use ra_syntax::{ast, AstNode, SourceFile, TextRange};
 use ra_db::SourceDatabase;
+use hir::{ModuleDef, Resolution, source_binder};
 
 use crate::{
     FileId, HighlightedRange,
@@ -9,6 +12,12 @@ use crate::{
 pub(crate) fn highlight(db: &RootDatabase, file_id: FileId) -> Vec<HighlightedRange> {
     let source_file = db.parse(file_id);
     let mut res = ra_ide_api_light::highlight(source_file.syntax());
+    let semantic_tags = semantic_tags(db, file_id, &source_file);
+    for range in res.iter_mut() {
+        if let Some(&tag) = semantic_tags.get(&range.range) {
+            range.tag = tag;
+        }
+    }
     for macro_call in source_file.syntax().descendants().filter_map(ast::MacroCall::cast) {
         if let Some((off, exp)) = hir::MacroDef::ast_expand(macro_call) {
             let mapped_ranges =
@@ -23,6 +32,153 @@ pub(crate) fn highlight(db: &RootDatabase, file_id: FileId) -> Vec<HighlightedRa
     res
 }
 
+/// Computes tags for names and name references, based on what they resolve
+/// to. These refine the purely syntactic tags.
+fn semantic_tags(
+    db: &RootDatabase,
+    file_id: FileId,
+    source_file: &SourceFile,
+) -> FxHashMap<TextRange, &'static str> {
+    let mut res = FxHashMap::default();
+    for node in source_file.syntax().descendants() {
+        let tag = if let Some(name) = ast::Name::cast(node) {
+            name_tag(name)
+        } else if let Some(name_ref) = ast::NameRef::cast(node) {
+            name_ref_tag(db, file_id, source_file, name_ref)
+        } else {
+            None
+        };
+        if let Some(tag) = tag {
+            res.insert(node.range(), tag);
+        }
+    }
+    res
+}
+
+fn name_tag(name: &ast::Name) -> Option<&'static str> {
+    let parent = name.syntax().parent()?;
+    if let Some(bind_pat) = ast::BindPat::cast(parent) {
+        // Closure parameters are highlighted as locals.
+        let is_param = bind_pat
+            .syntax()
+            .ancestors()
+            .find_map(ast::Param::cast)
+            .and_then(|param| param.syntax().parent()?.parent())
+            .map_or(false, |it| ast::FnDef::cast(it).is_some());
+        return Some(binding_tag(is_param, bind_pat.is_mutable()));
+    }
+    let tag = if let Some(fn_def) = ast::FnDef::cast(parent) {
+        let has_self_param = fn_def.param_list().and_then(|it| it.self_param()).is_some();
+        if has_self_param {
+            "method"
+        } else {
+            "function"
+        }
+    } else if ast::StructDef::cast(parent).is_some() {
+        "struct"
+    } else if ast::EnumDef::cast(parent).is_some() {
+        "enum"
+    } else if ast::EnumVariant::cast(parent).is_some() {
+        "variant"
+    } else if ast::TraitDef::cast(parent).is_some() {
+        "trait"
+    } else if ast::TypeDef::cast(parent).is_some() {
+        "type_alias"
+    } else if ast::TypeParam::cast(parent).is_some() {
+        "type_param"
+    } else if ast::NamedFieldDef::cast(parent).is_some() {
+        "field"
+    } else if ast::ConstDef::cast(parent).is_some() {
+        "constant"
+    } else if ast::StaticDef::cast(parent).is_some() {
+        "static"
+    } else if ast::Module::cast(parent).is_some() {
+        "module"
+    } else {
+        return None;
+    };
+    Some(tag)
+}
+
+fn name_ref_tag(
+    db: &RootDatabase,
+    file_id: FileId,
+    source_file: &SourceFile,
+    name_ref: &ast::NameRef,
+) -> Option<&'static str> {
+    let parent = name_ref.syntax().parent()?;
+    if let Some(method_call) = ast::MethodCallExpr::cast(parent) {
+        let function = source_binder::function_from_child_node(db, file_id, parent)?;
+        let expr = ast::Expr::cast(method_call.syntax()).unwrap();
+        let expr_id = function.body_syntax_mapping(db).node_expr(expr)?;
+        function.infer(db).method_resolution(expr_id)?;
+        return Some("method");
+    }
+    if let Some(field_expr) = ast::FieldExpr::cast(parent) {
+        let function = source_binder::function_from_child_node(db, file_id, parent)?;
+        let expr = ast::Expr::cast(field_expr.syntax()).unwrap();
+        let expr_id = function.body_syntax_mapping(db).node_expr(expr)?;
+        function.infer(db).field_resolution(expr_id)?;
+        return Some("field");
+    }
+    if ast::NamedField::cast(parent).is_some() {
+        return Some("field");
+    }
+
+    let path = name_ref.syntax().ancestors().find_map(ast::Path::cast)?;
+    let path_parent = path.syntax().parent()?;
+    // Macros are highlighted syntactically, and imports are not resolved
+    // relative to the scope they are in.
+    if ast::MacroCall::cast(path_parent).is_some()
+        || path.syntax().ancestors().any(|it| ast::UseItem::cast(it).is_some())
+    {
+        return None;
+    }
+    let hir_path = hir::Path::from_ast(path)?;
+    let resolver = source_binder::resolver_for_node(db, file_id, name_ref.syntax());
+    let resolved = resolver.resolve_path(db, &hir_path);
+    let tag = match resolved.clone().take_types().or_else(|| resolved.take_values()) {
+        Some(Resolution::Def(def)) => match def {
+            ModuleDef::Module(_) => "module",
+            ModuleDef::Function(f) => {
+                if f.signature(db).has_self_param() {
+                    "method"
+                } else {
+                    "function"
+                }
+            }
+            ModuleDef::Struct(_) => "struct",
+            ModuleDef::Enum(_) => "enum",
+            ModuleDef::EnumVariant(_) => "variant",
+            ModuleDef::Const(_) => "constant",
+            ModuleDef::Static(_) => "static",
+            ModuleDef::Trait(_) => "trait",
+            ModuleDef::Type(_) => "type_alias",
+        },
+        Some(Resolution::LocalBinding(pat)) => {
+            let body = resolver.body()?;
+            let is_param = body.params().contains(&pat);
+            let ptr = body.syntax_mapping(db).pat_syntax(pat)?;
+            let is_mutable =
+                ast::BindPat::cast(ptr.to_node(source_file)).map_or(false, |it| it.is_mutable());
+            binding_tag(is_param, is_mutable)
+        }
+        Some(Resolution::GenericParam(_)) => "type_param",
+        Some(Resolution::SelfType(_)) => "type",
+        None if hir_path.as_ident().map_or(false, |it| it.is_primitive_type()) => "builtin",
+        None => "unresolved",
+    };
+    Some(tag)
+}
+
+fn binding_tag(is_param: bool, is_mutable: bool) -> &'static str {
+    match (is_param, is_mutable) {
+        (true, _) => "parameter",
+        (false, true) => "variable.mut",
+        (false, false) => "variable",
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::mock_analysis::single_file;
@@ -41,4 +197,53 @@ mod tests {
         let highlights = analysis.highlight(file_id).unwrap();
         assert_debug_snapshot_matches!("highlights_code_inside_macros", &highlights);
     }
+
+    #[test]
+    fn highlights_names_by_resolution() {
+        let code = "
+struct Foo { x: i32 }
+fn foo(p: Foo) -> i32 {
+    let mut a = p.x;
+    let b = a;
+    a = unknown;
+    b + bar(b)
+}
+fn bar(x: i32) -> i32 { x }
+";
+        let (analysis, file_id) = single_file(code);
+        let highlights = analysis
+            .highlight(file_id)
+            .unwrap()
+            .into_iter()
+            .filter(|it| it.tag != "keyword" && it.tag != "literal")
+            .map(|it| (&code[it.range.start().to_usize()..it.range.end().to_usize()], it.tag))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            highlights,
+            vec![
+                ("Foo", "struct"),
+                ("x", "field"),
+                ("i32", "builtin"),
+                ("foo", "function"),
+                ("p", "parameter"),
+                ("Foo", "struct"),
+                ("i32", "builtin"),
+                ("a", "variable.mut"),
+                ("p", "parameter"),
+                ("x", "field"),
+                ("b", "variable"),
+                ("a", "variable.mut"),
+                ("a", "variable.mut"),
+                ("unknown", "unresolved"),
+                ("b", "variable"),
+                ("bar", "function"),
+                ("b", "variable"),
+                ("bar", "function"),
+                ("x", "parameter"),
+                ("i32", "builtin"),
+                ("i32", "builtin"),
+                ("x", "parameter"),
+            ]
+        );
+    }
 }
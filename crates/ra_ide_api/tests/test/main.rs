@@ -1,7 +1,7 @@
 use insta::assert_debug_snapshot_matches;
 use ra_ide_api::{
     mock_analysis::{single_file, single_file_with_position, MockAnalysis},
-    AnalysisChange, CrateGraph, FileId, Query,
+    AnalysisChange, CrateGraph, FileId, Query, ReferenceAccess,
 };
 use ra_syntax::TextRange;
 
@@ -90,6 +90,66 @@ fn test_find_all_refs_for_fn_param() {
     assert_eq!(refs.len(), 2);
 }
 
+fn get_highlights(text: &str) -> Vec<ReferenceAccess> {
+    let (analysis, position) = single_file_with_position(text);
+    analysis.highlight_references(position).unwrap().into_iter().map(|it| it.access).collect()
+}
+
+#[test]
+fn test_highlight_references_for_local() {
+    let code = r#"
+    fn main() {
+        let mut i<|> = 1;
+        let r = &mut i;
+        i += 1;
+        foo(i);
+    }"#;
+
+    let highlights = get_highlights(code);
+    assert_eq!(
+        highlights,
+        vec![
+            ReferenceAccess::Definition,
+            ReferenceAccess::Write,
+            ReferenceAccess::Write,
+            ReferenceAccess::Read
+        ]
+    );
+}
+
+#[test]
+fn test_highlight_references_for_field() {
+    let code = r#"
+    struct S { x<|>: i32 }
+    fn main(s: S) {
+        s.x = 1;
+        let y = s.x;
+    }"#;
+
+    let highlights = get_highlights(code);
+    assert_eq!(
+        highlights,
+        vec![ReferenceAccess::Definition, ReferenceAccess::Write, ReferenceAccess::Read]
+    );
+}
+
+#[test]
+fn test_highlight_references_for_item() {
+    let code = r#"
+    struct Foo;
+    fn foo() -> Foo {}
+    fn main() {
+        let x: Foo = foo<|>();
+        foo();
+    }"#;
+
+    let highlights = get_highlights(code);
+    assert_eq!(
+        highlights,
+        vec![ReferenceAccess::Definition, ReferenceAccess::Read, ReferenceAccess::Read]
+    );
+}
+
 #[test]
 #[ignore]
 fn world_symbols_include_stuff_from_macros() {
@@ -5,8 +5,8 @@ use lsp_types::{
     WorkspaceEdit,
 };
 use ra_ide_api::{
-    CompletionItem, CompletionItemKind, FileId, FilePosition, FileRange, FileSystemEdit,
-    NavigationTarget, SourceChange, SourceFileEdit, RangeInfo,
+    CompletionItem, CompletionItemKind, CompletionScore, FileId, FilePosition, FileRange,
+    FileSystemEdit, NavigationTarget, SourceChange, SourceFileEdit, RangeInfo, ReferenceAccess,
     LineCol, LineIndex, translate_offset_with_edit, InsertTextFormat
 };
 use ra_syntax::{SyntaxKind, TextRange, TextUnit};
@@ -71,6 +71,23 @@ impl Conv for CompletionItemKind {
             CompletionItemKind::Static => Value,
             CompletionItemKind::Method => Method,
             CompletionItemKind::TypeParam => TypeParameter,
+            CompletionItemKind::Attribute => Property,
+            CompletionItemKind::Macro => Method,
+            CompletionItemKind::Lifetime => TypeParameter,
+            CompletionItemKind::Label => Reference,
+        }
+    }
+}
+
+impl Conv for ReferenceAccess {
+    type Output = ::lsp_types::DocumentHighlightKind;
+
+    fn conv(self) -> <Self as Conv>::Output {
+        use lsp_types::DocumentHighlightKind::*;
+        match self {
+            ReferenceAccess::Definition => Text,
+            ReferenceAccess::Read => Read,
+            ReferenceAccess::Write => Write,
         }
     }
 }
@@ -99,6 +116,15 @@ impl ConvWith for CompletionItem {
             InsertTextFormat::Snippet => lsp_types::InsertTextFormat::Snippet,
             InsertTextFormat::PlainText => lsp_types::InsertTextFormat::PlainText,
         });
+        if let Some(score) = self.score() {
+            // Clients sort by label when there is no `sortText`, and digits
+            // come before any identifier, so scored items go first.
+            let rank = match score {
+                CompletionScore::TypeAndNameMatch => 0,
+                CompletionScore::TypeMatch => 1,
+            };
+            res.sort_text = Some(format!("{}{}", rank, self.label()));
+        }
 
         res
     }
@@ -281,8 +307,25 @@ impl TryConvWith for SourceChange {
             }
         };
         let mut document_changes: Vec<DocumentChangeOperation> = Vec::new();
-        for resource_op in self.file_system_edits.try_conv_with(world)? {
+        for file_system_edit in self.file_system_edits {
+            // Created files are filled by an edit following the creation.
+            let initial_edit = match &file_system_edit {
+                FileSystemEdit::CreateFile { source_root, path, text } if !text.is_empty() => {
+                    let text_document = VersionedTextDocumentIdentifier {
+                        uri: world.path_to_uri(*source_root, path)?,
+                        version: None,
+                    };
+                    let start = Position::new(0, 0);
+                    let edit = lsp_types::TextEdit::new(Range::new(start, start), text.clone());
+                    Some(TextDocumentEdit { text_document, edits: vec![edit] })
+                }
+                _ => None,
+            };
+            let resource_op = file_system_edit.try_conv_with(world)?;
             document_changes.push(DocumentChangeOperation::Op(resource_op));
+            if let Some(edit) = initial_edit {
+                document_changes.push(DocumentChangeOperation::Edit(edit));
+            }
         }
         for text_document_edit in self.source_file_edits.try_conv_with(world)? {
             document_changes.push(DocumentChangeOperation::Edit(text_document_edit));
@@ -314,7 +357,7 @@ impl TryConvWith for FileSystemEdit {
     type Output = ResourceOp;
     fn try_conv_with(self, world: &ServerWorld) -> Result<ResourceOp> {
         let res = match self {
-            FileSystemEdit::CreateFile { source_root, path } => {
+            FileSystemEdit::CreateFile { source_root, path, .. } => {
                 let uri = world.path_to_uri(source_root, &path)?;
                 ResourceOp::Create(CreateFile { uri, options: None })
             }
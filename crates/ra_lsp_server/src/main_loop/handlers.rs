@@ -1,14 +1,15 @@
 use gen_lsp_server::ErrorCode;
 use lsp_types::{
-    CodeActionResponse, CodeLens, Command, Diagnostic, DiagnosticSeverity,
-    DocumentFormattingParams, DocumentHighlight, DocumentSymbol, FoldingRange,
+    CodeActionResponse, CodeLens, Command, CompletionItem, Diagnostic, DiagnosticSeverity,
+    Documentation, DocumentFormattingParams, DocumentHighlight, DocumentSymbol, FoldingRange,
     FoldingRangeKind, FoldingRangeParams, Hover, HoverContents, Location, MarkupContent,
     MarkupKind, ParameterInformation, ParameterLabel, Position, PrepareRenameResponse, Range,
     RenameParams, SignatureInformation, SymbolInformation, TextDocumentIdentifier, TextEdit,
     WorkspaceEdit,
 };
 use ra_ide_api::{
-    FileId, FilePosition, FileRange, FoldKind, Query, RangeInfo, RunnableKind, Severity, Cancelable,
+    FileId, FilePosition, FileRange, FoldKind, InlayKind, Query, RangeInfo, RunnableKind, Severity,
+    Cancelable,
 };
 use ra_syntax::{AstNode, SyntaxKind, TextUnit};
 use rustc_hash::FxHashMap;
@@ -318,6 +319,30 @@ pub fn handle_decorations(
     highlight(&world, file_id)
 }
 
+pub fn handle_inlay_hints(
+    world: ServerWorld,
+    params: req::InlayHintsParams,
+) -> Result<Vec<req::InlayHint>> {
+    let file_id = params.text_document.try_conv_with(&world)?;
+    let line_index = world.analysis().file_line_index(file_id);
+    let res = world
+        .analysis()
+        .inlay_hints(file_id)?
+        .into_iter()
+        .map(|hint| req::InlayHint {
+            range: hint.range.conv_with(&line_index),
+            kind: match hint.kind {
+                InlayKind::LetBindingType => req::InlayKind::LetBindingType,
+                InlayKind::ClosureParameterType => req::InlayKind::ClosureParameterType,
+                InlayKind::ChainedCallType => req::InlayKind::ChainedCallType,
+                InlayKind::ParameterName => req::InlayKind::ParameterName,
+            },
+            label: hint.label.to_string(),
+        })
+        .collect();
+    Ok(res)
+}
+
 pub fn handle_completion(
     world: ServerWorld,
     params: req::CompletionParams,
@@ -350,16 +375,61 @@ pub fn handle_completion(
         return Ok(None);
     }
 
-    let items = match world.analysis().completions(position)? {
+    // The docs and the details are filled in by `handle_completion_resolve`,
+    // when the item is actually selected.
+    let items = match world.analysis().completions_without_details(position)? {
         None => return Ok(None),
         Some(items) => items,
     };
+    // Only the first of the best-ranked items is preselected, as the client
+    // can select a single one.
+    let top_score = items.iter().filter_map(|it| it.score()).max();
+    let preselected = items.iter().position(|it| top_score.is_some() && it.score() == top_score);
     let line_index = world.analysis().file_line_index(position.file_id);
-    let items = items.into_iter().map(|item| item.conv_with(&line_index)).collect();
+    let resolve_position = req::TextDocumentPositionParams {
+        text_document: params.text_document,
+        position: params.position,
+    };
+    let items = items
+        .into_iter()
+        .enumerate()
+        .map(|(index, item)| {
+            let mut item = item.conv_with(&line_index);
+            if preselected == Some(index) {
+                item.preselect = Some(true);
+            }
+            let position = resolve_position.clone();
+            item.data = Some(to_value(req::CompletionResolveData { position, index }).unwrap());
+            item
+        })
+        .collect();
 
     Ok(Some(req::CompletionResponse::Array(items)))
 }
 
+pub fn handle_completion_resolve(
+    world: ServerWorld,
+    mut item: CompletionItem,
+) -> Result<CompletionItem> {
+    let data: req::CompletionResolveData = match item.data.take() {
+        Some(data) => serde_json::from_value(data)?,
+        None => return Ok(item),
+    };
+    let position = (&data.position).try_conv_with(&world)?;
+    // The file may have changed since the list was computed, in which case
+    // the item can't be identified anymore.
+    let resolved = match world.analysis().resolve_completion(position, data.index, &item.label)? {
+        None => return Ok(item),
+        Some(it) => it,
+    };
+    let line_index = world.analysis().file_line_index(position.file_id);
+    let resolved = resolved.conv_with(&line_index);
+    item.detail = resolved.detail;
+    item.documentation = resolved.documentation;
+    item.additional_text_edits = resolved.additional_text_edits;
+    Ok(item)
+}
+
 pub fn handle_folding_range(
     world: ServerWorld,
     params: FoldingRangeParams,
@@ -400,12 +470,13 @@ pub fn handle_signature_help(
 ) -> Result<Option<req::SignatureHelp>> {
     let position = params.try_conv_with(&world)?;
     if let Some(call_info) = world.analysis().call_info(position)? {
+        let mut parameter_types = call_info.parameter_types.into_iter();
         let parameters: Vec<ParameterInformation> = call_info
             .parameters
             .into_iter()
             .map(|param| ParameterInformation {
                 label: ParameterLabel::Simple(param.clone()),
-                documentation: None,
+                documentation: parameter_types.next().map(Documentation::String),
             })
             .collect();
 
@@ -712,11 +783,14 @@ pub fn handle_document_highlight(
     let file_id = params.text_document.try_conv_with(&world)?;
     let line_index = world.analysis().file_line_index(file_id);
 
-    let refs = world.analysis().find_all_refs(params.try_conv_with(&world)?)?;
+    let refs = world.analysis().highlight_references(params.try_conv_with(&world)?)?;
 
     Ok(Some(
         refs.into_iter()
-            .map(|r| DocumentHighlight { range: r.1.conv_with(&line_index), kind: None })
+            .map(|r| DocumentHighlight {
+                range: r.range.conv_with(&line_index),
+                kind: Some(r.access.conv()),
+            })
             .collect(),
     ))
 }
@@ -11,6 +11,16 @@ pub use lsp_types::{
     TextDocumentPositionParams, TextEdit, WorkspaceEdit, WorkspaceSymbolParams
 };
 
+/// The `data` of the items of `CompletionResponse`, identifying them for
+/// `ResolveCompletionItem`.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CompletionResolveData {
+    pub position: TextDocumentPositionParams,
+    /// The index of the item among the completions at `position`.
+    pub index: usize,
+}
+
 pub enum AnalyzerStatus {}
 
 impl Request for AnalyzerStatus {
@@ -171,6 +181,35 @@ pub struct SourceChange {
     pub cursor_position: Option<TextDocumentPositionParams>,
 }
 
+pub enum InlayHints {}
+
+impl Request for InlayHints {
+    type Params = InlayHintsParams;
+    type Result = Vec<InlayHint>;
+    const METHOD: &'static str = "rust-analyzer/inlayHints";
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct InlayHintsParams {
+    pub text_document: TextDocumentIdentifier,
+}
+
+#[derive(Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum InlayKind {
+    LetBindingType,
+    ClosureParameterType,
+    ChainedCallType,
+    ParameterName,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct InlayHint {
+    pub range: Range,
+    pub kind: InlayKind,
+    pub label: String,
+}
+
 pub enum InternalFeedback {}
 
 impl Notification for InternalFeedback {
@@ -6,10 +6,11 @@ use std::{
 };
 
 use lsp_types::{
-    CodeActionContext, DocumentFormattingParams, FormattingOptions, Position, Range,
+    CodeActionContext, CompletionItem, DocumentFormattingParams, FormattingOptions, Position, Range,
 };
 use ra_lsp_server::req::{
     CodeActionParams, CodeActionRequest, Formatting, Runnables, RunnablesParams, CompletionParams, Completion,
+    ResolveCompletionItem,
 };
 use serde_json::json;
 
@@ -43,6 +44,34 @@ use std::collections::Spam;
     eprintln!("completion took {:?}", completion_start.elapsed());
 }
 
+#[test]
+fn resolves_completion_documentation_lazily() {
+    let server = project(
+        r#"
+//- Cargo.toml
+[package]
+name = "foo"
+version = "0.0.0"
+
+//- src/lib.rs
+/// Frobnicates the widget.
+pub fn frobnicate() {}
+fn main() { frob }
+"#,
+    );
+    server.wait_for_feedback("workspace loaded");
+    let res = server.send_request::<Completion>(CompletionParams {
+        text_document: server.doc_id("src/lib.rs"),
+        context: None,
+        position: Position::new(2, 16),
+    });
+    let items: Vec<CompletionItem> = serde_json::from_value(res).unwrap();
+    let item = items.into_iter().find(|it| it.label == "frobnicate").unwrap();
+    assert!(item.documentation.is_none());
+    let res = server.send_request::<ResolveCompletionItem>(item);
+    assert!(format!("{}", res).contains("Frobnicates the widget."));
+}
+
 #[test]
 fn test_runnables_no_project() {
     let server = project(
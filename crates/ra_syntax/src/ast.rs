@@ -577,6 +577,26 @@ pub enum BinOp {
     BitXorAssign,
 }
 
+impl BinOp {
+    /// Checks whether this is `=` or one of the compound assignment operators.
+    pub fn is_assignment(self) -> bool {
+        match self {
+            BinOp::Assignment
+            | BinOp::AddAssign
+            | BinOp::DivAssign
+            | BinOp::MulAssign
+            | BinOp::RemAssign
+            | BinOp::ShrAssign
+            | BinOp::ShlAssign
+            | BinOp::SubAssign
+            | BinOp::BitOrAssign
+            | BinOp::BitAndAssign
+            | BinOp::BitXorAssign => true,
+            _ => false,
+        }
+    }
+}
+
 impl BinExpr {
     pub fn op(&self) -> Option<BinOp> {
         self.syntax()
@@ -374,6 +374,10 @@ impl BreakExpr {
     pub fn expr(&self) -> Option<&Expr> {
         super::child_opt(self)
     }
+
+    pub fn lifetime(&self) -> Option<&Lifetime> {
+        super::child_opt(self)
+    }
 }
 
 // Byte
@@ -660,7 +664,11 @@ impl ToOwned for ContinueExpr {
 }
 
 
-impl ContinueExpr {}
+impl ContinueExpr {
+    pub fn lifetime(&self) -> Option<&Lifetime> {
+        super::child_opt(self)
+    }
+}
 
 // DynTraitType
 #[derive(Debug, PartialEq, Eq, Hash)]
@@ -971,6 +979,7 @@ impl ToOwned for ExternCrateItem {
 }
 
 
+impl ast::AttrsOwner for ExternCrateItem {}
 impl ExternCrateItem {
     pub fn name_ref(&self) -> Option<&NameRef> {
         super::child_opt(self)
@@ -1253,6 +1262,10 @@ impl ToOwned for ForExpr {
 
 impl ast::LoopBodyOwner for ForExpr {}
 impl ForExpr {
+    pub fn label(&self) -> Option<&Label> {
+        super::child_opt(self)
+    }
+
     pub fn pat(&self) -> Option<&Pat> {
         super::child_opt(self)
     }
@@ -1550,7 +1563,11 @@ impl ToOwned for Label {
 }
 
 
-impl Label {}
+impl Label {
+    pub fn lifetime(&self) -> Option<&Lifetime> {
+        super::child_opt(self)
+    }
+}
 
 // LambdaExpr
 #[derive(Debug, PartialEq, Eq, Hash)]
@@ -1880,7 +1897,11 @@ impl ToOwned for LoopExpr {
 
 
 impl ast::LoopBodyOwner for LoopExpr {}
-impl LoopExpr {}
+impl LoopExpr {
+    pub fn label(&self) -> Option<&Label> {
+        super::child_opt(self)
+    }
+}
 
 // MacroCall
 #[derive(Debug, PartialEq, Eq, Hash)]
@@ -1909,6 +1930,7 @@ impl ToOwned for MacroCall {
 
 
 impl ast::NameOwner for MacroCall {}
+impl ast::AttrsOwner for MacroCall {}
 impl MacroCall {
     pub fn token_tree(&self) -> Option<&TokenTree> {
         super::child_opt(self)
@@ -3738,7 +3760,11 @@ impl ast::NameOwner for TraitDef {}
 impl ast::AttrsOwner for TraitDef {}
 impl ast::DocCommentsOwner for TraitDef {}
 impl ast::TypeParamsOwner for TraitDef {}
-impl TraitDef {}
+impl TraitDef {
+    pub fn item_list(&self) -> Option<&ItemList> {
+        super::child_opt(self)
+    }
+}
 
 // TrueKw
 #[derive(Debug, PartialEq, Eq, Hash)]
@@ -4210,6 +4236,7 @@ impl ToOwned for UseItem {
 }
 
 
+impl ast::VisibilityOwner for UseItem {}
 impl UseItem {
     pub fn use_tree(&self) -> Option<&UseTree> {
         super::child_opt(self)
@@ -4372,6 +4399,10 @@ impl ToOwned for WhileExpr {
 
 impl ast::LoopBodyOwner for WhileExpr {}
 impl WhileExpr {
+    pub fn label(&self) -> Option<&Label> {
+        super::child_opt(self)
+    }
+
     pub fn condition(&self) -> Option<&Condition> {
         super::child_opt(self)
     }
@@ -29,7 +29,22 @@ export class Highlighter {
             ['text', decor('#DCDCCC')],
             ['attribute', decor('#BFEBBF')],
             ['literal', decor('#DFAF8F')],
-            ['macro', decor('#DFAF8F')]
+            ['macro', decor('#DFAF8F')],
+            ['variable', decor('#DCDCCC')],
+            ['variable.mut', decor('#F0DFAF')],
+            ['field', decor('#DCDCCC')],
+            ['method', decor('#93E0E3')],
+            ['module', decor('#DCDCCC')],
+            ['struct', decor('#7CB8BB')],
+            ['enum', decor('#7CB8BB')],
+            ['variant', decor('#7CB8BB')],
+            ['trait', decor('#7CB8BB')],
+            ['type', decor('#7CB8BB')],
+            ['type_alias', decor('#7CB8BB')],
+            ['type_param', decor('#7CB8BB')],
+            ['constant', decor('#DFAF8F')],
+            ['static', decor('#DFAF8F')],
+            ['unresolved', decor('#CC9393')]
         ];
 
         return new Map<string, vscode.TextEditorDecorationType>(decorations);